@@ -103,7 +103,7 @@ fn handle_prerequisites_error(error: &Error) {
         Error::DockerNotFound => {
             println!("   💡 Install Docker from: https://docs.docker.com/get-docker/");
         }
-        Error::DaemonNotRunning => {
+        Error::DaemonNotRunning { .. } => {
             println!("   💡 Start Docker daemon with: sudo systemctl start docker");
             println!("   💡 Or start Docker Desktop application");
         }
@@ -4,7 +4,7 @@
 
 use docker_wrapper::command::DockerCommand;
 use docker_wrapper::{
-    BackoffStrategy, DebugConfig, DebugExecutor, DryRunPreview, PsCommand, PullCommand,
+    BackoffStrategy, DebugConfig, DebugExecutor, DryRunPreview, ErrorKind, PsCommand, PullCommand,
     RetryPolicy, RunCommand,
 };
 use std::time::Duration;
@@ -71,6 +71,7 @@ async fn main() {
             max: Duration::from_secs(5),
             multiplier: 2.0,
         })
+        .retry_on([ErrorKind::DaemonNotRunning, ErrorKind::Timeout])
         .on_retry(|attempt, error| {
             eprintln!("  Attempt {} failed: {}", attempt, error);
         });
@@ -0,0 +1,533 @@
+//! Running many commands with bounded parallelism.
+//!
+//! A [`Batch`] collects commands of any type, runs at most
+//! [`concurrency`](Batch::concurrency) of them at a time and reports each
+//! command's typed result along with how long it took.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use docker_wrapper::{Batch, PullCommand, StopCommand};
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let mut batch = Batch::new().concurrency(4);
+//! let stop = batch.add(StopCommand::new("web"));
+//! let pull = batch.add(PullCommand::new("redis:7"));
+//!
+//! let mut report = batch.run().await;
+//! println!("{}", report.summary());
+//!
+//! if let Some(item) = report.take(pull) {
+//!     println!("pull took {:?}: {:?}", item.duration, item.result.is_ok());
+//! }
+//! let _ = report.take(stop);
+//! # }
+//! ```
+//!
+//! For a batch of commands of the same type, [`Batch::run_all`] returns the
+//! results in order without handles.
+
+use crate::command::DockerCommand;
+use crate::error::{Error, Result};
+use crate::tracing_compat::{debug, info, info_span, warn, Instrument};
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Number of commands a [`Batch`] runs at once unless configured otherwise
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 8;
+
+type ErasedOutput = Box<dyn Any + Send>;
+type Job = Pin<Box<dyn Future<Output = Result<ErasedOutput>> + Send>>;
+
+/// A set of commands run together with a concurrency limit
+pub struct Batch {
+    jobs: Vec<Job>,
+    concurrency: usize,
+    fail_fast: bool,
+    timeout: Option<Duration>,
+}
+
+impl fmt::Debug for Batch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Batch")
+            .field("jobs", &self.jobs.len())
+            .field("concurrency", &self.concurrency)
+            .field("fail_fast", &self.fail_fast)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+impl Default for Batch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Batch {
+    /// Create an empty batch
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            jobs: Vec::new(),
+            concurrency: DEFAULT_BATCH_CONCURRENCY,
+            fail_fast: false,
+            timeout: None,
+        }
+    }
+
+    /// Run at most `limit` commands at once (at least one)
+    #[must_use]
+    pub fn concurrency(mut self, limit: usize) -> Self {
+        self.concurrency = limit.max(1);
+        self
+    }
+
+    /// Stop the batch at the first failure
+    ///
+    /// Commands still running are stopped and commands not yet started are
+    /// skipped; both are reported as [`Error::Interrupted`].
+    #[must_use]
+    pub fn fail_fast(mut self, enabled: bool) -> Self {
+        self.fail_fast = enabled;
+        self
+    }
+
+    /// Set a timeout for every command added afterwards that has none
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Add a command, returning a handle to its result
+    pub fn add<C>(&mut self, mut command: C) -> BatchHandle<C::Output>
+    where
+        C: DockerCommand + Send + Sync + 'static,
+        C::Output: Send + 'static,
+    {
+        if let Some(timeout) = self.timeout {
+            let executor = command.get_executor_mut();
+            executor.timeout = executor.timeout.or(Some(timeout));
+        }
+
+        let index = self.jobs.len();
+        self.jobs.push(Box::pin(async move {
+            command
+                .execute()
+                .await
+                .map(|output| Box::new(output) as ErasedOutput)
+        }));
+
+        BatchHandle {
+            index,
+            _output: PhantomData,
+        }
+    }
+
+    /// Number of commands in the batch
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.jobs.len()
+    }
+
+    /// Check whether the batch has no commands
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+
+    /// Run every command and collect the results
+    ///
+    /// Commands are spawned on the current Tokio runtime.
+    pub async fn run(self) -> BatchReport {
+        let span = info_span!(
+            "docker.batch",
+            total = self.jobs.len(),
+            concurrency = self.concurrency,
+            fail_fast = self.fail_fast,
+        );
+        self.run_inner().instrument(span).await
+    }
+
+    /// Add `commands` and run the batch, returning their results in order
+    ///
+    /// Commands added earlier run as well, but only the results of
+    /// `commands` are returned.
+    pub async fn run_all<C, I>(mut self, commands: I) -> BatchResults<C::Output>
+    where
+        C: DockerCommand + Send + Sync + 'static,
+        C::Output: Send + 'static,
+        I: IntoIterator<Item = C>,
+    {
+        let handles: Vec<_> = commands.into_iter().map(|c| self.add(c)).collect();
+        let mut report = self.run().await;
+        let items = handles
+            .into_iter()
+            .filter_map(|handle| report.take(handle))
+            .collect();
+
+        BatchResults {
+            items,
+            summary: report.summary,
+        }
+    }
+
+    async fn run_inner(self) -> BatchReport {
+        let started_at = Instant::now();
+        let total = self.jobs.len();
+        let semaphore = Arc::new(Semaphore::new(self.concurrency));
+        let mut tasks = JoinSet::new();
+        let mut task_indices = HashMap::with_capacity(total);
+
+        for (index, job) in self.jobs.into_iter().enumerate() {
+            let semaphore = Arc::clone(&semaphore);
+            let item_span = info_span!("docker.batch.item", index = index);
+            let handle = tasks.spawn(
+                async move {
+                    let _permit = semaphore.acquire_owned().await;
+                    let item_started = Instant::now();
+                    let result = job.await;
+                    (index, result, item_started.elapsed())
+                }
+                .instrument(item_span),
+            );
+            task_indices.insert(handle.id(), index);
+        }
+
+        let mut items: Vec<Option<ErasedItem>> = (0..total).map(|_| None).collect();
+        let mut stopped = false;
+
+        while let Some(joined) = tasks.join_next_with_id().await {
+            let (index, result, duration) = match joined {
+                Ok((_, (index, result, duration))) => (index, result, duration),
+                Err(join_error) => {
+                    let index = task_indices[&join_error.id()];
+                    let result = if join_error.is_cancelled() {
+                        Err(Error::Interrupted)
+                    } else {
+                        Err(Error::custom(format!(
+                            "batch command panicked: {join_error}"
+                        )))
+                    };
+                    (index, result, Duration::ZERO)
+                }
+            };
+
+            if let Err(ref e) = result {
+                debug!(index = index, error = %e, "batch command failed");
+                if self.fail_fast && !stopped && !matches!(e, Error::Interrupted) {
+                    warn!(index = index, "stopping batch after failure");
+                    stopped = true;
+                    tasks.abort_all();
+                }
+            }
+
+            items[index] = Some(ErasedItem { result, duration });
+        }
+
+        let summary = BatchSummary::from_items(&items, started_at.elapsed());
+        info!(
+            total = summary.total,
+            succeeded = summary.succeeded,
+            failed = summary.failed,
+            cancelled = summary.cancelled,
+            "batch completed"
+        );
+
+        BatchReport { items, summary }
+    }
+}
+
+/// Typed reference to a command added to a [`Batch`]
+pub struct BatchHandle<T> {
+    index: usize,
+    _output: PhantomData<fn() -> T>,
+}
+
+impl<T> BatchHandle<T> {
+    /// Position of the command in the batch
+    #[must_use]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<T> Clone for BatchHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for BatchHandle<T> {}
+
+impl<T> fmt::Debug for BatchHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BatchHandle")
+            .field("index", &self.index)
+            .finish()
+    }
+}
+
+/// Result of one command of a batch
+#[derive(Debug)]
+pub struct BatchItem<T> {
+    /// Position of the command in the batch
+    pub index: usize,
+    /// The command's output, or why it failed
+    ///
+    /// Commands stopped or skipped by fail-fast hold [`Error::Interrupted`].
+    pub result: Result<T>,
+    /// Time the command ran, excluding time spent waiting for a slot
+    pub duration: Duration,
+}
+
+struct ErasedItem {
+    result: Result<ErasedOutput>,
+    duration: Duration,
+}
+
+/// Results of a [`Batch`] run
+pub struct BatchReport {
+    items: Vec<Option<ErasedItem>>,
+    summary: BatchSummary,
+}
+
+impl fmt::Debug for BatchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BatchReport")
+            .field("summary", &self.summary)
+            .finish_non_exhaustive()
+    }
+}
+
+impl BatchReport {
+    /// Aggregated counts for the whole batch
+    #[must_use]
+    pub fn summary(&self) -> &BatchSummary {
+        &self.summary
+    }
+
+    /// Take the result of the command behind `handle`
+    ///
+    /// Returns `None` if the result was already taken or the handle belongs
+    /// to another batch.
+    pub fn take<T: 'static>(&mut self, handle: BatchHandle<T>) -> Option<BatchItem<T>> {
+        let slot = self.items.get_mut(handle.index)?;
+        let is_t = match slot.as_ref()?.result {
+            Ok(ref output) => output.is::<T>(),
+            Err(_) => true,
+        };
+        if !is_t {
+            return None;
+        }
+
+        let item = slot.take()?;
+        let result = item.result.map(|output| {
+            *output
+                .downcast::<T>()
+                .unwrap_or_else(|_| unreachable!("output type checked above"))
+        });
+        Some(BatchItem {
+            index: handle.index,
+            result,
+            duration: item.duration,
+        })
+    }
+}
+
+/// Results of [`Batch::run_all`]
+#[derive(Debug)]
+pub struct BatchResults<T> {
+    /// One item per command, in the order they were given
+    pub items: Vec<BatchItem<T>>,
+    /// Aggregated counts for the whole batch
+    pub summary: BatchSummary,
+}
+
+/// Aggregated outcome of a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchSummary {
+    /// Number of commands in the batch
+    pub total: usize,
+    /// Commands that succeeded
+    pub succeeded: usize,
+    /// Commands that failed
+    pub failed: usize,
+    /// Commands stopped or skipped
+    pub cancelled: usize,
+    /// Wall-clock time of the whole batch
+    pub elapsed: Duration,
+}
+
+impl BatchSummary {
+    fn from_items(items: &[Option<ErasedItem>], elapsed: Duration) -> Self {
+        let mut summary = Self {
+            total: items.len(),
+            succeeded: 0,
+            failed: 0,
+            cancelled: 0,
+            elapsed,
+        };
+        for item in items.iter().flatten() {
+            match item.result {
+                Ok(_) => summary.succeeded += 1,
+                Err(Error::Interrupted) => summary.cancelled += 1,
+                Err(_) => summary.failed += 1,
+            }
+        }
+        summary
+    }
+
+    /// Check whether every command succeeded
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.succeeded == self.total
+    }
+}
+
+impl fmt::Display for BatchSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} succeeded, {} failed, {} cancelled in {:?}",
+            self.succeeded, self.total, self.failed, self.cancelled, self.elapsed
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::{
+        ArgMatcher, CommandRunner, FakeResponse, FakeRunner, Invocation, ProcessOutput,
+    };
+    use crate::{PsCommand, StopCommand};
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Runner that sleeps, tracking how many invocations overlap
+    #[derive(Debug, Default)]
+    struct SlowRunner {
+        running: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl CommandRunner for SlowRunner {
+        async fn run(&self, invocation: &Invocation) -> Result<ProcessOutput> {
+            let now = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            let delay = if invocation.args.iter().any(|arg| arg == "fail") {
+                Duration::from_millis(5)
+            } else {
+                Duration::from_millis(50)
+            };
+            tokio::time::sleep(delay).await;
+            self.running.fetch_sub(1, Ordering::SeqCst);
+
+            Ok(if invocation.args.iter().any(|arg| arg == "fail") {
+                ProcessOutput {
+                    exit_code: 1,
+                    stderr: b"boom".to_vec(),
+                    ..ProcessOutput::default()
+                }
+            } else {
+                ProcessOutput::default()
+            })
+        }
+    }
+
+    /// Lets tests keep a handle on a runner they pass to commands
+    #[derive(Debug, Clone)]
+    struct Shared(Arc<SlowRunner>);
+
+    #[async_trait]
+    impl CommandRunner for Shared {
+        async fn run(&self, invocation: &Invocation) -> Result<ProcessOutput> {
+            self.0.run(invocation).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_heterogeneous_results() {
+        let fake = FakeRunner::new()
+            .on(ArgMatcher::prefix(["stop"]), FakeResponse::success("web\n"))
+            .on(
+                ArgMatcher::prefix(["ps"]),
+                FakeResponse::failure(1, "permission denied"),
+            );
+
+        let mut batch = Batch::new();
+        let mut stop = StopCommand::new("web");
+        stop.with_runner(fake.clone());
+        let mut ps = PsCommand::new();
+        ps.with_runner(fake);
+        let stop = batch.add(stop);
+        let ps = batch.add(ps);
+        assert_eq!(batch.len(), 2);
+
+        let mut report = batch.run().await;
+        assert_eq!(report.summary().succeeded, 1);
+        assert_eq!(report.summary().failed, 1);
+        assert!(!report.summary().is_success());
+
+        let stopped = report.take(stop).unwrap();
+        assert_eq!(stopped.index, 0);
+        assert_eq!(stopped.result.unwrap().stopped_containers, vec!["web"]);
+        assert!(report.take(ps).unwrap().result.is_err());
+        assert!(report.take(stop).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limit() {
+        let runner = Arc::new(SlowRunner::default());
+        let commands = (0..6).map(|i| {
+            let mut stop = StopCommand::new(format!("c{i}"));
+            stop.with_runner(Shared(Arc::clone(&runner)));
+            stop
+        });
+
+        let results = Batch::new().concurrency(2).run_all(commands).await;
+
+        assert_eq!(results.items.len(), 6);
+        assert!(results.summary.is_success());
+        assert_eq!(runner.peak.load(Ordering::SeqCst), 2);
+        assert!(results
+            .items
+            .iter()
+            .enumerate()
+            .all(|(i, item)| item.index == i && item.duration >= Duration::from_millis(50)));
+    }
+
+    #[tokio::test]
+    async fn test_fail_fast_stops_remaining_commands() {
+        let runner = Arc::new(SlowRunner::default());
+        let commands = ["fail", "a", "b", "c"].map(|name| {
+            let mut stop = StopCommand::new(name);
+            stop.with_runner(Shared(Arc::clone(&runner)));
+            stop
+        });
+
+        let results = Batch::new()
+            .concurrency(2)
+            .fail_fast(true)
+            .run_all(commands)
+            .await;
+
+        assert_eq!(results.summary.failed, 1);
+        assert_eq!(results.summary.cancelled, 3);
+        assert!(results.items[1..]
+            .iter()
+            .all(|item| matches!(item.result, Err(Error::Interrupted))));
+    }
+}
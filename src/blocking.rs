@@ -0,0 +1,131 @@
+//! Blocking wrappers around the async API.
+//!
+//! With the `blocking` feature, synchronous programs can run commands without
+//! setting up Tokio themselves:
+//!
+//! - [`DockerCommand::execute_blocking`](crate::DockerCommand::execute_blocking)
+//!   for every command
+//! - `*_blocking` versions of the [`Template`](crate::Template) lifecycle
+//!   methods, with a template feature enabled
+//! - `*_blocking` versions of the `ContainerGuard` lifecycle methods, with
+//!   the `testing` feature enabled
+//! - [`block_on`] for anything else
+//!
+//! Like `reqwest::blocking`, these drive the async code on an internal
+//! runtime that is started on first use and shared by all threads. Calling
+//! them from inside an async runtime works, but blocks that runtime's thread
+//! until the command finishes; prefer `.await` there.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use docker_wrapper::{DockerCommand, RunCommand, VersionCommand};
+//!
+//! fn main() -> docker_wrapper::Result<()> {
+//!     let version = VersionCommand::new().execute_blocking()?;
+//!     if let Some(info) = version.version_info {
+//!         println!("Docker {}", info.client.version);
+//!     }
+//!
+//!     let id = RunCommand::new("nginx:alpine").detach().execute_blocking()?;
+//!     println!("Started {}", id.short());
+//!     Ok(())
+//! }
+//! ```
+
+use std::future::Future;
+use std::sync::LazyLock;
+use tokio::runtime::{Builder, Handle, Runtime};
+
+/// Runtime shared by every blocking call
+static RUNTIME: LazyLock<Runtime> = LazyLock::new(|| {
+    Builder::new_multi_thread()
+        .worker_threads(1)
+        .thread_name("docker-wrapper-blocking")
+        .enable_all()
+        .build()
+        .expect("failed to start the docker-wrapper blocking runtime")
+});
+
+/// Run `future` to completion on the internal runtime
+///
+/// Blocks the current thread until the future finishes. Inside an async
+/// runtime the future is driven from a helper thread, since a runtime cannot
+/// be blocked on from within another one.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use docker_wrapper::blocking::block_on;
+/// use docker_wrapper::{DockerCommand, PullCommand, RunCommand};
+///
+/// let id = block_on(async {
+///     PullCommand::new("redis:7-alpine").execute().await?;
+///     RunCommand::new("redis:7-alpine").detach().execute().await
+/// })?;
+/// # Ok::<(), docker_wrapper::Error>(())
+/// ```
+pub fn block_on<F>(future: F) -> F::Output
+where
+    F: Future + Send,
+    F::Output: Send,
+{
+    if Handle::try_current().is_err() {
+        return RUNTIME.block_on(future);
+    }
+
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| RUNTIME.block_on(future))
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::command::{CommandExecutor, DockerCommand};
+    use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
+    use crate::PsCommand;
+
+    fn ps(fake: &FakeRunner) -> PsCommand {
+        let mut ps = PsCommand::new();
+        ps.executor = CommandExecutor::new().runner(fake.clone());
+        ps
+    }
+
+    #[test]
+    fn test_execute_blocking_without_runtime() {
+        let fake = FakeRunner::new().on(ArgMatcher::prefix(["ps"]), FakeResponse::success(""));
+
+        let output = ps(&fake).execute_blocking().unwrap();
+
+        assert!(output.containers.is_empty());
+        assert_eq!(fake.invocations().len(), 1);
+    }
+
+    #[test]
+    fn test_execute_blocking_from_many_threads() {
+        let fake = FakeRunner::new().on(ArgMatcher::Any, FakeResponse::success(""));
+
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| ps(&fake).execute_blocking().unwrap());
+            }
+        });
+
+        assert_eq!(fake.invocations().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_execute_blocking_inside_runtime() {
+        let fake = FakeRunner::new().on(
+            ArgMatcher::Any,
+            FakeResponse::failure(1, "Error response from daemon: No such container: web"),
+        );
+
+        let err = ps(&fake).execute_blocking().unwrap_err();
+
+        assert!(matches!(err, crate::Error::ContainerNotFound { .. }));
+    }
+}
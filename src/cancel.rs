@@ -0,0 +1,131 @@
+//! Cooperative cancellation for commands and streams.
+//!
+//! A [`CancellationToken`] is handed to any number of commands (see
+//! [`DockerCommand::with_cancellation`](crate::DockerCommand::with_cancellation)).
+//! Cancelling it stops every command still in flight: the child process gets
+//! `SIGTERM`, then `SIGKILL` if it hasn't exited after the executor's
+//! termination grace period, and the command returns
+//! [`Error::Interrupted`](crate::Error::Interrupted).
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use docker_wrapper::{CancellationToken, DockerCommand, PullCommand};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let shutdown = CancellationToken::new();
+//!
+//! let mut pull = PullCommand::new("postgres:16");
+//! pull.with_cancellation(shutdown.clone());
+//! let pull_task = tokio::spawn(async move { pull.execute().await });
+//!
+//! // Later, e.g. on SIGINT:
+//! shutdown.cancel();
+//! assert!(pull_task.await?.is_err());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::Notify;
+
+/// A cloneable handle for cancelling in-flight commands
+///
+/// All clones share the same state; cancelling one cancels them all.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancellationToken {
+    /// Create a new, uncancelled token
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the token, waking everything waiting on it
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    /// Check whether the token has been cancelled
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Wait until the token is cancelled
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.inner.notify.notified();
+            tokio::pin!(notified);
+            // Register before checking so a concurrent cancel can't be missed
+            notified.as_mut().enable();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Run `future` until it completes or the token is cancelled
+    ///
+    /// Returns `None` if the token was cancelled first, in which case
+    /// `future` is dropped.
+    pub async fn run_until_cancelled<F: Future>(&self, future: F) -> Option<F::Output> {
+        if self.is_cancelled() {
+            return None;
+        }
+        tokio::select! {
+            biased;
+            () = self.cancelled() => None,
+            output = future => Some(output),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_cancel_wakes_waiters() {
+        let token = CancellationToken::new();
+        let waiter = tokio::spawn({
+            let token = token.clone();
+            async move { token.cancelled().await }
+        });
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!token.is_cancelled());
+        token.cancel();
+
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("waiter should wake")
+            .unwrap();
+        assert!(token.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn test_run_until_cancelled() {
+        let token = CancellationToken::new();
+        assert_eq!(token.run_until_cancelled(async { 7 }).await, Some(7));
+
+        token.cancel();
+        let never = std::future::pending::<()>();
+        assert_eq!(token.run_until_cancelled(never).await, None);
+    }
+}
@@ -0,0 +1,539 @@
+//! Per-client connection settings.
+//!
+//! By default every command talks to whatever daemon the `docker` CLI picks
+//! from the process environment (`DOCKER_HOST`, the current context, ...).
+//! A [`DockerClient`] pins a set of connection settings instead, so that two
+//! clients in the same process can talk to different daemons without touching
+//! global environment variables.
+//!
+//! # Example
+//!
+//! ```rust
+//! use docker_wrapper::{DockerClient, DockerCommand, PsCommand};
+//!
+//! let remote = DockerClient::new()
+//!     .host("tcp://build-host:2376")
+//!     .tls_verify()
+//!     .tls_ca_cert("/etc/docker/certs/ca.pem");
+//! let local = DockerClient::new().context("default");
+//!
+//! let remote_ps = remote.command(PsCommand::new().all());
+//! let local_ps = local.command(PsCommand::new().all());
+//!
+//! assert_eq!(
+//!     remote_ps.get_executor().connection.global_args(),
+//!     ["--host", "tcp://build-host:2376", "--tlsverify", "--tlscacert", "/etc/docker/certs/ca.pem"]
+//! );
+//! assert_eq!(local_ps.get_executor().connection.global_args(), ["--context", "default"]);
+//! ```
+
+use crate::cancel::CancellationToken;
+use crate::command::{CommandExecutor, DockerCommand};
+use crate::debug::RetryPolicy;
+use crate::error::{Error, Result};
+use crate::interceptor::Interceptor;
+use crate::platform::PlatformInfo;
+use crate::runner::CommandRunner;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Global CLI options selecting which daemon a command talks to
+///
+/// These are passed before the subcommand (`docker --host ... ps`). An empty
+/// configuration adds no arguments and leaves daemon selection to the CLI.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConnectionConfig {
+    /// Daemon socket to connect to (`--host`)
+    pub host: Option<String>,
+    /// Docker context to use (`--context`)
+    pub context: Option<String>,
+    /// Location of the client config directory (`--config`)
+    pub config_dir: Option<PathBuf>,
+    /// Use TLS (`--tls`)
+    pub tls: bool,
+    /// Use TLS and verify the remote (`--tlsverify`)
+    pub tls_verify: bool,
+    /// Trust certs signed only by this CA (`--tlscacert`)
+    pub tls_ca_cert: Option<PathBuf>,
+    /// Path to TLS certificate file (`--tlscert`)
+    pub tls_cert: Option<PathBuf>,
+    /// Path to TLS key file (`--tlskey`)
+    pub tls_key: Option<PathBuf>,
+}
+
+impl ConnectionConfig {
+    /// Create an empty connection configuration
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check whether any connection option is set
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Check whether the daemon is selected explicitly
+    ///
+    /// When it is, `DOCKER_HOST` from platform detection is not passed to
+    /// the process so it cannot compete with the explicit choice.
+    #[must_use]
+    pub fn selects_daemon(&self) -> bool {
+        self.host.is_some() || self.context.is_some()
+    }
+
+    /// Validate the configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidConfig`] if both a host and a context are set,
+    /// which the Docker CLI rejects.
+    pub fn validate(&self) -> Result<()> {
+        if self.host.is_some() && self.context.is_some() {
+            return Err(Error::invalid_config(
+                "connection host and context are mutually exclusive",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Build the global arguments that precede the subcommand
+    #[must_use]
+    pub fn global_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+
+        if let Some(ref config_dir) = self.config_dir {
+            args.push("--config".to_string());
+            args.push(config_dir.display().to_string());
+        }
+
+        if let Some(ref context) = self.context {
+            args.push("--context".to_string());
+            args.push(context.clone());
+        }
+
+        if let Some(ref host) = self.host {
+            args.push("--host".to_string());
+            args.push(host.clone());
+        }
+
+        if self.tls {
+            args.push("--tls".to_string());
+        }
+
+        if self.tls_verify {
+            args.push("--tlsverify".to_string());
+        }
+
+        if let Some(ref ca) = self.tls_ca_cert {
+            args.push("--tlscacert".to_string());
+            args.push(ca.display().to_string());
+        }
+
+        if let Some(ref cert) = self.tls_cert {
+            args.push("--tlscert".to_string());
+            args.push(cert.display().to_string());
+        }
+
+        if let Some(ref key) = self.tls_key {
+            args.push("--tlskey".to_string());
+            args.push(key.display().to_string());
+        }
+
+        args
+    }
+}
+
+/// A handle holding connection settings and execution defaults
+///
+/// Commands and templates built through a client run against the client's
+/// daemon with the client's runner, platform and timeout. The client itself
+/// is cheap to clone.
+#[derive(Debug, Clone, Default)]
+pub struct DockerClient {
+    executor: CommandExecutor,
+}
+
+impl DockerClient {
+    /// Create a client with default settings
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a client from an existing executor
+    ///
+    /// Raw arguments and stdin of the executor are ignored; they belong to
+    /// individual commands.
+    #[must_use]
+    pub fn from_executor(mut executor: CommandExecutor) -> Self {
+        executor.raw_args.clear();
+        executor.stdin = None;
+        Self { executor }
+    }
+
+    /// Connect to the daemon at `host` (e.g. `unix:///run/docker.sock`)
+    #[must_use]
+    pub fn host(mut self, host: impl Into<String>) -> Self {
+        self.executor.connection.host = Some(host.into());
+        self
+    }
+
+    /// Use the named Docker context
+    #[must_use]
+    pub fn context(mut self, context: impl Into<String>) -> Self {
+        self.executor.connection.context = Some(context.into());
+        self
+    }
+
+    /// Use the client config directory at `path`
+    #[must_use]
+    pub fn config(mut self, path: impl Into<PathBuf>) -> Self {
+        self.executor.connection.config_dir = Some(path.into());
+        self
+    }
+
+    /// Use TLS
+    #[must_use]
+    pub fn tls(mut self) -> Self {
+        self.executor.connection.tls = true;
+        self
+    }
+
+    /// Use TLS and verify the remote
+    #[must_use]
+    pub fn tls_verify(mut self) -> Self {
+        self.executor.connection.tls_verify = true;
+        self
+    }
+
+    /// Trust certs signed only by the CA at `path`
+    #[must_use]
+    pub fn tls_ca_cert(mut self, path: impl Into<PathBuf>) -> Self {
+        self.executor.connection.tls_ca_cert = Some(path.into());
+        self
+    }
+
+    /// Use the TLS certificate at `path`
+    #[must_use]
+    pub fn tls_cert(mut self, path: impl Into<PathBuf>) -> Self {
+        self.executor.connection.tls_cert = Some(path.into());
+        self
+    }
+
+    /// Use the TLS key at `path`
+    #[must_use]
+    pub fn tls_key(mut self, path: impl Into<PathBuf>) -> Self {
+        self.executor.connection.tls_key = Some(path.into());
+        self
+    }
+
+    /// Replace all connection settings
+    #[must_use]
+    pub fn connection(mut self, connection: ConnectionConfig) -> Self {
+        self.executor.connection = connection;
+        self
+    }
+
+    /// Set the platform information used by commands
+    #[must_use]
+    pub fn platform(mut self, platform_info: PlatformInfo) -> Self {
+        self.executor.platform_info = Some(platform_info);
+        self
+    }
+
+    /// Set the default timeout of commands
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.executor.timeout = Some(timeout);
+        self
+    }
+
+    /// Abort every command of this client when `token` is cancelled
+    #[must_use]
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.executor.cancellation = Some(token);
+        self
+    }
+
+    /// Retry every command of this client according to `policy`
+    #[must_use]
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.executor.retry = Some(policy);
+        self
+    }
+
+    /// Add an interceptor run around every command of this client
+    #[must_use]
+    pub fn interceptor(mut self, interceptor: impl Interceptor + 'static) -> Self {
+        self.executor.interceptors.push(interceptor);
+        self
+    }
+
+    /// Set the runner used to execute processes
+    #[must_use]
+    pub fn runner(mut self, runner: impl CommandRunner + 'static) -> Self {
+        self.executor.runner = Arc::new(runner);
+        self
+    }
+
+    /// Set an environment variable for every runtime process of this client
+    #[must_use]
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.executor.set_env(key.into(), value.into());
+        self
+    }
+
+    /// Remove an environment variable from every runtime process of this client
+    #[must_use]
+    pub fn env_remove(mut self, key: impl Into<String>) -> Self {
+        self.executor.remove_env(key.into());
+        self
+    }
+
+    /// Start every runtime process of this client from an empty environment
+    #[must_use]
+    pub fn env_clear(mut self) -> Self {
+        self.executor.clear_env();
+        self
+    }
+
+    /// Set the working directory of every runtime process of this client
+    #[must_use]
+    pub fn current_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.executor.current_dir = Some(dir.into());
+        self
+    }
+
+    /// Get the connection settings
+    #[must_use]
+    pub fn connection_config(&self) -> &ConnectionConfig {
+        &self.executor.connection
+    }
+
+    /// Get the executor commands built from this client start from
+    #[must_use]
+    pub fn executor(&self) -> &CommandExecutor {
+        &self.executor
+    }
+
+    /// Bind a command to this client
+    ///
+    /// The command keeps its own raw arguments and stdin. A timeout,
+    /// cancellation token, retry policy or working directory set on the
+    /// command takes precedence over the client's, and its environment
+    /// changes apply on top of the client's. The command's interceptors run
+    /// after the client's.
+    #[must_use]
+    pub fn command<C: DockerCommand>(&self, mut command: C) -> C {
+        let own = command.get_executor_mut();
+        let mut executor = self.executor.clone();
+        executor.raw_args = std::mem::take(&mut own.raw_args);
+        executor.stdin = own.stdin.take();
+        if own.timeout.is_some() {
+            executor.timeout = own.timeout;
+        }
+        if own.cancellation.is_some() {
+            executor.cancellation = own.cancellation.take();
+        }
+        if own.retry.is_some() {
+            executor.retry = own.retry.take();
+        }
+        executor.interceptors.extend(&own.interceptors);
+        if own.env_clear {
+            executor.clear_env();
+        }
+        for key in std::mem::take(&mut own.env_remove) {
+            executor.remove_env(key);
+        }
+        for (key, value) in std::mem::take(&mut own.env) {
+            executor.set_env(key, value);
+        }
+        if own.current_dir.is_some() {
+            executor.current_dir = own.current_dir.take();
+        }
+        *own = executor;
+        command
+    }
+
+    /// Bind a template to this client
+    ///
+    /// Every command the template runs uses this client's settings.
+    #[cfg(any(
+        feature = "templates",
+        feature = "template-redis",
+        feature = "template-redis-cluster",
+        feature = "template-redis-enterprise",
+        feature = "template-postgres",
+        feature = "template-mysql",
+        feature = "template-mongodb",
+        feature = "template-nginx",
+        feature = "template-toxiproxy"
+    ))]
+    #[must_use]
+    pub fn template<T: crate::template::Template>(&self, template: T) -> T {
+        template.with_executor(self.executor.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
+    use crate::{PsCommand, StopCommand};
+
+    #[test]
+    fn test_empty_connection_adds_no_args() {
+        let connection = ConnectionConfig::new();
+        assert!(connection.is_empty());
+        assert!(connection.global_args().is_empty());
+        assert!(connection.validate().is_ok());
+    }
+
+    #[test]
+    fn test_connection_global_args() {
+        let client = DockerClient::new()
+            .config("/tmp/docker-config")
+            .host("tcp://remote:2376")
+            .tls_verify()
+            .tls_ca_cert("/certs/ca.pem")
+            .tls_cert("/certs/cert.pem")
+            .tls_key("/certs/key.pem");
+
+        assert_eq!(
+            client.connection_config().global_args(),
+            vec![
+                "--config",
+                "/tmp/docker-config",
+                "--host",
+                "tcp://remote:2376",
+                "--tlsverify",
+                "--tlscacert",
+                "/certs/ca.pem",
+                "--tlscert",
+                "/certs/cert.pem",
+                "--tlskey",
+                "/certs/key.pem",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_host_and_context_conflict() {
+        let connection = ConnectionConfig {
+            host: Some("unix:///var/run/docker.sock".to_string()),
+            context: Some("remote".to_string()),
+            ..ConnectionConfig::default()
+        };
+        assert!(matches!(
+            connection.validate(),
+            Err(Error::InvalidConfig { .. })
+        ));
+    }
+
+    #[test]
+    fn test_command_keeps_own_args_and_timeout() {
+        let client = DockerClient::new()
+            .context("staging")
+            .timeout(Duration::from_secs(5));
+
+        let mut ps = PsCommand::new();
+        ps.arg("--no-trunc").with_timeout_secs(60);
+        let ps = client.command(ps);
+
+        let executor = ps.get_executor();
+        assert_eq!(executor.raw_args, vec!["--no-trunc"]);
+        assert_eq!(executor.timeout, Some(Duration::from_secs(60)));
+        assert_eq!(executor.connection.context.as_deref(), Some("staging"));
+    }
+
+    #[test]
+    fn test_command_layers_process_env_over_client() {
+        let client = DockerClient::new()
+            .env("DOCKER_CONFIG", "/etc/docker-ci")
+            .env("COMPOSE_PROJECT_NAME", "ci")
+            .current_dir("/srv");
+
+        let mut ps = PsCommand::new();
+        ps.with_env("COMPOSE_PROJECT_NAME", "local")
+            .with_env_remove("DOCKER_CONFIG")
+            .with_current_dir("/work");
+        let ps = client.command(ps);
+
+        let executor = ps.get_executor();
+        assert_eq!(
+            executor.env,
+            vec![("COMPOSE_PROJECT_NAME".to_string(), "local".to_string())]
+        );
+        assert_eq!(executor.env_remove, vec!["DOCKER_CONFIG"]);
+        assert_eq!(executor.current_dir, Some(PathBuf::from("/work")));
+        assert_eq!(
+            client.command(PsCommand::new()).get_executor().current_dir,
+            Some(PathBuf::from("/srv"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_clients_target_different_daemons() {
+        let fake = FakeRunner::new().on(ArgMatcher::Any, FakeResponse::success(""));
+        let first = DockerClient::new()
+            .host("unix:///run/first.sock")
+            .runner(fake.clone());
+        let second = DockerClient::new().context("second").runner(fake.clone());
+
+        first
+            .command(StopCommand::new("web"))
+            .execute()
+            .await
+            .unwrap();
+        second
+            .command(StopCommand::new("web"))
+            .execute()
+            .await
+            .unwrap();
+
+        let invocations = fake.invocations();
+        assert_eq!(
+            invocations[0].args,
+            vec!["--host", "unix:///run/first.sock", "stop", "web"]
+        );
+        assert_eq!(
+            invocations[1].args,
+            vec!["--context", "second", "stop", "web"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_conflicting_connection_fails_before_running() {
+        let fake = FakeRunner::new().on(ArgMatcher::Any, FakeResponse::success(""));
+        let client = DockerClient::new()
+            .host("tcp://a:2375")
+            .context("b")
+            .runner(fake.clone());
+
+        let result = client.command(StopCommand::new("web")).execute().await;
+        assert!(matches!(result, Err(Error::InvalidConfig { .. })));
+        assert!(fake.invocations().is_empty());
+    }
+
+    #[cfg(feature = "template-redis")]
+    #[tokio::test]
+    async fn test_template_commands_use_client_connection() {
+        use crate::template::Template;
+        use crate::RedisTemplate;
+
+        let fake = FakeRunner::new().on(ArgMatcher::Any, FakeResponse::success(""));
+        let client = DockerClient::new().context("ci").runner(fake.clone());
+
+        client
+            .template(RedisTemplate::new("cache"))
+            .stop()
+            .await
+            .unwrap();
+
+        let invocations = fake.invocations();
+        assert_eq!(invocations[0].args[..3], ["--context", "ci", "stop"]);
+    }
+}
@@ -43,23 +43,64 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! # Serialization
+//!
+//! [`RunCommand`](run::RunCommand), [`BuildCommand`](build::BuildCommand) and
+//! [`ExecCommand`](exec::ExecCommand) implement serde's `Serialize` and
+//! `Deserialize`, so specs can be stored, diffed or sent to another process.
+//! The format is an object with one `snake_case` field per option; the field
+//! names are pinned and don't follow renames in the code. Maps are written
+//! with sorted keys, and arguments added with [`arg()`](DockerCommand::arg)
+//! are kept as `extra_args`. When deserializing, missing options take their
+//! default, unknown fields are rejected, and `image` (run) or `container` and
+//! `command` (exec) are required.
+//! Executor settings such as the runner, timeout or retry policy are not
+//! serialized; a deserialized command runs with a default executor.
+//!
+//! ```rust
+//! use docker_wrapper::{DockerCommand, RunCommand};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let spec = serde_json::to_string(&RunCommand::new("redis:7").name("cache").port(6379, 6379))?;
+//!
+//! let cmd: RunCommand = serde_json::from_str(&spec)?;
+//! assert_eq!(
+//!     cmd.build_command_args(),
+//!     ["run", "--name", "cache", "--publish", "6379:6379", "redis:7"]
+//! );
+//! # Ok(())
+//! # }
+//! ```
 
-use crate::error::{Error, Result};
+use crate::cancel::CancellationToken;
+use crate::client::ConnectionConfig;
+use crate::debug::RetryPolicy;
+use crate::error::{CommandFailure, Error, Result};
+use crate::interceptor::{Interceptor, InterceptorChain};
+use crate::metrics;
 use crate::platform::PlatformInfo;
-use crate::tracing_compat::{debug, error, info, trace, warn};
+use crate::redact::Redactor;
+use crate::runner::{CommandRunner, Invocation, ProcessRunner, DEFAULT_TERMINATION_GRACE};
+use crate::stream::{OutputStream, StreamCompletion};
+use crate::tracing_compat::{debug, info, info_span, trace, warn, Instrument};
 use async_trait::async_trait;
-use std::collections::HashMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
 use std::ffi::OsStr;
 use std::path::PathBuf;
 use std::process::Stdio;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use tokio::process::Command as TokioCommand;
+use tokio::io::{AsyncRead, AsyncWriteExt};
+use tokio::process::{ChildStdin, Command as TokioCommand};
 
 // Re-export all command modules
 pub mod attach;
 pub mod bake;
 pub mod build;
 pub mod builder;
+pub mod buildkit;
 pub mod commit;
 #[cfg(feature = "compose")]
 pub mod compose;
@@ -87,8 +128,10 @@ pub mod logs;
 #[cfg(feature = "manifest")]
 pub mod manifest;
 pub mod network;
+pub(crate) mod parse_util;
 pub mod pause;
 pub mod port;
+pub mod progress;
 pub mod ps;
 pub mod pull;
 pub mod push;
@@ -131,6 +174,58 @@ pub trait DockerCommand {
     /// Execute the command and return the typed output
     async fn execute(&self) -> Result<Self::Output>;
 
+    /// Execute the command, blocking the current thread until it finishes
+    ///
+    /// Runs [`execute()`](Self::execute) on the internal runtime of the
+    /// [`blocking`](crate::blocking) module.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`execute()`](Self::execute).
+    #[cfg(feature = "blocking")]
+    fn execute_blocking(&self) -> Result<Self::Output>
+    where
+        Self: Sync,
+        Self::Output: Send,
+    {
+        crate::blocking::block_on(self.execute())
+    }
+
+    /// Start the command and stream its output while it runs
+    ///
+    /// Works for every command, including `pull`, `push`, `exec` and the
+    /// compose commands. Lines arrive on the [`OutputStream`] as they are
+    /// written; the [`StreamCompletion`] resolves to the exit status once the
+    /// process has exited. The executor's environment, stdin, cancellation
+    /// token and interceptors apply; the timeout and retry policy do not.
+    ///
+    /// ```rust,no_run
+    /// use docker_wrapper::{DockerCommand, ExecCommand};
+    ///
+    /// # async fn example() -> docker_wrapper::Result<()> {
+    /// let exec = ExecCommand::new("web", vec!["tail".into(), "-f".into(), "/var/log/app.log".into()]);
+    /// let (mut lines, completion) = exec.stream_output()?;
+    /// while let Some(line) = lines.next_line().await {
+    ///     println!("{line:?}");
+    /// }
+    /// completion.await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the process cannot be started, for example when
+    /// an interceptor vetoes it. Failures while it runs are reported by the
+    /// completion.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a Tokio runtime.
+    fn stream_output(&self) -> Result<(OutputStream, StreamCompletion)> {
+        crate::stream::spawn_output_stream(self.get_executor(), self.build_command_args())
+    }
+
     /// Helper method to execute the command with proper error handling
     async fn execute_command(&self, command_args: Vec<String>) -> Result<CommandOutput> {
         let executor = self.get_executor();
@@ -197,6 +292,119 @@ pub trait DockerCommand {
         self.get_executor_mut().timeout = Some(std::time::Duration::from_secs(seconds));
         self
     }
+
+    /// Feed the given source to the command's standard input
+    ///
+    /// Useful for commands that read from stdin, such as `login --password-stdin`,
+    /// `load`, `import -` or `build -`.
+    ///
+    /// ```rust,no_run
+    /// use docker_wrapper::{DockerCommand, ImportCommand, StdinSource};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// ImportCommand::new("-")
+    ///     .repository("my-image:latest")
+    ///     .with_stdin(StdinSource::file("rootfs.tar"))
+    ///     .execute()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn with_stdin(&mut self, source: impl Into<StdinSource>) -> &mut Self {
+        self.get_executor_mut().stdin = Some(source.into());
+        self
+    }
+
+    /// Execute this command through the given runner instead of spawning
+    /// the runtime binary directly
+    ///
+    /// See [`FakeRunner`](crate::FakeRunner) for testing without a daemon.
+    fn with_runner(&mut self, runner: impl CommandRunner + 'static) -> &mut Self {
+        self.get_executor_mut().runner = Arc::new(runner);
+        self
+    }
+
+    /// Abort this command when `token` is cancelled
+    ///
+    /// The process is stopped and execution returns [`Error::Interrupted`].
+    /// Streaming commands honor the token too.
+    fn with_cancellation(&mut self, token: CancellationToken) -> &mut Self {
+        self.get_executor_mut().cancellation = Some(token);
+        self
+    }
+
+    /// Set the connection options (host, context, TLS) for this command
+    ///
+    /// See [`DockerClient`](crate::DockerClient) for applying the same
+    /// settings to many commands.
+    fn with_connection(&mut self, connection: ConnectionConfig) -> &mut Self {
+        self.get_executor_mut().connection = connection;
+        self
+    }
+
+    /// Retry this command according to `policy` when it fails
+    ///
+    /// Each attempt re-runs the same invocation, so the command keeps its
+    /// typed output. Which errors are retried is decided by
+    /// [`RetryPolicy::should_retry`]. A stdin reader can only be consumed
+    /// once; use bytes or a file as the stdin source for retried commands.
+    fn with_retry(&mut self, policy: RetryPolicy) -> &mut Self {
+        self.get_executor_mut().retry = Some(policy);
+        self
+    }
+
+    /// Add an interceptor run around this command
+    ///
+    /// See [`interceptor`](crate::interceptor) for ordering and veto rules.
+    fn with_interceptor(&mut self, interceptor: impl Interceptor + 'static) -> &mut Self {
+        self.get_executor_mut().interceptors.push(interceptor);
+        self
+    }
+
+    /// Set an environment variable for the runtime process running this
+    /// command
+    ///
+    /// This configures the CLI (e.g. `DOCKER_BUILDKIT=1`), not the container;
+    /// see [`CommandExecutor::env`]. Streaming commands use it too.
+    ///
+    /// ```rust,no_run
+    /// use docker_wrapper::{BuildCommand, DockerCommand};
+    ///
+    /// # async fn example() -> docker_wrapper::Result<()> {
+    /// BuildCommand::new("app")
+    ///     .tag("app:latest")
+    ///     .with_env("DOCKER_BUILDKIT", "1")
+    ///     .with_current_dir("/src/project")
+    ///     .execute()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn with_env(&mut self, key: impl Into<String>, value: impl Into<String>) -> &mut Self {
+        self.get_executor_mut().set_env(key.into(), value.into());
+        self
+    }
+
+    /// Remove an environment variable from the runtime process running this
+    /// command
+    fn with_env_remove(&mut self, key: impl Into<String>) -> &mut Self {
+        self.get_executor_mut().remove_env(key.into());
+        self
+    }
+
+    /// Run this command's runtime process with an empty environment
+    ///
+    /// See [`CommandExecutor::env_clear`].
+    fn with_env_clear(&mut self) -> &mut Self {
+        self.get_executor_mut().clear_env();
+        self
+    }
+
+    /// Set the working directory of the runtime process running this command
+    fn with_current_dir(&mut self, dir: impl Into<PathBuf>) -> &mut Self {
+        self.get_executor_mut().current_dir = Some(dir.into());
+        self
+    }
 }
 
 /// Base configuration for all compose commands
@@ -504,6 +712,30 @@ pub struct CommandExecutor {
     pub platform_info: Option<PlatformInfo>,
     /// Optional timeout for command execution
     pub timeout: Option<Duration>,
+    /// Optional source for the command's standard input
+    pub stdin: Option<StdinSource>,
+    /// Runner that actually executes the process
+    pub runner: Arc<dyn CommandRunner>,
+    /// Global options selecting the daemon to talk to
+    pub connection: ConnectionConfig,
+    /// Token that aborts the command when cancelled
+    pub cancellation: Option<CancellationToken>,
+    /// Time a timed-out or cancelled process gets to exit after `SIGTERM`
+    pub termination_grace: Duration,
+    /// Policy for retrying failed attempts
+    pub retry: Option<RetryPolicy>,
+    /// Hooks run around every process this executor starts
+    pub interceptors: InterceptorChain,
+    /// Environment variables set for the process, on top of the platform's
+    pub env: Vec<(String, String)>,
+    /// Variables removed from the process environment
+    pub env_remove: Vec<String>,
+    /// Start the process from an empty environment
+    pub env_clear: bool,
+    /// Working directory of the process (inherited if `None`)
+    pub current_dir: Option<PathBuf>,
+    /// Masks secrets in command lines and failure output
+    pub redactor: Arc<Redactor>,
 }
 
 impl CommandExecutor {
@@ -514,6 +746,18 @@ impl CommandExecutor {
             raw_args: Vec::new(),
             platform_info: None,
             timeout: None,
+            stdin: None,
+            runner: Arc::new(ProcessRunner),
+            connection: ConnectionConfig::default(),
+            cancellation: None,
+            termination_grace: DEFAULT_TERMINATION_GRACE,
+            retry: None,
+            interceptors: InterceptorChain::new(),
+            env: Vec::new(),
+            env_remove: Vec::new(),
+            env_clear: false,
+            current_dir: None,
+            redactor: crate::redact::default_redactor(),
         }
     }
 
@@ -528,6 +772,18 @@ impl CommandExecutor {
             raw_args: Vec::new(),
             platform_info: Some(platform_info),
             timeout: None,
+            stdin: None,
+            runner: Arc::new(ProcessRunner),
+            connection: ConnectionConfig::default(),
+            cancellation: None,
+            termination_grace: DEFAULT_TERMINATION_GRACE,
+            retry: None,
+            interceptors: InterceptorChain::new(),
+            env: Vec::new(),
+            env_remove: Vec::new(),
+            env_clear: false,
+            current_dir: None,
+            redactor: crate::redact::default_redactor(),
         })
     }
 
@@ -540,8 +796,10 @@ impl CommandExecutor {
 
     /// Set a timeout for command execution
     ///
-    /// If the command takes longer than the specified duration, it will be
-    /// terminated and an `Error::Timeout` will be returned.
+    /// If the command takes longer than the specified duration, the process
+    /// is sent `SIGTERM` (then `SIGKILL` after the
+    /// [termination grace period](Self::termination_grace)) and an
+    /// `Error::Timeout` is returned.
     #[must_use]
     pub fn timeout(mut self, timeout: Duration) -> Self {
         self.timeout = Some(timeout);
@@ -555,6 +813,266 @@ impl CommandExecutor {
         self
     }
 
+    /// Set the source for the command's standard input
+    #[must_use]
+    pub fn stdin(mut self, source: impl Into<StdinSource>) -> Self {
+        self.stdin = Some(source.into());
+        self
+    }
+
+    /// Set the runner used to execute processes
+    ///
+    /// Defaults to [`ProcessRunner`]. Use a [`FakeRunner`](crate::FakeRunner)
+    /// to test code without a Docker daemon.
+    #[must_use]
+    pub fn runner(mut self, runner: impl CommandRunner + 'static) -> Self {
+        self.runner = Arc::new(runner);
+        self
+    }
+
+    /// Abort the command when `token` is cancelled
+    ///
+    /// A cancelled command returns [`Error::Interrupted`].
+    #[must_use]
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Set how long a timed-out or cancelled process gets to exit after
+    /// `SIGTERM` before it is killed
+    #[must_use]
+    pub fn termination_grace(mut self, grace: Duration) -> Self {
+        self.termination_grace = grace;
+        self
+    }
+
+    /// Retry failed attempts according to `policy`
+    ///
+    /// The timeout applies to each attempt; cancellation stops the whole
+    /// sequence, including any backoff delay.
+    #[must_use]
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Add an interceptor run around every command
+    ///
+    /// See [`interceptor`](crate::interceptor) for ordering and veto rules.
+    #[must_use]
+    pub fn interceptor(mut self, interceptor: impl Interceptor + 'static) -> Self {
+        self.interceptors.push(interceptor);
+        self
+    }
+
+    /// Set the redactor masking secrets in command lines and failure output
+    ///
+    /// Defaults to [`Redactor::new`]. See [`redact`](crate::redact) for what
+    /// is masked.
+    #[must_use]
+    pub fn redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = Arc::new(redactor);
+        self
+    }
+
+    /// Mask `value` in this executor's command lines and failure output
+    ///
+    /// Use this for secrets passed as plain arguments or echoed in output,
+    /// such as a password inside a `sh -c` script. The value is masked
+    /// however short or plain it is; see [`Redactor::secret`].
+    #[must_use]
+    pub fn secret(mut self, value: impl Into<String>) -> Self {
+        Arc::make_mut(&mut self.redactor).add_secret(value.into());
+        self
+    }
+
+    /// Set the connection options passed before every subcommand
+    #[must_use]
+    pub fn connection(mut self, connection: ConnectionConfig) -> Self {
+        self.connection = connection;
+        self
+    }
+
+    /// Set an environment variable for the runtime process
+    ///
+    /// This is the environment of the `docker` CLI itself (e.g.
+    /// `DOCKER_BUILDKIT`, `DOCKER_CONFIG`, `COMPOSE_PROJECT_NAME`), not of
+    /// the container. It overrides the inherited and platform environment.
+    #[must_use]
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.set_env(key.into(), value.into());
+        self
+    }
+
+    /// Remove an environment variable from the runtime process
+    ///
+    /// Removes inherited and platform variables as well as ones set earlier
+    /// with [`env`](Self::env).
+    #[must_use]
+    pub fn env_remove(mut self, key: impl Into<String>) -> Self {
+        self.remove_env(key.into());
+        self
+    }
+
+    /// Start the runtime process from an empty environment
+    ///
+    /// Variables set earlier are dropped; the platform's variables and ones
+    /// set afterwards with [`env`](Self::env) are still passed.
+    #[must_use]
+    pub fn env_clear(mut self) -> Self {
+        self.clear_env();
+        self
+    }
+
+    /// Set the working directory of the runtime process
+    ///
+    /// Relative paths given to compose (`-f`, `--project-directory`) and
+    /// build contexts resolve against it.
+    #[must_use]
+    pub fn current_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.current_dir = Some(dir.into());
+        self
+    }
+
+    pub(crate) fn set_env(&mut self, key: String, value: String) {
+        self.env_remove.retain(|removed| *removed != key);
+        if let Some(entry) = self.env.iter_mut().find(|(k, _)| *k == key) {
+            entry.1 = value;
+        } else {
+            self.env.push((key, value));
+        }
+    }
+
+    pub(crate) fn remove_env(&mut self, key: String) {
+        self.env.retain(|(k, _)| *k != key);
+        if !self.env_remove.contains(&key) {
+            self.env_remove.push(key);
+        }
+    }
+
+    pub(crate) fn clear_env(&mut self) {
+        self.env.clear();
+        self.env_remove.clear();
+        self.env_clear = true;
+    }
+
+    /// Build the error for a command that exited non-zero, masking its
+    /// output with the executor's redactor
+    pub(crate) fn failure(
+        &self,
+        command: String,
+        exit_code: i32,
+        stdout: &str,
+        stderr: &str,
+    ) -> Error {
+        Error::from_failure(CommandFailure {
+            command,
+            exit_code,
+            stdout: self.redactor.redact_text(stdout),
+            stderr: self.redactor.redact_text(stderr),
+        })
+    }
+
+    /// Describe how `args` (subcommand first) would be run by this executor
+    ///
+    /// The invocation carries the runtime binary, connection options,
+    /// platform environment and stdin, with arguments adapted to the
+    /// detected runtime. Raw arguments are not added; callers pass the
+    /// complete argument list.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnsupportedByRuntime`] if the detected runtime
+    /// cannot run the arguments or connection options.
+    pub fn invocation(&self, args: Vec<String>) -> Result<Invocation> {
+        if args.first().is_some_and(|arg| arg == "compose") {
+            if let Some(invocation) = self.standalone_compose_invocation(&args[1..])? {
+                return Ok(invocation);
+            }
+        }
+
+        let mut all_args = self.connection.global_args();
+        if let Some(ref platform_info) = self.platform_info {
+            platform_info.runtime.check_global_args(&all_args)?;
+            all_args.extend(platform_info.runtime.adapt_args(args)?);
+        } else {
+            all_args.extend(args);
+        }
+
+        let mut invocation = Invocation::new(self.get_runtime_command(), all_args);
+        self.apply_process_env(&mut invocation);
+        invocation.stdin.clone_from(&self.stdin);
+        invocation.termination_grace = self.termination_grace;
+        invocation.redactor = Arc::clone(&self.redactor);
+        Ok(invocation)
+    }
+
+    /// Build the invocation for a compose command when the platform's compose
+    /// entrypoint is a standalone binary (`docker-compose`, `podman-compose`).
+    ///
+    /// Returns `None` when compose runs as a subcommand of the runtime.
+    fn standalone_compose_invocation(&self, args: &[String]) -> Result<Option<Invocation>> {
+        let Some(ref platform_info) = self.platform_info else {
+            return Ok(None);
+        };
+        let Some((program, prefix)) = platform_info.compose_command.split_first() else {
+            return Ok(None);
+        };
+        if program == platform_info.runtime.command() {
+            return Ok(None);
+        }
+
+        // docker-compose v1 understands the Docker CLI connection flags;
+        // other standalone tools do not.
+        let mut all_args = Vec::new();
+        if program == "docker-compose" {
+            all_args.extend(self.connection.global_args());
+        } else if !self.connection.is_empty() {
+            return Err(Error::unsupported_by_runtime(
+                program.clone(),
+                "connection options",
+            ));
+        }
+        all_args.extend(prefix.iter().cloned());
+        all_args.extend(args.iter().cloned());
+
+        let mut invocation = Invocation::new(program.clone(), all_args);
+        self.apply_process_env(&mut invocation);
+        invocation.stdin.clone_from(&self.stdin);
+        invocation.termination_grace = self.termination_grace;
+        invocation.redactor = Arc::clone(&self.redactor);
+        Ok(Some(invocation))
+    }
+
+    /// Set the invocation's environment and working directory
+    ///
+    /// Platform variables come first, minus removed ones; explicitly set
+    /// variables override them.
+    fn apply_process_env(&self, invocation: &mut Invocation) {
+        let mut platform_env = self.platform_env();
+        platform_env.retain(|(key, _)| {
+            !self.env_remove.contains(key) && !self.env.iter().any(|(k, _)| k == key)
+        });
+        invocation.platform_env = platform_env;
+        invocation.env.clone_from(&self.env);
+        invocation.env_remove.clone_from(&self.env_remove);
+        invocation.env_clear = self.env_clear;
+        invocation.current_dir.clone_from(&self.current_dir);
+    }
+
+    /// Environment variables derived from platform info
+    fn platform_env(&self) -> Vec<(String, String)> {
+        let Some(ref platform_info) = self.platform_info else {
+            return Vec::new();
+        };
+        let mut env = platform_info.environment_vars();
+        if self.connection.selects_daemon() {
+            env.retain(|(key, _)| key != "DOCKER_HOST");
+        }
+        env
+    }
+
     /// Get the runtime command to use
     fn get_runtime_command(&self) -> String {
         if let Some(ref platform_info) = self.platform_info {
@@ -576,6 +1094,8 @@ impl CommandExecutor {
             Runtime::Colima => "colima",
             Runtime::RancherDesktop => "rancher-desktop",
             Runtime::OrbStack => "orbstack",
+            Runtime::Nerdctl => "nerdctl",
+            Runtime::Finch => "finch",
         })
     }
 
@@ -604,6 +1124,8 @@ impl CommandExecutor {
         command_name: &str,
         args: Vec<String>,
     ) -> Result<CommandOutput> {
+        self.connection.validate()?;
+
         // Prepend raw args (they should come before command-specific args)
         let mut all_args = self.raw_args.clone();
         all_args.extend(args);
@@ -611,21 +1133,44 @@ impl CommandExecutor {
         // Insert the command name at the beginning
         all_args.insert(0, command_name.to_string());
 
-        let runtime_command = self.get_runtime_command();
+        let mut invocation = self.invocation(all_args)?;
+        self.interceptors.before(&mut invocation).inspect_err(|e| {
+            debug!(error = %e, "command vetoed by interceptor");
+        })?;
 
-        trace!(args = ?all_args, "executing docker command");
+        trace!(command = %invocation.command_line(), "executing docker command");
 
         let started_at = std::time::Instant::now();
 
-        // Execute with or without timeout
-        let result = if let Some(timeout_duration) = self.timeout {
-            self.execute_with_timeout(&runtime_command, &all_args, timeout_duration)
-                .await
+        let run = async {
+            if let Some(ref policy) = self.retry {
+                self.execute_with_retry(command_name, &invocation, policy)
+                    .await
+            } else {
+                self.execute_attempt(command_name, &invocation).await
+            }
+        };
+
+        let result = if let Some(ref token) = self.cancellation {
+            token.run_until_cancelled(run).await.unwrap_or_else(|| {
+                debug!("command cancelled");
+                Err(Error::Interrupted)
+            })
         } else {
-            self.execute_internal(&runtime_command, &all_args).await
+            run.await
         };
 
-        let duration_ms = u64::try_from(started_at.elapsed().as_millis()).unwrap_or(u64::MAX);
+        let elapsed = started_at.elapsed();
+        self.interceptors
+            .after(&invocation, result.as_ref(), elapsed);
+        metrics::record_command(
+            command_name,
+            &invocation.program,
+            metrics::Mode::Buffered,
+            result.as_ref().map(|output| output.exit_code),
+            elapsed,
+        );
+        let duration_ms = u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX);
 
         match &result {
             Ok(output) => {
@@ -642,12 +1187,8 @@ impl CommandExecutor {
                 }
             }
             Err(e) => {
-                let (exit_code, stderr_snippet) = match e {
-                    Error::CommandFailed {
-                        exit_code, stderr, ..
-                    } => (Some(*exit_code), Some(truncate_for_log(stderr))),
-                    _ => (None, None),
-                };
+                let exit_code = e.exit_code();
+                let stderr_snippet = e.stderr().map(truncate_for_log);
                 warn!(
                     command = %command_name,
                     exit_code = exit_code,
@@ -662,58 +1203,119 @@ impl CommandExecutor {
         result
     }
 
+    /// Run a single attempt, with or without timeout
+    async fn execute_attempt(
+        &self,
+        command_name: &str,
+        invocation: &Invocation,
+    ) -> Result<CommandOutput> {
+        if let Some(timeout_duration) = self.timeout {
+            let result = self
+                .execute_with_timeout(invocation, timeout_duration)
+                .await;
+            if let Err(Error::Timeout { .. }) = result {
+                metrics::record_timeout(command_name, &invocation.program);
+            }
+            result
+        } else {
+            self.execute_internal(invocation).await
+        }
+    }
+
+    /// Run attempts until one succeeds, the error isn't retryable or the
+    /// policy's attempts are used up
+    async fn execute_with_retry(
+        &self,
+        command_name: &str,
+        invocation: &Invocation,
+        policy: &RetryPolicy,
+    ) -> Result<CommandOutput> {
+        let max_attempts = policy.max_attempts.max(1);
+        let mut attempt = 1;
+
+        loop {
+            let span = info_span!("docker.attempt", attempt, max_attempts);
+            let result = self
+                .execute_attempt(command_name, invocation)
+                .instrument(span)
+                .await;
+
+            let error = match result {
+                Ok(output) => {
+                    if attempt > 1 {
+                        info!(attempt = attempt, "command succeeded after retry");
+                    }
+                    return Ok(output);
+                }
+                Err(error) => error,
+            };
+
+            if !policy.should_retry(&error) {
+                debug!(
+                    attempt = attempt,
+                    error_kind = ?error.kind(),
+                    "error is not retryable, failing immediately"
+                );
+                return Err(error);
+            }
+
+            if attempt >= max_attempts {
+                warn!(
+                    attempt = attempt,
+                    max_attempts = max_attempts,
+                    error_kind = ?error.kind(),
+                    "all retry attempts exhausted"
+                );
+                return Err(error);
+            }
+
+            let delay = policy.calculate_delay(attempt);
+            #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+            let delay_ms = u64::try_from(delay.as_millis()).unwrap_or(u64::MAX);
+            warn!(
+                attempt = attempt,
+                max_attempts = max_attempts,
+                error_kind = ?error.kind(),
+                error = %error,
+                delay_ms = delay_ms,
+                "attempt failed, will retry after delay"
+            );
+
+            if let Some(ref callback) = policy.on_retry {
+                callback(attempt, &error.to_string());
+            }
+
+            metrics::record_retry(command_name, &invocation.program);
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
     /// Internal method to execute a command without timeout
     #[cfg_attr(
         feature = "tracing",
         tracing::instrument(
             name = "docker.process",
-            skip(self, all_args),
+            skip(self, invocation),
             fields(
-                full_command = %format!("{} {}", runtime_command, all_args.join(" ")),
+                full_command = %invocation.command_line(),
             )
         )
     )]
-    async fn execute_internal(
-        &self,
-        runtime_command: &str,
-        all_args: &[String],
-    ) -> Result<CommandOutput> {
-        let mut command = TokioCommand::new(runtime_command);
-
-        // Set environment variables from platform info
-        if let Some(ref platform_info) = self.platform_info {
-            let env_count = platform_info.environment_vars().len();
-            if env_count > 0 {
-                trace!(
-                    env_vars = env_count,
-                    "setting platform environment variables"
-                );
-            }
-            for (key, value) in platform_info.environment_vars() {
-                command.env(key, value);
-            }
+    async fn execute_internal(&self, invocation: &Invocation) -> Result<CommandOutput> {
+        if !invocation.platform_env.is_empty() {
+            trace!(
+                env_vars = invocation.platform_env.len(),
+                "setting platform environment variables"
+            );
         }
 
-        trace!("spawning process");
-
-        let output = command
-            .args(all_args)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .await
-            .map_err(|e| {
-                error!(error = %e, "failed to spawn process");
-                Error::custom(format!(
-                    "Failed to execute {runtime_command} {}: {e}",
-                    all_args.first().unwrap_or(&String::new())
-                ))
-            })?;
+        let output = self.runner.run(invocation).await?;
 
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        let success = output.status.success();
-        let exit_code = output.status.code().unwrap_or(-1);
+        let success = output.success();
+        let exit_code = output.exit_code;
 
         trace!(
             exit_code = exit_code,
@@ -724,12 +1326,7 @@ impl CommandExecutor {
         );
 
         if !success {
-            return Err(Error::command_failed(
-                format!("{} {}", runtime_command, all_args.join(" ")),
-                exit_code,
-                stdout,
-                stderr,
-            ));
+            return Err(self.failure(invocation.command_line(), exit_code, &stdout, &stderr));
         }
 
         Ok(CommandOutput {
@@ -745,26 +1342,20 @@ impl CommandExecutor {
         feature = "tracing",
         tracing::instrument(
             name = "docker.timeout",
-            skip(self, all_args),
+            skip(self, invocation),
             fields(timeout_secs = timeout_duration.as_secs())
         )
     )]
     async fn execute_with_timeout(
         &self,
-        runtime_command: &str,
-        all_args: &[String],
+        invocation: &Invocation,
         timeout_duration: Duration,
     ) -> Result<CommandOutput> {
         use tokio::time::timeout;
 
         debug!("executing with timeout");
 
-        if let Ok(result) = timeout(
-            timeout_duration,
-            self.execute_internal(runtime_command, all_args),
-        )
-        .await
-        {
+        if let Ok(result) = timeout(timeout_duration, self.execute_internal(invocation)).await {
             result
         } else {
             warn!(
@@ -824,6 +1415,170 @@ impl Default for CommandExecutor {
     }
 }
 
+/// Source of bytes fed to a command's standard input
+///
+/// Attach a source with [`DockerCommand::with_stdin`] or
+/// [`CommandExecutor::stdin`]. Bytes and files can be replayed on every
+/// execution; an [`AsyncRead`] source is consumed by the first execution and
+/// later executions fail with [`Error::InvalidConfig`].
+///
+/// ```rust
+/// use docker_wrapper::StdinSource;
+///
+/// let from_bytes = StdinSource::from("FROM alpine\nRUN echo hi\n");
+/// let from_file = StdinSource::file("image.tar");
+/// ```
+#[derive(Clone)]
+pub struct StdinSource {
+    kind: StdinKind,
+}
+
+#[derive(Clone)]
+enum StdinKind {
+    Bytes(Arc<[u8]>),
+    File(PathBuf),
+    Reader(Arc<Mutex<Option<BoxedReader>>>),
+}
+
+type BoxedReader = Box<dyn AsyncRead + Send + Unpin>;
+
+/// Pending stdin data to be written once the child has been spawned.
+pub(crate) enum StdinFeed {
+    Bytes(Arc<[u8]>),
+    Reader(BoxedReader),
+}
+
+impl StdinSource {
+    /// Feed the given in-memory bytes
+    #[must_use]
+    pub fn bytes(data: impl Into<Vec<u8>>) -> Self {
+        Self {
+            kind: StdinKind::Bytes(data.into().into()),
+        }
+    }
+
+    /// Feed the contents of a file on the host
+    ///
+    /// The file is opened when the command is executed.
+    #[must_use]
+    pub fn file(path: impl Into<PathBuf>) -> Self {
+        Self {
+            kind: StdinKind::File(path.into()),
+        }
+    }
+
+    /// Feed everything read from an async reader
+    ///
+    /// The reader is consumed by the first execution of the command.
+    #[must_use]
+    pub fn reader(reader: impl AsyncRead + Send + Unpin + 'static) -> Self {
+        Self {
+            kind: StdinKind::Reader(Arc::new(Mutex::new(Some(Box::new(reader))))),
+        }
+    }
+
+    /// Get the in-memory bytes, if this source holds any
+    #[must_use]
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self.kind {
+            StdinKind::Bytes(ref data) => Some(data),
+            _ => None,
+        }
+    }
+
+    /// Configure `command`'s stdin and return anything that still needs to be
+    /// written after spawning.
+    pub(crate) fn attach(&self, command: &mut TokioCommand) -> Result<Option<StdinFeed>> {
+        match self.kind {
+            StdinKind::Bytes(ref data) => {
+                command.stdin(Stdio::piped());
+                Ok(Some(StdinFeed::Bytes(Arc::clone(data))))
+            }
+            StdinKind::File(ref path) => {
+                let file = std::fs::File::open(path).map_err(|e| Error::Io {
+                    message: format!("Failed to open stdin file {}: {e}", path.display()),
+                    source: e,
+                })?;
+                command.stdin(Stdio::from(file));
+                Ok(None)
+            }
+            StdinKind::Reader(ref slot) => {
+                let reader = slot
+                    .lock()
+                    .ok()
+                    .and_then(|mut guard| guard.take())
+                    .ok_or_else(|| {
+                        Error::invalid_config("stdin reader has already been consumed")
+                    })?;
+                command.stdin(Stdio::piped());
+                Ok(Some(StdinFeed::Reader(reader)))
+            }
+        }
+    }
+}
+
+impl StdinFeed {
+    /// Write the pending data and close the pipe.
+    ///
+    /// A child that exits without reading all of its input closes the pipe
+    /// early; that is reported through its exit status rather than here.
+    pub(crate) async fn write_to(self, mut pipe: ChildStdin) -> Result<()> {
+        let result = match self {
+            StdinFeed::Bytes(data) => pipe.write_all(&data).await,
+            StdinFeed::Reader(mut reader) => {
+                tokio::io::copy(&mut reader, &mut pipe).await.map(|_| ())
+            }
+        };
+        match result {
+            Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+            other => {
+                other?;
+                pipe.shutdown().await.or_else(|e| {
+                    if e.kind() == std::io::ErrorKind::BrokenPipe {
+                        Ok(())
+                    } else {
+                        Err(e.into())
+                    }
+                })
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for StdinSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            StdinKind::Bytes(ref data) => write!(f, "StdinSource::Bytes({} bytes)", data.len()),
+            StdinKind::File(ref path) => write!(f, "StdinSource::File({})", path.display()),
+            StdinKind::Reader(_) => write!(f, "StdinSource::Reader"),
+        }
+    }
+}
+
+impl From<Vec<u8>> for StdinSource {
+    fn from(data: Vec<u8>) -> Self {
+        Self::bytes(data)
+    }
+}
+
+impl From<&[u8]> for StdinSource {
+    fn from(data: &[u8]) -> Self {
+        Self::bytes(data)
+    }
+}
+
+impl From<String> for StdinSource {
+    fn from(data: String) -> Self {
+        Self::bytes(data)
+    }
+}
+
+impl From<&str> for StdinSource {
+    fn from(data: &str) -> Self {
+        Self::bytes(data)
+    }
+}
+
 /// Output from executing a Docker command
 #[derive(Debug, Clone)]
 pub struct CommandOutput {
@@ -863,10 +1618,47 @@ impl CommandOutput {
     }
 }
 
+/// Serialize a map with sorted keys, so equal maps always serialize the same
+pub(crate) fn serialize_sorted<S: Serializer>(
+    map: &HashMap<String, String>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.collect_map(map.iter().collect::<BTreeMap<_, _>>())
+}
+
+/// Serde adapter storing an executor as its raw arguments only
+///
+/// Runners, timeouts, retries and the other executor settings belong to the
+/// process that executes a command, so they are not part of its serialized
+/// form. A deserialized command gets a default executor.
+pub(crate) mod raw_args {
+    use super::CommandExecutor;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(
+        executor: &CommandExecutor,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        executor.raw_args.serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<CommandExecutor, D::Error> {
+        let mut executor = CommandExecutor::new();
+        executor.raw_args = Vec::deserialize(deserializer)?;
+        Ok(executor)
+    }
+}
+
 /// Helper for building environment variables
+///
+/// Serializes as a map from name to value, with `null` for variables passed
+/// through from the host.
 #[derive(Debug, Clone, Default)]
 pub struct EnvironmentBuilder {
     vars: HashMap<String, String>,
+    pass_through: Vec<String>,
 }
 
 impl EnvironmentBuilder {
@@ -879,14 +1671,32 @@ impl EnvironmentBuilder {
     /// Add an environment variable
     #[must_use]
     pub fn var(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
-        self.vars.insert(key.into(), value.into());
+        let key = key.into();
+        self.pass_through.retain(|k| *k != key);
+        self.vars.insert(key, value.into());
         self
     }
 
     /// Add multiple environment variables from a `HashMap`
     #[must_use]
     pub fn vars(mut self, vars: HashMap<String, String>) -> Self {
-        self.vars.extend(vars);
+        for (key, value) in vars {
+            self = self.var(key, value);
+        }
+        self
+    }
+
+    /// Pass a variable through from the environment of the docker client
+    ///
+    /// Emitted as `--env KEY`, so docker copies the value from its own
+    /// environment when the command runs.
+    #[must_use]
+    pub fn pass_through(mut self, key: impl Into<String>) -> Self {
+        let key = key.into();
+        self.vars.remove(&key);
+        if !self.pass_through.contains(&key) {
+            self.pass_through.push(key);
+        }
         self
     }
 
@@ -898,18 +1708,55 @@ impl EnvironmentBuilder {
             args.push("--env".to_string());
             args.push(format!("{key}={value}"));
         }
+        for key in &self.pass_through {
+            args.push("--env".to_string());
+            args.push(key.clone());
+        }
         args
     }
 
     /// Get the environment variables as a `HashMap`
+    ///
+    /// Variables passed through from the host are not included.
     #[must_use]
     pub fn as_map(&self) -> &HashMap<String, String> {
         &self.vars
     }
+
+    /// Get the variables passed through from the host
+    #[must_use]
+    pub fn pass_through_keys(&self) -> &[String] {
+        &self.pass_through
+    }
+}
+
+impl Serialize for EnvironmentBuilder {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let entries: BTreeMap<&str, Option<&str>> = self
+            .vars
+            .iter()
+            .map(|(key, value)| (key.as_str(), Some(value.as_str())))
+            .chain(self.pass_through.iter().map(|key| (key.as_str(), None)))
+            .collect();
+        entries.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for EnvironmentBuilder {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let entries = BTreeMap::<String, Option<String>>::deserialize(deserializer)?;
+        Ok(entries
+            .into_iter()
+            .fold(Self::new(), |env, (key, value)| match value {
+                Some(value) => env.var(key, value),
+                None => env.pass_through(key),
+            }))
+    }
 }
 
 /// Helper for building port mappings
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct PortBuilder {
     mappings: Vec<PortMapping>,
 }
@@ -962,6 +1809,13 @@ impl PortBuilder {
         self
     }
 
+    /// Add a fully specified port mapping
+    #[must_use]
+    pub fn mapping(mut self, mapping: PortMapping) -> Self {
+        self.mappings.push(mapping);
+        self
+    }
+
     /// Build the port arguments for Docker
     #[must_use]
     pub fn build_args(&self) -> Vec<String> {
@@ -981,7 +1835,8 @@ impl PortBuilder {
 }
 
 /// Port mapping configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct PortMapping {
     /// Host port (None for dynamic allocation)
     pub host_port: Option<u16>,
@@ -1000,28 +1855,66 @@ impl std::fmt::Display for PortMapping {
             Protocol::Udp => "/udp",
         };
 
-        if let Some(host_port) = self.host_port {
-            if let Some(host_ip) = self.host_ip {
-                write!(
-                    f,
-                    "{}:{}:{}{}",
-                    host_ip, host_port, self.container_port, protocol_suffix
-                )
-            } else {
-                write!(
-                    f,
-                    "{}:{}{}",
-                    host_port, self.container_port, protocol_suffix
-                )
-            }
-        } else {
-            write!(f, "{}{}", self.container_port, protocol_suffix)
+        match self.host_ip {
+            Some(std::net::IpAddr::V6(host_ip)) => write!(f, "[{host_ip}]:")?,
+            Some(host_ip) => write!(f, "{host_ip}:")?,
+            None => {}
+        }
+        match self.host_port {
+            Some(host_port) => write!(f, "{host_port}:")?,
+            None if self.host_ip.is_some() => write!(f, ":")?,
+            None => {}
         }
+        write!(f, "{}{}", self.container_port, protocol_suffix)
+    }
+}
+
+impl std::str::FromStr for PortMapping {
+    type Err = Error;
+
+    /// Parse the `--publish` syntax: `[host_ip:][host_port:]container_port[/protocol]`
+    ///
+    /// Port ranges are not supported.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::invalid_config(format!("invalid port mapping: {s}"));
+        let (ports, protocol) = match s.rsplit_once('/') {
+            Some((ports, "tcp")) => (ports, Protocol::Tcp),
+            Some((ports, "udp")) => (ports, Protocol::Udp),
+            Some(_) => return Err(invalid()),
+            None => (s, Protocol::Tcp),
+        };
+
+        let mut fields = ports.rsplitn(3, ':');
+        let container_port = fields
+            .next()
+            .and_then(|port| port.parse().ok())
+            .ok_or_else(invalid)?;
+        let host_port = match fields.next() {
+            Some("") | None => None,
+            Some(port) => Some(port.parse().map_err(|_| invalid())?),
+        };
+        let host_ip = match fields.next() {
+            Some(ip) => Some(
+                ip.trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .parse()
+                    .map_err(|_| invalid())?,
+            ),
+            None => None,
+        };
+
+        Ok(Self {
+            host_port,
+            container_port,
+            protocol,
+            host_ip,
+        })
     }
 }
 
 /// Network protocol for port mappings
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Protocol {
     /// TCP protocol
     Tcp,
@@ -1068,6 +1961,201 @@ mod tests {
         assert_eq!(executor_with_secs.timeout, Some(Duration::from_secs(30)));
     }
 
+    #[test]
+    fn test_stdin_source_bytes() {
+        let source = StdinSource::from("secret");
+        assert_eq!(source.as_bytes(), Some(&b"secret"[..]));
+        assert_eq!(format!("{source:?}"), "StdinSource::Bytes(6 bytes)");
+
+        let executor = CommandExecutor::new().stdin(vec![1u8, 2, 3]);
+        assert_eq!(
+            executor.stdin.as_ref().and_then(StdinSource::as_bytes),
+            Some(&[1u8, 2, 3][..])
+        );
+    }
+
+    #[test]
+    fn test_stdin_reader_consumed_once() {
+        let source = StdinSource::reader(&b"data"[..]);
+        let mut command = TokioCommand::new("docker");
+        assert!(source.attach(&mut command).unwrap().is_some());
+
+        let err = source.clone().attach(&mut command).err().unwrap();
+        assert!(matches!(err, Error::InvalidConfig { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_stdin_missing_file_fails_before_spawn() {
+        let executor = CommandExecutor::new().stdin(StdinSource::file("/nonexistent/stdin.tar"));
+        let err = executor
+            .execute_command("load", Vec::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Io { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_executor_uses_nerdctl_runtime() {
+        use crate::platform::Runtime;
+        use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
+
+        let fake = FakeRunner::new().on(ArgMatcher::Any, FakeResponse::success(""));
+        let executor = CommandExecutor::new()
+            .platform(PlatformInfo::for_runtime(Runtime::Nerdctl))
+            .runner(fake.clone());
+
+        executor
+            .execute_command("ps", vec!["--all".to_string()])
+            .await
+            .unwrap();
+        let err = executor
+            .execute_command("run", vec!["--link".to_string(), "db".to_string()])
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, Error::UnsupportedByRuntime { .. }));
+        let invocations = fake.invocations();
+        assert_eq!(invocations.len(), 1);
+        assert_eq!(invocations[0].program, "nerdctl");
+        assert_eq!(invocations[0].args, vec!["ps", "--all"]);
+    }
+
+    #[tokio::test]
+    async fn test_executor_runs_standalone_docker_compose() {
+        use crate::platform::Runtime;
+        use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
+
+        let fake = FakeRunner::new().on(ArgMatcher::Any, FakeResponse::success(""));
+        let executor = CommandExecutor::new()
+            .platform(
+                PlatformInfo::for_runtime(Runtime::Docker).with_compose_command(["docker-compose"]),
+            )
+            .connection(ConnectionConfig {
+                host: Some("tcp://remote:2375".to_string()),
+                ..ConnectionConfig::default()
+            })
+            .runner(fake.clone());
+
+        executor
+            .execute_command("compose", vec!["up".to_string(), "-d".to_string()])
+            .await
+            .unwrap();
+
+        let invocation = &fake.invocations()[0];
+        assert_eq!(invocation.program, "docker-compose");
+        assert_eq!(
+            invocation.args,
+            vec!["--host", "tcp://remote:2375", "up", "-d"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_recovers_from_transient_failure() {
+        use crate::debug::{BackoffStrategy, RetryPolicy};
+        use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
+        use crate::PullCommand;
+
+        let fake = FakeRunner::new().on_sequence(
+            ArgMatcher::prefix(["pull"]),
+            [
+                FakeResponse::failure(1, "net/http: TLS handshake timeout"),
+                FakeResponse::success("Status: Downloaded newer image for redis:7\n"),
+            ],
+        );
+        let retries = Arc::new(Mutex::new(Vec::new()));
+        // Default policy: network failures are retried without opting in
+        let policy = RetryPolicy::new()
+            .backoff(BackoffStrategy::Fixed(Duration::ZERO))
+            .on_retry({
+                let retries = Arc::clone(&retries);
+                move |attempt, _| retries.lock().unwrap().push(attempt)
+            });
+
+        let mut pull = PullCommand::new("redis:7");
+        pull.with_runner(fake.clone()).with_retry(policy);
+        let output = pull.execute().await.unwrap();
+
+        assert!(output.success);
+        assert_eq!(fake.invocations().len(), 2);
+        assert_eq!(*retries.lock().unwrap(), vec![1]);
+    }
+
+    #[tokio::test]
+    async fn test_retry_stops_on_permanent_error() {
+        use crate::debug::{BackoffStrategy, RetryPolicy};
+        use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
+
+        let fake = FakeRunner::new().on(
+            ArgMatcher::Any,
+            FakeResponse::failure(1, "Error response from daemon: No such image: nope:latest"),
+        );
+        let executor = CommandExecutor::new()
+            .runner(fake.clone())
+            .retry(RetryPolicy::new().backoff(BackoffStrategy::Fixed(Duration::ZERO)));
+
+        let err = executor
+            .execute_command("rmi", vec!["nope:latest".to_string()])
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, Error::ImageNotFound { .. }));
+        assert_eq!(fake.invocations().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_predicate_and_attempt_limit() {
+        use crate::debug::{BackoffStrategy, RetryPolicy};
+        use crate::error::ErrorKind;
+        use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
+
+        let fake = FakeRunner::new().on(
+            ArgMatcher::Any,
+            FakeResponse::failure(
+                125,
+                "docker: Error response from daemon: Conflict. The container name \"/web\" is already in use by container \"abc\".",
+            ),
+        );
+        let executor = CommandExecutor::new().runner(fake.clone()).retry(
+            RetryPolicy::new()
+                .max_attempts(4)
+                .backoff(BackoffStrategy::Fixed(Duration::ZERO))
+                .retry_on([ErrorKind::NameConflict]),
+        );
+
+        let err = executor
+            .execute_command("run", vec!["--name".to_string(), "web".to_string()])
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.kind(), ErrorKind::NameConflict);
+        assert_eq!(fake.invocations().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_failed_command_masks_secrets() {
+        use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
+
+        let fake = FakeRunner::new().on(ArgMatcher::Any, FakeResponse::failure(1, "boom"));
+        let executor = CommandExecutor::new().runner(fake);
+
+        let err = executor
+            .execute_command(
+                "run",
+                vec![
+                    "-e".to_string(),
+                    "DB_PASSWORD=hunter2".to_string(),
+                    "postgres".to_string(),
+                ],
+            )
+            .await
+            .unwrap_err();
+
+        let Error::CommandFailed { command, .. } = err else {
+            panic!("expected CommandFailed, got {err:?}");
+        };
+        assert_eq!(command, "docker run -e DB_PASSWORD=*** postgres");
+    }
+
     #[test]
     fn test_environment_builder() {
         let env = EnvironmentBuilder::new()
@@ -1080,6 +2168,27 @@ mod tests {
         assert!(args.contains(&"KEY2=value2".to_string()));
     }
 
+    #[test]
+    fn test_environment_builder_pass_through() {
+        let env = EnvironmentBuilder::new()
+            .var("MODE", "prod")
+            .pass_through("TOKEN")
+            .pass_through("TOKEN");
+
+        assert_eq!(env.build_args(), ["--env", "MODE=prod", "--env", "TOKEN"]);
+        assert_eq!(env.pass_through_keys(), ["TOKEN"]);
+        assert!(!env.as_map().contains_key("TOKEN"));
+
+        let json = serde_json::to_string(&env).unwrap();
+        assert_eq!(json, r#"{"MODE":"prod","TOKEN":null}"#);
+        let back: EnvironmentBuilder = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.build_args(), env.build_args());
+
+        let env = env.var("TOKEN", "set");
+        assert!(env.pass_through_keys().is_empty());
+        assert_eq!(env.as_map()["TOKEN"], "set");
+    }
+
     #[test]
     fn test_port_builder() {
         let ports = PortBuilder::new()
@@ -1121,6 +2230,31 @@ mod tests {
         assert_eq!(dynamic_mapping.to_string(), "443");
     }
 
+    #[test]
+    fn test_port_mapping_from_str() {
+        for spec in [
+            "8080:80",
+            "443",
+            "53:53/udp",
+            "127.0.0.1:5432:5432",
+            "127.0.0.1::80",
+            "[::1]:8080:80/udp",
+        ] {
+            let mapping: PortMapping = spec.parse().unwrap();
+            assert_eq!(mapping.to_string(), spec);
+        }
+
+        let mapping: PortMapping = "0.0.0.0:9000:90/tcp".parse().unwrap();
+        assert_eq!(mapping.host_port, Some(9000));
+        assert_eq!(mapping.container_port, 90);
+        assert_eq!(mapping.protocol, Protocol::Tcp);
+        assert_eq!(mapping.to_string(), "0.0.0.0:9000:90");
+
+        for spec in ["", "http", "8000-8010:80", "80/sctp", "host:80:80"] {
+            assert!(spec.parse::<PortMapping>().is_err(), "{spec}");
+        }
+    }
+
     #[test]
     fn test_command_output_helpers() {
         let output = CommandOutput {
@@ -4,6 +4,7 @@
 
 use super::{CommandExecutor, CommandOutput, DockerCommand};
 use crate::error::Result;
+use crate::stream::ExecSession;
 use async_trait::async_trait;
 
 /// Docker attach command builder
@@ -143,6 +144,24 @@ impl AttachCommand {
             container: self.container.clone(),
         })
     }
+
+    /// Attach as a session with handles to the container's streams
+    ///
+    /// Writes to the session's stdin reach the container's main process
+    /// unless [`no_stdin`](Self::no_stdin) is set; this requires a container
+    /// started with `--interactive` and without a TTY.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the command cannot be started or an interceptor
+    /// vetoes it
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a Tokio runtime.
+    pub fn session(&self) -> Result<ExecSession> {
+        crate::stream::spawn_session(&self.executor, self.build_command_args())
+    }
 }
 
 #[async_trait]
@@ -3,26 +3,33 @@
 //! This module provides a comprehensive implementation of the `docker build` command
 //! with support for all native options and an extensible architecture for any additional options.
 
+use super::buildkit::{self, BuildEvent, BuildSummary};
 use super::{CommandExecutor, DockerCommand};
-use crate::error::Result;
+use crate::error::{Error, Result};
+use crate::parse::ArgParser;
 use crate::stream::{OutputLine, StreamResult, StreamableCommand};
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
-use tokio::process::Command as TokioCommand;
 use tokio::sync::mpsc;
 
 /// Docker build command builder with fluent API
-#[derive(Debug, Clone)]
+///
+/// Serializes to its options; see [`crate::command`] for the format.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 #[allow(clippy::struct_excessive_bools)]
 pub struct BuildCommand {
     /// Build context (path, URL, or stdin)
     context: String,
     /// Command executor for extensibility
+    #[serde(rename = "extra_args", with = "crate::command::raw_args")]
     pub executor: CommandExecutor,
     /// Custom host-to-IP mappings
     add_hosts: Vec<String>,
     /// Build-time variables
+    #[serde(serialize_with = "crate::command::serialize_sorted")]
     build_args: HashMap<String, String>,
     /// Images to consider as cache sources
     cache_from: Vec<String>,
@@ -47,6 +54,7 @@ pub struct BuildCommand {
     /// Container isolation technology
     isolation: Option<String>,
     /// Set metadata for an image
+    #[serde(serialize_with = "crate::command::serialize_sorted")]
     labels: HashMap<String, String>,
     /// Memory limit
     memory: Option<String>,
@@ -1041,6 +1049,131 @@ impl BuildCommand {
     }
 }
 
+impl BuildCommand {
+    /// Build a build command from `docker build` arguments
+    ///
+    /// The leading `docker` and `build` (or `image build`, `buildx build`)
+    /// are optional, and flags may follow the context. See [`crate::parse`]
+    /// for the accepted syntax.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use docker_wrapper::BuildCommand;
+    ///
+    /// let build_cmd = BuildCommand::from_args(["build", "-t", "myapp:latest", "."]).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for unknown flags, missing or invalid values, and a
+    /// missing or repeated build context.
+    #[allow(clippy::too_many_lines)]
+    pub fn from_args<I, S>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let paths: &[&[&str]] = &[
+            &["build"],
+            &["image", "build"],
+            &["buildx", "build"],
+            &["builder", "build"],
+        ];
+        let mut parser = ArgParser::new("build", paths, args).interspersed();
+        let mut cmd = Self::new("");
+
+        while let Some(flag) = parser.next_flag() {
+            let p = &mut parser;
+            match flag.name.as_str() {
+                "--add-host" => cmd = cmd.add_host(p.value(&flag)?),
+                "--build-arg" => {
+                    let (key, value) = p.key_value(&flag)?;
+                    cmd = cmd.build_arg(key, value);
+                }
+                "--cache-from" => cmd = cmd.cache_from(p.value(&flag)?),
+                "-f" | "--file" => cmd = cmd.file(p.value(&flag)?),
+                "--no-cache" => cmd.no_cache = p.switch(&flag)?,
+                "--pull" => cmd.pull = p.switch(&flag)?,
+                "-q" | "--quiet" => cmd.quiet = p.switch(&flag)?,
+                "-t" | "--tag" => cmd = cmd.tag(p.value(&flag)?),
+                "--target" => cmd = cmd.target(p.value(&flag)?),
+
+                // Resources
+                "--cpu-period" => cmd = cmd.cpu_period(p.parse(&flag)?),
+                "--cpu-quota" => cmd = cmd.cpu_quota(p.parse(&flag)?),
+                "-c" | "--cpu-shares" => cmd = cmd.cpu_shares(p.parse(&flag)?),
+                "--cpuset-cpus" => cmd = cmd.cpuset_cpus(p.value(&flag)?),
+                "--cpuset-mems" => cmd = cmd.cpuset_mems(p.value(&flag)?),
+                "-m" | "--memory" => cmd = cmd.memory(p.value(&flag)?),
+                "--memory-swap" => cmd = cmd.memory_swap(p.value(&flag)?),
+                "--shm-size" => cmd = cmd.shm_size(p.value(&flag)?),
+
+                // Container
+                "--cgroup-parent" => cmd = cmd.cgroup_parent(p.value(&flag)?),
+                "--compress" => cmd.compress = p.switch(&flag)?,
+                "--disable-content-trust" => cmd.disable_content_trust = p.switch(&flag)?,
+                "--force-rm" => cmd.force_rm = p.switch(&flag)?,
+                "--iidfile" => cmd = cmd.iidfile(p.value(&flag)?),
+                "--isolation" => cmd = cmd.isolation(p.value(&flag)?),
+                "--network" => cmd = cmd.network(p.value(&flag)?),
+                "--platform" => cmd = cmd.platform(p.value(&flag)?),
+                "--rm" => cmd.rm = p.switch(&flag)?,
+                "--security-opt" => cmd = cmd.security_opt(p.value(&flag)?),
+                "--ulimit" => cmd = cmd.ulimit(p.value(&flag)?),
+
+                // Metadata
+                "--label" => {
+                    let (key, value) = p.key_value(&flag)?;
+                    cmd = cmd.label(key, value);
+                }
+                "--annotation" => cmd = cmd.annotation(p.value(&flag)?),
+                "--metadata-file" => cmd = cmd.metadata_file(p.value(&flag)?),
+
+                // Buildx
+                "--allow" => cmd = cmd.allow(p.value(&flag)?),
+                "--attest" => cmd = cmd.attest(p.value(&flag)?),
+                "--build-context" => cmd = cmd.build_context(p.value(&flag)?),
+                "--builder" => cmd = cmd.builder(p.value(&flag)?),
+                "--cache-to" => cmd = cmd.cache_to(p.value(&flag)?),
+                "--call" => cmd = cmd.call(p.value(&flag)?),
+                "--check" => cmd.check = p.switch(&flag)?,
+                "--load" => cmd.load = p.switch(&flag)?,
+                "--no-cache-filter" => cmd = cmd.no_cache_filter(p.value(&flag)?),
+                "--progress" => cmd = cmd.progress(p.value(&flag)?),
+                "--provenance" => cmd = cmd.provenance(p.value(&flag)?),
+                "--push" => cmd.push = p.switch(&flag)?,
+                "--sbom" => cmd = cmd.sbom(p.value(&flag)?),
+                "--secret" => cmd = cmd.secret(p.value(&flag)?),
+                "--ssh" => cmd = cmd.ssh(p.value(&flag)?),
+
+                _ => return Err(p.unknown(&flag)),
+            }
+        }
+
+        let mut positionals = parser.finish().into_iter();
+        cmd.context = positionals
+            .next()
+            .ok_or_else(|| Error::invalid_config("docker build requires a build context"))?;
+        if let Some(extra) = positionals.next() {
+            return Err(Error::invalid_config(format!(
+                "docker build accepts a single build context, got another: {extra}"
+            )));
+        }
+
+        Ok(cmd)
+    }
+}
+
+impl std::str::FromStr for BuildCommand {
+    type Err = Error;
+
+    /// Parse a `docker build ...` shell line
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_args(crate::parse::split(s)?)
+    }
+}
+
 impl Default for BuildCommand {
     fn default() -> Self {
         Self::new(".")
@@ -1355,23 +1488,13 @@ impl StreamableCommand for BuildCommand {
     where
         F: FnMut(OutputLine) + Send + 'static,
     {
-        let mut cmd = TokioCommand::new("docker");
-
-        for arg in self.build_command_args() {
-            cmd.arg(arg);
-        }
-
-        crate::stream::stream_command(cmd, handler, "build").await
+        crate::stream::stream_command(&self.executor, self.build_command_args(), handler, "build")
+            .await
     }
 
     async fn stream_channel(&self) -> Result<(mpsc::Receiver<OutputLine>, StreamResult)> {
-        let mut cmd = TokioCommand::new("docker");
-
-        for arg in self.build_command_args() {
-            cmd.arg(arg);
-        }
-
-        crate::stream::stream_command_channel(cmd, "build").await
+        crate::stream::stream_command_channel(&self.executor, self.build_command_args(), "build")
+            .await
     }
 }
 
@@ -1402,6 +1525,31 @@ impl BuildCommand {
     {
         <Self as StreamableCommand>::stream(self, handler).await
     }
+
+    /// Build with `BuildKit`'s `rawjson` progress, passing typed events to
+    /// `on_event` as they happen
+    ///
+    /// Overrides [`progress`](Self::progress). Returns each step's timing and
+    /// cache status; see [`crate::command::buildkit`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the build fails; the error carries `BuildKit`'s
+    /// error message rather than the progress lines
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a Tokio runtime.
+    pub async fn run_with_progress<F>(&self, on_event: F) -> Result<BuildSummary>
+    where
+        F: FnMut(&BuildEvent) + Send,
+    {
+        let mut summary = buildkit::run_with_progress(self, on_event).await?;
+        if self.quiet {
+            summary.output.image_id = Some(summary.output.stdout.trim().to_string());
+        }
+        Ok(summary)
+    }
 }
 
 #[cfg(test)]
@@ -1654,4 +1802,192 @@ mod tests {
         assert!(args.contains(&"--ssh".to_string()));
         assert!(args.contains(&"default".to_string()));
     }
+
+    #[test]
+    fn test_build_command_from_args_round_trip() {
+        let cmd = BuildCommand::new("./app")
+            .add_host("registry:10.0.0.2")
+            .build_arg("VERSION", "1.2.3")
+            .cache_from("myapp:cache")
+            .file("docker/Dockerfile")
+            .no_cache()
+            .pull()
+            .quiet()
+            .tags(vec!["myapp:latest".to_string(), "myapp:1.2.3".to_string()])
+            .target("release")
+            .cpu_period(100_000)
+            .cpu_quota(50_000)
+            .cpu_shares(512)
+            .cpuset_cpus("0-1")
+            .cpuset_mems("0")
+            .memory("2g")
+            .memory_swap("4g")
+            .shm_size("128m")
+            .cgroup_parent("/build")
+            .compress()
+            .disable_content_trust()
+            .force_rm()
+            .iidfile("/tmp/iid")
+            .isolation("default")
+            .network("host")
+            .platform("linux/arm64")
+            .no_rm()
+            .security_opt("seccomp=unconfined")
+            .ulimit("nofile=1024:1024")
+            .label("team", "platform")
+            .annotation("org.opencontainers.image.title=myapp")
+            .metadata_file("/tmp/metadata.json")
+            .allow("network.host")
+            .attest("type=sbom")
+            .build_context("shared=../shared")
+            .builder("ci")
+            .cache_to("type=inline")
+            .call("build")
+            .check()
+            .load()
+            .no_cache_filter("deps")
+            .progress("plain")
+            .provenance("mode=max")
+            .push()
+            .sbom("generator=image")
+            .secret("id=npm,src=.npmrc")
+            .ssh("default");
+        let args = cmd.build_command_args();
+
+        let parsed = BuildCommand::from_args(args.clone()).unwrap();
+        assert_eq!(parsed.build_command_args(), args);
+    }
+
+    #[test]
+    fn test_build_command_parse_shell_line() {
+        let cmd: BuildCommand =
+            "docker buildx build . -t app:dev -f Dockerfile.dev --build-arg A=1 --rm=false"
+                .parse()
+                .unwrap();
+
+        assert_eq!(
+            cmd.build_command_args(),
+            [
+                "build",
+                "--build-arg",
+                "A=1",
+                "--file",
+                "Dockerfile.dev",
+                "--tag",
+                "app:dev",
+                "--rm=false",
+                "."
+            ]
+        );
+        assert!("docker build -t app".parse::<BuildCommand>().is_err());
+        assert!("docker build . other".parse::<BuildCommand>().is_err());
+        assert!("docker build -o out .".parse::<BuildCommand>().is_err());
+        assert!("docker build --build-arg HTTP_PROXY ."
+            .parse::<BuildCommand>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_build_command_serde_round_trip() {
+        let cmd = BuildCommand::new("./app")
+            .tag("myapp:latest")
+            .build_arg("VERSION", "1.2.3")
+            .build_arg("BASE", "alpine")
+            .label("team", "platform")
+            .no_rm()
+            .secret("id=npm,src=.npmrc");
+
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains(r#""build_args":{"BASE":"alpine","VERSION":"1.2.3"}"#));
+
+        let restored: BuildCommand = serde_json::from_str(&json).unwrap();
+        let mut expected = cmd.build_command_args();
+        let mut actual = restored.build_command_args();
+        expected.sort();
+        actual.sort();
+        assert_eq!(actual, expected);
+
+        let minimal: BuildCommand = serde_json::from_str(r#"{"tags": ["app"]}"#).unwrap();
+        assert_eq!(minimal.build_command_args(), ["build", "--tag", "app", "."]);
+    }
+
+    /// Serialized form of the command in `test_build_command_serde_schema`
+    const BUILD_SCHEMA: &str = r#"{
+  "context": "./app",
+  "extra_args": [],
+  "add_hosts": [],
+  "build_args": {
+    "VERSION": "1"
+  },
+  "cache_from": [],
+  "cgroup_parent": null,
+  "compress": false,
+  "cpu_period": null,
+  "cpu_quota": null,
+  "cpu_shares": null,
+  "cpuset_cpus": null,
+  "cpuset_mems": null,
+  "disable_content_trust": false,
+  "file": null,
+  "force_rm": false,
+  "iidfile": null,
+  "isolation": null,
+  "labels": {
+    "team": "platform"
+  },
+  "memory": null,
+  "memory_swap": null,
+  "network": null,
+  "no_cache": false,
+  "platform": null,
+  "pull": false,
+  "quiet": false,
+  "rm": true,
+  "security_opts": [],
+  "shm_size": null,
+  "tags": [
+    "app:1"
+  ],
+  "target": null,
+  "ulimits": [],
+  "allow": [],
+  "annotations": [],
+  "attestations": [],
+  "build_contexts": [],
+  "builder": null,
+  "cache_to": [],
+  "call": null,
+  "check": false,
+  "load": false,
+  "metadata_file": null,
+  "no_cache_filter": [],
+  "progress": null,
+  "provenance": null,
+  "push": false,
+  "sbom": null,
+  "secrets": [],
+  "ssh": []
+}"#;
+
+    #[test]
+    fn test_build_command_serde_schema() {
+        let cmd = BuildCommand::new("./app")
+            .tag("app:1")
+            .build_arg("VERSION", "1")
+            .label("team", "platform");
+
+        assert_eq!(
+            serde_json::to_value(&cmd).unwrap(),
+            serde_json::from_str::<serde_json::Value>(BUILD_SCHEMA).unwrap()
+        );
+
+        let err = serde_json::from_str::<BuildCommand>(r#"{"tag": ["app"]}"#).unwrap_err();
+        assert!(err.to_string().contains("unknown field `tag`"), "{err}");
+    }
+
+    #[test]
+    fn test_build_command_serde_fixture() {
+        let cmd: BuildCommand = serde_json::from_str(BUILD_SCHEMA).unwrap();
+        assert_eq!(serde_json::to_string_pretty(&cmd).unwrap(), BUILD_SCHEMA);
+    }
 }
@@ -0,0 +1,683 @@
+//! `BuildKit` progress of image builds.
+//!
+//! With `--progress=rawjson`, `BuildKit` reports the build graph as JSON lines:
+//! every step (a *vertex*) with its start and completion times, whether it
+//! came from the cache and why it failed, plus progress and log output of
+//! running steps. [`BuildProgress`] turns these lines into typed
+//! [`BuildEvent`]s and keeps the state of each [`BuildStep`].
+//!
+//! [`BuildCommand::run_with_progress`](crate::BuildCommand::run_with_progress)
+//! runs a build this way and returns a [`BuildSummary`], which makes slow and
+//! cache-busting steps easy to find:
+//!
+//! ```rust,no_run
+//! use docker_wrapper::{BuildCommand, BuildEvent};
+//!
+//! # async fn example() -> docker_wrapper::Result<()> {
+//! let summary = BuildCommand::new(".")
+//!     .tag("app:ci")
+//!     .run_with_progress(|event: &BuildEvent| {
+//!         if let BuildEvent::StepFailed { name, error, .. } = event {
+//!             eprintln!("{name} failed: {error}");
+//!         }
+//!     })
+//!     .await?;
+//!
+//! if let Some(step) = summary.cache_misses().next() {
+//!     println!("cache busted at {}", step.name);
+//! }
+//! for step in summary.slowest(3) {
+//!     println!("{:?} {}", step.duration(), step.name);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use super::build::{BuildCommand, BuildOutput};
+use super::parse_util::parse_timestamp;
+use super::DockerCommand;
+use crate::error::Result;
+use crate::stream::OutputLine;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+
+/// Change in a build reported by `BuildKit`
+///
+/// Steps are identified by the digest of their vertex; their names need not
+/// be unique.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildEvent {
+    /// A step started
+    StepStarted {
+        /// Vertex digest
+        step: String,
+        /// Step name, e.g. `[2/4] RUN cargo build --release`
+        name: String,
+    },
+    /// A step finished
+    StepCompleted {
+        /// Vertex digest
+        step: String,
+        /// Step name
+        name: String,
+        /// Whether the result came from the build cache
+        cached: bool,
+        /// Time from start to completion
+        duration: Option<Duration>,
+    },
+    /// A step failed
+    StepFailed {
+        /// Vertex digest
+        step: String,
+        /// Step name
+        name: String,
+        /// Error reported by `BuildKit`
+        error: String,
+    },
+    /// Progress of a task within a step, such as a layer being pulled or
+    /// the build context being transferred
+    Status {
+        /// Vertex digest
+        step: String,
+        /// Task, e.g. `transferring context` or `sha256:...`
+        id: String,
+        /// Units done so far, usually bytes
+        current: u64,
+        /// Units in total, when known
+        total: Option<u64>,
+        /// Whether the task has finished
+        completed: bool,
+    },
+    /// A line printed by a step
+    Log {
+        /// Vertex digest
+        step: String,
+        /// Step name
+        name: String,
+        /// The line, on the stream it was printed to
+        line: OutputLine,
+    },
+    /// A warning about the build, such as a Dockerfile lint
+    Warning {
+        /// Vertex digest, if the warning belongs to a step
+        step: Option<String>,
+        /// Warning text
+        message: String,
+    },
+}
+
+/// State of one step of a build
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildStep {
+    /// Vertex digest
+    pub digest: String,
+    /// Step name, e.g. `[2/4] RUN cargo build --release`
+    pub name: String,
+    /// When the step started
+    pub started: Option<SystemTime>,
+    /// When the step completed or failed
+    pub completed: Option<SystemTime>,
+    /// Whether the result came from the build cache
+    pub cached: bool,
+    /// Error, if the step failed
+    pub error: Option<String>,
+}
+
+impl BuildStep {
+    /// Time from start to completion, once both are known
+    #[must_use]
+    pub fn duration(&self) -> Option<Duration> {
+        self.completed?.duration_since(self.started?).ok()
+    }
+
+    /// Whether the step is one of `BuildKit`'s own, such as loading the
+    /// Dockerfile, rather than a build instruction
+    #[must_use]
+    pub fn is_internal(&self) -> bool {
+        self.name.starts_with("[internal]")
+    }
+}
+
+/// Tracks the steps of a build from its `rawjson` progress lines
+#[derive(Debug, Clone, Default)]
+pub struct BuildProgress {
+    steps: Vec<BuildStep>,
+    /// Unterminated log output per step and stream
+    partial_logs: HashMap<(String, i32), String>,
+    image_id: Option<String>,
+}
+
+impl BuildProgress {
+    /// Create progress with no steps
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply one line of `rawjson` output, returning the events it contains
+    ///
+    /// Lines that are not `BuildKit` status messages return no events.
+    pub fn update(&mut self, line: &str) -> Vec<BuildEvent> {
+        let Ok(status) = serde_json::from_str::<SolveStatus>(line.trim()) else {
+            return Vec::new();
+        };
+        let mut events = Vec::new();
+        for vertex in status.vertexes {
+            self.update_vertex(vertex, &mut events);
+        }
+        for status in status.statuses {
+            if let Some(image) = status.id.strip_prefix("writing image ") {
+                self.image_id = Some(image.trim().to_string());
+            }
+            events.push(BuildEvent::Status {
+                step: status.vertex,
+                id: status.id,
+                current: status.current,
+                total: (status.total > 0).then_some(status.total),
+                completed: status.completed.is_some(),
+            });
+        }
+        for log in status.logs {
+            let text = decode_base64(log.data.as_deref().unwrap_or_default())
+                .map(|data| String::from_utf8_lossy(&data).into_owned())
+                .unwrap_or_default();
+            let partial = self
+                .partial_logs
+                .entry((log.vertex.clone(), log.stream))
+                .or_default();
+            partial.push_str(&text);
+            let Some(end) = partial.rfind('\n') else {
+                continue;
+            };
+            let complete: String = partial.drain(..=end).collect();
+            let name = self.name_of(&log.vertex);
+            for line in complete.lines() {
+                events.push(log_event(&log.vertex, &name, log.stream, line));
+            }
+        }
+        for warning in status.warnings {
+            let message = decode_base64(warning.short.as_deref().unwrap_or_default())
+                .map(|data| String::from_utf8_lossy(&data).trim().to_string())
+                .unwrap_or_default();
+            events.push(BuildEvent::Warning {
+                step: (!warning.vertex.is_empty()).then_some(warning.vertex),
+                message,
+            });
+        }
+        events
+    }
+
+    fn update_vertex(&mut self, vertex: Vertex, events: &mut Vec<BuildEvent>) {
+        let index = if let Some(index) = self.steps.iter().position(|s| s.digest == vertex.digest) {
+            index
+        } else {
+            self.steps.push(BuildStep {
+                digest: vertex.digest.clone(),
+                name: vertex.name.clone(),
+                started: None,
+                completed: None,
+                cached: false,
+                error: None,
+            });
+            self.steps.len() - 1
+        };
+        let step = &mut self.steps[index];
+        if !vertex.name.is_empty() {
+            step.name = vertex.name;
+        }
+        step.cached |= vertex.cached;
+
+        if step.started.is_none() {
+            if let Some(started) = vertex.started.as_deref().and_then(parse_timestamp) {
+                step.started = Some(started);
+                events.push(BuildEvent::StepStarted {
+                    step: step.digest.clone(),
+                    name: step.name.clone(),
+                });
+            }
+        }
+        if step.completed.is_some() {
+            return;
+        }
+        let Some(completed) = vertex.completed.as_deref().and_then(parse_timestamp) else {
+            return;
+        };
+        step.completed = Some(completed);
+
+        // Output printed without a trailing newline
+        let digest = step.digest.clone();
+        let name = step.name.clone();
+        for stream in [1, 2] {
+            if let Some(rest) = self.partial_logs.remove(&(digest.clone(), stream)) {
+                if !rest.is_empty() {
+                    events.push(log_event(&digest, &name, stream, &rest));
+                }
+            }
+        }
+
+        let step = &mut self.steps[index];
+        if vertex.error.is_empty() {
+            events.push(BuildEvent::StepCompleted {
+                step: digest,
+                name,
+                cached: step.cached,
+                duration: step.duration(),
+            });
+        } else {
+            step.error = Some(vertex.error.clone());
+            events.push(BuildEvent::StepFailed {
+                step: digest,
+                name,
+                error: vertex.error,
+            });
+        }
+    }
+
+    fn name_of(&self, digest: &str) -> String {
+        self.steps
+            .iter()
+            .find(|step| step.digest == digest)
+            .map(|step| step.name.clone())
+            .unwrap_or_default()
+    }
+
+    /// Steps in the order `BuildKit` first reported them
+    #[must_use]
+    pub fn steps(&self) -> &[BuildStep] {
+        &self.steps
+    }
+
+    /// ID of the image written by the build, once reported
+    #[must_use]
+    pub fn image_id(&self) -> Option<&str> {
+        self.image_id.as_deref()
+    }
+}
+
+fn log_event(step: &str, name: &str, stream: i32, line: &str) -> BuildEvent {
+    let line = line.to_string();
+    BuildEvent::Log {
+        step: step.to_string(),
+        name: name.to_string(),
+        line: if stream == 2 {
+            OutputLine::Stderr(line)
+        } else {
+            OutputLine::Stdout(line)
+        },
+    }
+}
+
+/// Result of [`BuildCommand::run_with_progress`]
+#[derive(Debug, Clone)]
+pub struct BuildSummary {
+    /// Output of the build; stderr holds the `rawjson` progress lines
+    pub output: BuildOutput,
+    /// Steps in the order `BuildKit` first reported them
+    pub steps: Vec<BuildStep>,
+}
+
+impl BuildSummary {
+    /// Time from the first step starting to the last one completing
+    #[must_use]
+    pub fn total_duration(&self) -> Option<Duration> {
+        let started = self.steps.iter().filter_map(|s| s.started).min()?;
+        let completed = self.steps.iter().filter_map(|s| s.completed).max()?;
+        completed.duration_since(started).ok()
+    }
+
+    /// The `count` steps that took longest, slowest first
+    #[must_use]
+    pub fn slowest(&self, count: usize) -> Vec<&BuildStep> {
+        let mut steps: Vec<&BuildStep> = self
+            .steps
+            .iter()
+            .filter(|step| step.duration().is_some())
+            .collect();
+        steps.sort_by_key(|step| std::cmp::Reverse(step.duration()));
+        steps.truncate(count);
+        steps
+    }
+
+    /// Build instructions that ran instead of coming from the cache, in
+    /// build order
+    ///
+    /// The first one is where the cache was busted.
+    pub fn cache_misses(&self) -> impl Iterator<Item = &BuildStep> {
+        self.steps
+            .iter()
+            .filter(|step| !step.cached && !step.is_internal() && step.started.is_some())
+    }
+
+    /// Number of steps that came from the cache
+    #[must_use]
+    pub fn cached_steps(&self) -> usize {
+        self.steps.iter().filter(|step| step.cached).count()
+    }
+}
+
+/// Run `command` with `rawjson` progress, passing events to `on_event`
+pub(crate) async fn run_with_progress<F>(
+    command: &BuildCommand,
+    mut on_event: F,
+) -> Result<BuildSummary>
+where
+    F: FnMut(&BuildEvent) + Send,
+{
+    let command = command.clone().progress("rawjson");
+    let (mut lines, completion) = command.stream_output()?;
+    let mut progress = BuildProgress::new();
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+    let mut messages = Vec::new();
+
+    while let Some(line) = lines.next_line().await {
+        match line {
+            OutputLine::Stdout(text) => stdout.push(text),
+            OutputLine::Stderr(text) => {
+                if text.trim_start().starts_with('{') {
+                    for event in progress.update(&text) {
+                        on_event(&event);
+                    }
+                } else if !text.trim().is_empty() {
+                    messages.push(text.clone());
+                }
+                stderr.push(text);
+            }
+        }
+    }
+
+    let status = completion.await?;
+    let stdout = stdout.join("\n");
+    if !status.success {
+        if messages.is_empty() {
+            messages.extend(progress.steps().iter().filter_map(|s| s.error.clone()));
+        }
+        return Err(command.executor.failure(
+            "docker build".to_string(),
+            status.exit_code,
+            &stdout,
+            &messages.join("\n"),
+        ));
+    }
+
+    Ok(BuildSummary {
+        output: BuildOutput {
+            stdout,
+            stderr: stderr.join("\n"),
+            exit_code: status.exit_code,
+            image_id: progress.image_id().map(str::to_string),
+        },
+        steps: progress.steps,
+    })
+}
+
+/// One `rawjson` line, `BuildKit`'s `SolveStatus`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct SolveStatus {
+    vertexes: Vec<Vertex>,
+    statuses: Vec<VertexStatus>,
+    logs: Vec<VertexLog>,
+    warnings: Vec<VertexWarning>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Vertex {
+    digest: String,
+    name: String,
+    started: Option<String>,
+    completed: Option<String>,
+    cached: bool,
+    error: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct VertexStatus {
+    id: String,
+    vertex: String,
+    current: u64,
+    total: u64,
+    completed: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct VertexLog {
+    vertex: String,
+    stream: i32,
+    /// Base64, as Go encodes bytes
+    data: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct VertexWarning {
+    vertex: String,
+    /// Base64, as Go encodes bytes
+    short: Option<String>,
+}
+
+/// Decode standard base64
+#[allow(clippy::cast_possible_truncation)]
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len() / 4 * 3);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in text.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => break,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    const LOAD: &str = r#"{"vertexes":[{"digest":"sha256:load","name":"[internal] load build definition from Dockerfile","started":"2024-05-01T12:00:00Z","completed":"2024-05-01T12:00:00.05Z"}]}"#;
+    const BASE: &str = r#"{"vertexes":[{"digest":"sha256:base","name":"[1/3] FROM docker.io/library/alpine:3.19","started":"2024-05-01T12:00:01Z","completed":"2024-05-01T12:00:01Z","cached":true}]}"#;
+    const COPY_START: &str = r#"{"vertexes":[{"digest":"sha256:copy","inputs":["sha256:base"],"name":"[2/3] COPY . /src","started":"2024-05-01T12:00:01.5Z"}],"statuses":[{"id":"transferring context","vertex":"sha256:copy","current":2048,"total":4096,"ts":"2024-05-01T12:00:01.6Z"}]}"#;
+    const COPY_DONE: &str = r#"{"vertexes":[{"digest":"sha256:copy","name":"[2/3] COPY . /src","started":"2024-05-01T12:00:01.5Z","completed":"2024-05-01T12:00:03Z"}]}"#;
+    // "compiling\nlinked" and "warning: unused" on stderr, without a newline
+    const RUN_LOGS: &str = r#"{"vertexes":[{"digest":"sha256:run","name":"[3/3] RUN make","started":"2024-05-01T14:00:03+02:00"}],"logs":[{"vertex":"sha256:run","stream":1,"data":"Y29tcGlsaW5nCmxp","ts":"2024-05-01T12:00:04Z"},{"vertex":"sha256:run","stream":1,"data":"bmtlZAo=","ts":"2024-05-01T12:00:05Z"},{"vertex":"sha256:run","stream":2,"data":"d2FybmluZzogdW51c2Vk","ts":"2024-05-01T12:00:05Z"}]}"#;
+    const RUN_DONE: &str = r#"{"vertexes":[{"digest":"sha256:run","name":"[3/3] RUN make","started":"2024-05-01T12:00:03Z","completed":"2024-05-01T12:00:13Z"}],"statuses":[{"id":"writing image sha256:0123abcd","vertex":"sha256:export","current":0,"completed":"2024-05-01T12:00:14Z"}]}"#;
+
+    fn events(progress: &mut BuildProgress, lines: &[&str]) -> Vec<BuildEvent> {
+        lines
+            .iter()
+            .flat_map(|line| progress.update(line))
+            .collect()
+    }
+
+    #[test]
+    fn test_decode_base64() {
+        assert_eq!(decode_base64("aGVsbG8=").unwrap(), b"hello");
+        assert_eq!(decode_base64("aGk").unwrap(), b"hi");
+        assert_eq!(decode_base64("").unwrap(), b"");
+        assert!(decode_base64("not base64!").is_none());
+    }
+
+    #[test]
+    fn test_steps_start_complete_and_cache() {
+        let mut progress = BuildProgress::new();
+        let events = events(&mut progress, &[LOAD, BASE, COPY_START, COPY_DONE]);
+
+        assert_eq!(
+            events[2..4],
+            [
+                BuildEvent::StepStarted {
+                    step: "sha256:base".to_string(),
+                    name: "[1/3] FROM docker.io/library/alpine:3.19".to_string(),
+                },
+                BuildEvent::StepCompleted {
+                    step: "sha256:base".to_string(),
+                    name: "[1/3] FROM docker.io/library/alpine:3.19".to_string(),
+                    cached: true,
+                    duration: Some(Duration::ZERO),
+                },
+            ]
+        );
+        assert_eq!(
+            events[5],
+            BuildEvent::Status {
+                step: "sha256:copy".to_string(),
+                id: "transferring context".to_string(),
+                current: 2048,
+                total: Some(4096),
+                completed: false,
+            }
+        );
+        assert_eq!(
+            events[6],
+            BuildEvent::StepCompleted {
+                step: "sha256:copy".to_string(),
+                name: "[2/3] COPY . /src".to_string(),
+                cached: false,
+                duration: Some(Duration::from_millis(1500)),
+            }
+        );
+        assert_eq!(events.len(), 7);
+        assert_eq!(progress.steps().len(), 3);
+    }
+
+    #[test]
+    fn test_logs_are_split_into_lines() {
+        let mut progress = BuildProgress::new();
+        let events = events(&mut progress, &[RUN_LOGS, RUN_DONE]);
+        let logs: Vec<&OutputLine> = events
+            .iter()
+            .filter_map(|event| match event {
+                BuildEvent::Log { name, line, .. } => {
+                    assert_eq!(name, "[3/3] RUN make");
+                    Some(line)
+                }
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(
+            logs,
+            [
+                &OutputLine::Stdout("compiling".to_string()),
+                &OutputLine::Stdout("linked".to_string()),
+                &OutputLine::Stderr("warning: unused".to_string()),
+            ]
+        );
+        assert_eq!(progress.image_id(), Some("sha256:0123abcd"));
+    }
+
+    #[test]
+    fn test_failed_step() {
+        let mut progress = BuildProgress::new();
+        let events = progress.update(
+            r#"{"vertexes":[{"digest":"sha256:run","name":"[3/3] RUN make","started":"2024-05-01T12:00:03Z","completed":"2024-05-01T12:00:04Z","error":"process \"/bin/sh -c make\" did not complete successfully: exit code: 2"}],"warnings":[{"vertex":"sha256:run","level":1,"short":"SnNvbkFyZ3NSZWNvbW1lbmRlZA=="}]}"#,
+        );
+
+        assert!(matches!(
+            &events[1],
+            BuildEvent::StepFailed { error, .. } if error.ends_with("exit code: 2")
+        ));
+        assert_eq!(
+            events[2],
+            BuildEvent::Warning {
+                step: Some("sha256:run".to_string()),
+                message: "JsonArgsRecommended".to_string(),
+            }
+        );
+        assert!(progress.steps()[0].error.is_some());
+        assert!(progress.update("#5 [2/3] COPY . /src").is_empty());
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut progress = BuildProgress::new();
+        events(
+            &mut progress,
+            &[LOAD, BASE, COPY_START, COPY_DONE, RUN_LOGS, RUN_DONE],
+        );
+        let summary = BuildSummary {
+            output: BuildOutput {
+                stdout: String::new(),
+                stderr: String::new(),
+                exit_code: 0,
+                image_id: None,
+            },
+            steps: progress.steps,
+        };
+
+        assert_eq!(summary.total_duration(), Some(Duration::from_secs(13)));
+        let slowest: Vec<&str> = summary.slowest(2).iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(slowest, ["[3/3] RUN make", "[2/3] COPY . /src"]);
+        let misses: Vec<&str> = summary.cache_misses().map(|s| s.name.as_str()).collect();
+        assert_eq!(misses, ["[2/3] COPY . /src", "[3/3] RUN make"]);
+        assert_eq!(summary.cached_steps(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_run_with_progress() {
+        use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
+
+        let fake = FakeRunner::new().on(
+            ArgMatcher::Any,
+            FakeResponse::success("")
+                .with_stderr(format!("{BASE}\n{COPY_START}\n{COPY_DONE}\n{RUN_DONE}\n")),
+        );
+        let mut build = BuildCommand::new(".").tag("app:ci");
+        build.with_runner(fake.clone());
+
+        let mut completed = Vec::new();
+        let summary = build
+            .run_with_progress(|event| {
+                if let BuildEvent::StepCompleted { name, .. } = event {
+                    completed.push(name.clone());
+                }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(completed.len(), 3);
+        assert_eq!(summary.steps.len(), 3);
+        assert_eq!(summary.output.image_id.as_deref(), Some("sha256:0123abcd"));
+        let args = &fake.invocations()[0].args;
+        let progress = args.iter().position(|arg| arg == "--progress").unwrap();
+        assert_eq!(args[progress + 1], "rawjson");
+    }
+
+    #[tokio::test]
+    async fn test_run_with_progress_failure() {
+        use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
+
+        let mut build = BuildCommand::new(".");
+        build.with_runner(FakeRunner::new().on(
+            ArgMatcher::Any,
+            FakeResponse::failure(
+                1,
+                "ERROR: failed to solve: process \"/bin/sh -c make\" did not complete successfully: exit code: 2\n",
+            ),
+        ));
+
+        let error = build.run_with_progress(|_| {}).await.unwrap_err();
+        assert!(
+            matches!(error, Error::CommandFailed { ref stderr, .. } if stderr.starts_with("ERROR: failed to solve")),
+            "{error:?}"
+        );
+    }
+}
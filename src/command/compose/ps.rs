@@ -4,6 +4,7 @@ use crate::command::{CommandExecutor, ComposeCommand, ComposeConfig, DockerComma
 use crate::error::Result;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 /// Docker Compose ps command builder
 #[derive(Debug, Clone)]
@@ -78,6 +79,70 @@ pub struct ComposeContainerInfo {
     pub publishers: Option<Vec<PortPublisher>>,
 }
 
+impl ComposeContainerInfo {
+    /// Label holding the compose service name
+    const SERVICE_LABEL: &'static str = "com.docker.compose.service";
+
+    /// Build container info from a Compose or `podman ps` JSON object
+    fn from_json(value: Value) -> Option<Self> {
+        if value.get("ID").is_some() {
+            return serde_json::from_value(value).ok();
+        }
+
+        // podman-compose forwards to `podman ps`
+        let id = value.get("Id")?.as_str()?.to_string();
+        let name = match value.get("Names") {
+            Some(Value::Array(names)) => names.first().and_then(Value::as_str),
+            Some(Value::String(name)) => Some(name.as_str()),
+            _ => None,
+        }
+        .unwrap_or_default()
+        .to_string();
+        let service = value
+            .get("Labels")
+            .and_then(|labels| labels.get(Self::SERVICE_LABEL))
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let publishers = value.get("Ports").and_then(Value::as_array).map(|ports| {
+            ports
+                .iter()
+                .filter_map(|port| {
+                    Some(PortPublisher {
+                        target_port: u16::try_from(port.get("container_port")?.as_u64()?).ok()?,
+                        published_port: port
+                            .get("host_port")
+                            .and_then(Value::as_u64)
+                            .and_then(|p| u16::try_from(p).ok()),
+                        protocol: port
+                            .get("protocol")
+                            .and_then(Value::as_str)
+                            .unwrap_or("tcp")
+                            .to_string(),
+                    })
+                })
+                .collect()
+        });
+
+        Some(Self {
+            id,
+            name,
+            service,
+            state: value
+                .get("State")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            health: None,
+            exit_code: value
+                .get("ExitCode")
+                .and_then(Value::as_i64)
+                .and_then(|code| i32::try_from(code).ok()),
+            publishers,
+        })
+    }
+}
+
 /// Port publishing information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PortPublisher {
@@ -179,11 +244,25 @@ impl ComposePsCommand {
     }
 
     /// Parse JSON output into container info
+    ///
+    /// Accepts one object per line (Compose v2.21+), a single JSON array
+    /// (earlier Compose v2) and the `podman ps` shape produced by
+    /// `podman-compose`.
     fn parse_json_output(stdout: &str) -> Vec<ComposeContainerInfo> {
-        stdout
-            .lines()
-            .filter(|line| !line.trim().is_empty())
-            .filter_map(|line| serde_json::from_str(line).ok())
+        let trimmed = stdout.trim();
+        let values: Vec<Value> = if trimmed.starts_with('[') {
+            serde_json::from_str(trimmed).unwrap_or_default()
+        } else {
+            trimmed
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect()
+        };
+
+        values
+            .into_iter()
+            .filter_map(ComposeContainerInfo::from_json)
             .collect()
     }
 }
@@ -304,6 +383,7 @@ impl ComposePsResult {
             self.stdout
                 .lines()
                 .skip(1) // Skip header
+                .filter(|line| !line.starts_with("---")) // Compose v1 separator
                 .filter_map(|line| line.split_whitespace().next())
                 .map(String::from)
                 .collect()
@@ -402,4 +482,81 @@ mod tests {
             "args should not contain 'docker': {args:?}"
         );
     }
+
+    #[test]
+    fn test_parse_ndjson_output() {
+        let stdout = concat!(
+            r#"{"ID":"abc","Name":"app-web-1","Service":"web","State":"running","Health":"","ExitCode":0,"Publishers":[{"TargetPort":80,"PublishedPort":8080,"Protocol":"tcp"}]}"#,
+            "\n",
+            r#"{"ID":"def","Name":"app-db-1","Service":"db","State":"exited","ExitCode":1}"#,
+            "\n"
+        );
+        let containers = ComposePsCommand::parse_json_output(stdout);
+        assert_eq!(containers.len(), 2);
+        assert_eq!(containers[0].service, "web");
+        assert_eq!(containers[1].exit_code, Some(1));
+    }
+
+    #[test]
+    fn test_parse_json_array_output() {
+        let stdout = r#"[{"ID":"abc","Name":"app-web-1","Service":"web","State":"running"}]"#;
+        let containers = ComposePsCommand::parse_json_output(stdout);
+        assert_eq!(containers.len(), 1);
+        assert_eq!(containers[0].id, "abc");
+    }
+
+    #[test]
+    fn test_parse_podman_compose_output() {
+        let stdout = r#"[{"Id":"0123abcd","Names":["app_web_1"],"State":"running","ExitCode":0,
+            "Labels":{"com.docker.compose.service":"web"},
+            "Ports":[{"host_ip":"","container_port":80,"host_port":8080,"range":1,"protocol":"tcp"}]}]"#;
+        let containers = ComposePsCommand::parse_json_output(stdout);
+        assert_eq!(containers.len(), 1);
+        let web = &containers[0];
+        assert_eq!(web.id, "0123abcd");
+        assert_eq!(web.name, "app_web_1");
+        assert_eq!(web.service, "web");
+        let publishers = web.publishers.as_ref().unwrap();
+        assert_eq!(publishers[0].target_port, 80);
+        assert_eq!(publishers[0].published_port, Some(8080));
+    }
+
+    #[test]
+    fn test_container_ids_from_compose_v1_table() {
+        let result = ComposePsResult {
+            stdout: "    Name        Command   State   Ports\n\
+                     ------------------------------------\n\
+                     app_web_1   nginx      Up      80/tcp\n"
+                .to_string(),
+            stderr: String::new(),
+            success: true,
+            containers: Vec::new(),
+        };
+        assert_eq!(result.container_ids(), vec!["app_web_1"]);
+    }
+
+    #[tokio::test]
+    async fn test_compose_ps_uses_platform_compose_command() {
+        use crate::platform::{PlatformInfo, Runtime};
+        use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
+
+        let fake = FakeRunner::new().on(
+            ArgMatcher::Any,
+            FakeResponse::success(r#"[{"Id":"0123","Names":["app_web_1"],"State":"running"}]"#),
+        );
+        let mut cmd = ComposePsCommand::new().project_name("app").json();
+        cmd.executor = CommandExecutor::new()
+            .platform(PlatformInfo::for_runtime(Runtime::Podman))
+            .runner(fake.clone());
+
+        let result = cmd.execute().await.unwrap();
+
+        assert_eq!(result.container_ids(), vec!["0123"]);
+        let invocation = &fake.invocations()[0];
+        assert_eq!(invocation.program, "podman-compose");
+        assert_eq!(
+            invocation.args,
+            vec!["--project-name", "app", "ps", "--format", "json"]
+        );
+    }
 }
@@ -4,7 +4,8 @@
 //! a container and the local filesystem.
 
 use super::{CommandExecutor, CommandOutput, DockerCommand};
-use crate::error::Result;
+use crate::error::{Error, Result};
+use crate::stream::ByteStream;
 use async_trait::async_trait;
 use std::path::Path;
 
@@ -38,6 +39,8 @@ use std::path::Path;
 pub struct CpCommand {
     /// Source path (container:path or local path)
     source: String,
+    /// Container holding the source, if it is not on the host
+    source_container: Option<String>,
     /// Destination path (container:path or local path)
     destination: String,
     /// Archive mode (preserve permissions)
@@ -64,9 +67,10 @@ impl CpCommand {
     /// ```
     #[must_use]
     pub fn from_container(container: impl Into<String>, path: impl Into<String>) -> Self {
-        let source = format!("{}:{}", container.into(), path.into());
+        let container = container.into();
         Self {
-            source,
+            source: format!("{container}:{}", path.into()),
+            source_container: Some(container),
             destination: String::new(),
             archive: false,
             follow_link: false,
@@ -90,6 +94,7 @@ impl CpCommand {
     pub fn from_host(path: &Path) -> Self {
         Self {
             source: path.to_string_lossy().into_owned(),
+            source_container: None,
             destination: String::new(),
             archive: false,
             follow_link: false,
@@ -160,6 +165,46 @@ impl CpCommand {
             destination: self.destination.clone(),
         })
     }
+
+    /// Copy the source out of the container as a tar archive read from
+    /// stdout
+    ///
+    /// Runs `docker cp container:path -`, ignoring any destination set on
+    /// the command. The source must be in a container, as created by
+    /// [`from_container`](Self::from_container).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use docker_wrapper::CpCommand;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut archive = CpCommand::from_container("my-container", "/var/log").reader()?;
+    /// let size = tokio::io::copy(&mut archive, &mut tokio::io::sink()).await?;
+    /// println!("/var/log is {size} bytes as a tar archive");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidConfig`] if the source is on the host, or an
+    /// error if the command cannot be started or an interceptor vetoes it
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a Tokio runtime.
+    pub fn reader(&self) -> Result<ByteStream> {
+        if self.source_container.is_none() {
+            return Err(Error::invalid_config(format!(
+                "cp reader needs a container source, got host path `{}`",
+                self.source
+            )));
+        }
+        let mut command = self.clone();
+        command.destination = "-".to_string();
+        crate::stream::spawn_byte_stream(&command.executor, command.build_command_args())
+    }
 }
 
 #[async_trait]
@@ -248,6 +293,37 @@ impl CpResult {
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn test_reader_copies_to_stdout() {
+        use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
+        use tokio::io::AsyncReadExt;
+
+        let fake = FakeRunner::new().on(ArgMatcher::Any, FakeResponse::success(b"a\0b".to_vec()));
+        let mut cmd = CpCommand::from_container("web", "/etc/hosts")
+            .to_host(Path::new("./hosts"))
+            .archive();
+        cmd.with_runner(fake.clone());
+
+        let mut archive = cmd.reader().unwrap();
+        let mut bytes = Vec::new();
+        archive.read_to_end(&mut bytes).await.unwrap();
+        assert_eq!(bytes, b"a\0b");
+        assert_eq!(
+            fake.invocations()[0].args,
+            ["cp", "--archive", "web:/etc/hosts", "-"]
+        );
+    }
+
+    #[test]
+    fn test_reader_rejects_host_source() {
+        let mut cmd =
+            CpCommand::from_host(Path::new("./data.txt")).to_container("web", "/data/data.txt");
+        cmd.with_runner(crate::runner::FakeRunner::new());
+
+        let error = cmd.reader().unwrap_err();
+        assert!(matches!(error, Error::InvalidConfig { .. }), "{error:?}");
+    }
+
     #[test]
     fn test_cp_from_container_to_host() {
         let cmd = CpCommand::from_container("test-container", "/app/file.txt")
@@ -3,7 +3,8 @@
 //! This module provides the `docker create` command for creating containers without starting them.
 
 use super::{CommandExecutor, CommandOutput, DockerCommand, EnvironmentBuilder, PortBuilder};
-use crate::error::Result;
+use crate::error::{Error, Result};
+use crate::parse::ArgParser;
 use async_trait::async_trait;
 
 /// Docker create command builder
@@ -154,6 +155,13 @@ impl CreateCommand {
         self
     }
 
+    /// Pass an environment variable through from the host (`--env KEY`)
+    #[must_use]
+    pub fn env_pass_through(mut self, key: impl Into<String>) -> Self {
+        self.env_builder = self.env_builder.pass_through(key);
+        self
+    }
+
     /// Add a port mapping
     ///
     /// # Example
@@ -310,6 +318,87 @@ impl CreateCommand {
     }
 }
 
+impl CreateCommand {
+    /// Build a create command from `docker create` arguments
+    ///
+    /// The leading `docker` and `create` (or `container create`) are optional.
+    /// See [`crate::parse`] for the accepted syntax.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use docker_wrapper::CreateCommand;
+    ///
+    /// let cmd = CreateCommand::from_args(["create", "--name", "job", "alpine", "true"]).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for unknown flags, missing or invalid values, and a
+    /// missing image.
+    pub fn from_args<I, S>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut parser = ArgParser::new("create", &[&["create"], &["container", "create"]], args);
+        let mut cmd = Self::new("");
+
+        while let Some(flag) = parser.next_flag() {
+            let p = &mut parser;
+            match flag.name.as_str() {
+                "--name" => cmd = cmd.name(p.value(&flag)?),
+                "-e" | "--env" => match p.env(&flag)? {
+                    (key, Some(value)) => cmd = cmd.env(key, value),
+                    (key, None) => cmd = cmd.env_pass_through(key),
+                },
+                "-p" | "--publish" => {
+                    let value = p.value(&flag)?;
+                    let mapping = value.parse().map_err(|_| p.invalid(&flag, &value))?;
+                    cmd.port_builder = cmd.port_builder.mapping(mapping);
+                }
+                "-w" | "--workdir" => cmd = cmd.workdir(p.value(&flag)?),
+                "-u" | "--user" => cmd = cmd.user(p.value(&flag)?),
+                "-h" | "--hostname" => cmd = cmd.hostname(p.value(&flag)?),
+                "-a" | "--attach" => {
+                    let value = p.value(&flag)?;
+                    match value.to_ascii_uppercase().as_str() {
+                        "STDIN" => cmd.attach_stdin = true,
+                        "STDOUT" => cmd.attach_stdout = true,
+                        "STDERR" => cmd.attach_stderr = true,
+                        _ => return Err(p.invalid(&flag, &value)),
+                    }
+                }
+                "-i" | "--interactive" => cmd.interactive = p.switch(&flag)?,
+                "-t" | "--tty" => cmd.tty = p.switch(&flag)?,
+                "-v" | "--volume" => cmd = cmd.volume(p.value(&flag)?),
+                "-l" | "--label" => cmd = cmd.label(p.value(&flag)?),
+                "-m" | "--memory" => cmd = cmd.memory(p.value(&flag)?),
+                "--cpus" => cmd = cmd.cpus(p.value(&flag)?),
+                "--network" | "--net" => cmd = cmd.network(p.value(&flag)?),
+                _ => return Err(p.unknown(&flag)),
+            }
+        }
+
+        let mut positionals = parser.finish().into_iter();
+        cmd.image = positionals
+            .next()
+            .ok_or_else(|| Error::invalid_config("docker create requires an image"))?;
+        cmd.command = positionals.collect();
+
+        Ok(cmd)
+    }
+}
+
+impl std::str::FromStr for CreateCommand {
+    type Err = Error;
+
+    /// Parse a `docker create ...` shell line
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_args(crate::parse::split(s)?)
+    }
+}
+
 #[async_trait]
 impl DockerCommand for CreateCommand {
     type Output = CommandOutput;
@@ -538,4 +627,59 @@ mod tests {
         assert!(args.contains(&"-c".to_string()));
         assert!(args.contains(&"echo hello".to_string()));
     }
+
+    #[test]
+    fn test_create_command_from_args_round_trip() {
+        let cmd = CreateCommand::new("alpine:latest")
+            .name("job")
+            .env("MODE", "batch")
+            .env_pass_through("TOKEN")
+            .port(8080, 80)
+            .workdir("/app")
+            .user("1000:1000")
+            .hostname("job-host")
+            .attach_stdin()
+            .attach_stdout()
+            .attach_stderr()
+            .interactive()
+            .tty()
+            .volume("/host/data:/data:ro")
+            .label("team=data")
+            .memory("256m")
+            .cpus("0.5")
+            .network("backend")
+            .cmd(vec!["sh", "-c", "echo hello"]);
+        let args = cmd.build_command_args();
+
+        let parsed = CreateCommand::from_args(args.clone()).unwrap();
+        assert_eq!(parsed.build_command_args(), args);
+    }
+
+    #[test]
+    fn test_create_command_parse_shell_line() {
+        let cmd: CreateCommand = "docker container create -it -a stdout --net host busybox top"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            cmd.build_command_args(),
+            [
+                "create",
+                "--attach",
+                "STDOUT",
+                "--interactive",
+                "--tty",
+                "--network",
+                "host",
+                "busybox",
+                "top"
+            ]
+        );
+        assert!("docker create --restart always busybox"
+            .parse::<CreateCommand>()
+            .is_err());
+        assert!("docker create -a STDWHAT busybox"
+            .parse::<CreateCommand>()
+            .is_err());
+    }
 }
@@ -1,11 +1,44 @@
 //! Docker events command implementation.
 //!
 //! This module provides the `docker events` command for getting real-time events from the Docker daemon.
+//!
+//! [`EventsCommand::subscribe`] turns it into a long-lived stream of typed
+//! [`DockerEvent`]s that reconnects when the CLI exits:
+//!
+//! ```rust,no_run
+//! use docker_wrapper::{ContainerAction, EventFilter, EventKind, EventType, EventsCommand};
+//!
+//! # async fn example() -> docker_wrapper::Result<()> {
+//! let mut events = EventsCommand::new()
+//!     .event_filter(EventFilter::Type(EventType::Container))
+//!     .event_filter(EventFilter::event(ContainerAction::Die))
+//!     .event_filter(EventFilter::event(ContainerAction::Oom))
+//!     .subscribe();
+//!
+//! while let Some(event) = events.next_event().await {
+//!     let event = event?;
+//!     if let EventKind::Container(ContainerAction::Die) = event.kind() {
+//!         println!("{:?} exited with {:?}", event.name(), event.exit_code());
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
 
 use super::{CommandExecutor, CommandOutput, DockerCommand};
-use crate::error::Result;
+use crate::debug::{BackoffStrategy, RetryPolicy};
+use crate::error::{Error, ErrorKind, Result};
+use crate::stream::{OutputLine, OUTPUT_STREAM_CAPACITY};
+use crate::tracing_compat::{debug, warn};
 use async_trait::async_trait;
+use futures_core::Stream;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::mpsc;
 
 /// Docker events command builder
 ///
@@ -41,6 +74,8 @@ pub struct EventsCommand {
     since: Option<String>,
     /// Show events until timestamp  
     until: Option<String>,
+    /// Reconnect policy used by `subscribe`
+    reconnect: Option<RetryPolicy>,
     /// Command executor
     pub executor: CommandExecutor,
 }
@@ -62,6 +97,7 @@ impl EventsCommand {
             format: None,
             since: None,
             until: None,
+            reconnect: None,
             executor: CommandExecutor::new(),
         }
     }
@@ -123,6 +159,186 @@ impl EventsCommand {
         self
     }
 
+    /// Add a typed filter for events
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use docker_wrapper::{ContainerAction, DockerCommand, EventFilter, EventType, EventsCommand};
+    ///
+    /// let cmd = EventsCommand::new()
+    ///     .event_filter(EventFilter::Type(EventType::Container))
+    ///     .event_filter(EventFilter::event(ContainerAction::Oom));
+    /// assert_eq!(
+    ///     cmd.build_command_args(),
+    ///     ["events", "--filter", "type=container", "--filter", "event=oom"]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn event_filter(self, filter: EventFilter) -> Self {
+        let (key, value) = filter.into_key_value();
+        self.filter(key, value)
+    }
+
+    /// Set how [`subscribe`](Self::subscribe) reconnects after the CLI exits
+    ///
+    /// `max_attempts` is how many runs of the CLI in a row may end without
+    /// delivering an event before the stream gives up; the backoff sets the
+    /// delay before each reconnect. Errors the policy does not
+    /// [retry](RetryPolicy::should_retry) end the stream at once. By
+    /// default the stream reconnects forever, also while the daemon is
+    /// unreachable or the CLI fails, with exponential backoff up to 10
+    /// seconds.
+    #[must_use]
+    pub fn reconnect(mut self, policy: RetryPolicy) -> Self {
+        self.reconnect = Some(policy);
+        self
+    }
+
+    /// Subscribe to events as a typed stream that outlives the CLI process
+    ///
+    /// Runs `docker events --format json` and yields every event as it
+    /// happens. When the CLI exits, for example because the daemon
+    /// restarted, it is started again with `--since` set to the last event
+    /// seen, so events are neither lost nor repeated. Lines that are not
+    /// events are skipped.
+    ///
+    /// The stream ends when it is dropped, when the executor's cancellation
+    /// token is cancelled, when the CLI exits cleanly with
+    /// [`until`](Self::until) set, or after yielding the error that stopped
+    /// it from reconnecting (see [`reconnect`](Self::reconnect)).
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a Tokio runtime.
+    #[must_use]
+    pub fn subscribe(&self) -> EventStream {
+        let (tx, rx) = mpsc::channel(OUTPUT_STREAM_CAPACITY);
+        let mut command = self.clone();
+        command.format = Some("json".to_string());
+        tokio::spawn(command.run_subscription(tx));
+        EventStream { events: rx }
+    }
+
+    /// Keep the CLI running and forward its events until the stream ends
+    async fn run_subscription(self, events: mpsc::Sender<Result<DockerEvent>>) {
+        let policy = self.reconnect.clone().unwrap_or_else(|| {
+            RetryPolicy::new()
+                .max_attempts(u32::MAX)
+                .backoff(BackoffStrategy::Exponential {
+                    initial: Duration::from_millis(100),
+                    max: Duration::from_secs(10),
+                    multiplier: 2.0,
+                })
+                .retry_on([
+                    ErrorKind::DaemonNotRunning,
+                    ErrorKind::Network,
+                    ErrorKind::CommandFailed,
+                    ErrorKind::Timeout,
+                    ErrorKind::Io,
+                ])
+        });
+        let mut cursor = EventCursor::default();
+        let mut failures = 0;
+
+        loop {
+            let mut session = self.clone();
+            if let Some(since) = cursor.since() {
+                session.since = Some(since);
+            }
+            let Some((delivered, result)) = session.forward_events(&mut cursor, &events).await
+            else {
+                return;
+            };
+
+            if delivered {
+                failures = 0;
+            }
+            let error = match result {
+                Ok(()) if self.until.is_some() => return,
+                Ok(()) => None,
+                Err(Error::Interrupted) => return,
+                Err(e) if !policy.should_retry(&e) => {
+                    let _ = events.send(Err(e)).await;
+                    return;
+                }
+                Err(e) => Some(e),
+            };
+            failures += 1;
+            if failures >= policy.max_attempts {
+                let error = error.unwrap_or_else(|| Error::custom("docker events kept exiting"));
+                let _ = events.send(Err(error)).await;
+                return;
+            }
+
+            let reason =
+                error.map_or_else(|| "docker events exited".to_string(), |e| e.to_string());
+            warn!(attempt = failures, reason = %reason, "reconnecting docker events");
+            if let Some(ref callback) = policy.on_retry {
+                callback(failures, &reason);
+            }
+            let cancelled = async {
+                match self.executor.cancellation {
+                    Some(ref token) => token.cancelled().await,
+                    None => std::future::pending().await,
+                }
+            };
+            tokio::select! {
+                () = tokio::time::sleep(policy.calculate_delay(failures)) => {}
+                () = cancelled => return,
+                () = events.closed() => return,
+            }
+        }
+    }
+
+    /// Run the CLI once, sending new events until it exits
+    ///
+    /// Returns whether any event was delivered and how the CLI exited, or
+    /// `None` once the receiver is gone.
+    async fn forward_events(
+        &self,
+        cursor: &mut EventCursor,
+        events: &mpsc::Sender<Result<DockerEvent>>,
+    ) -> Option<(bool, Result<()>)> {
+        let (mut lines, completion) = match self.stream_output() {
+            Ok(stream) => stream,
+            Err(e) => return Some((false, Err(e))),
+        };
+        let mut delivered = false;
+        let mut stderr = Vec::new();
+
+        loop {
+            let line = tokio::select! {
+                line = lines.next_line() => line,
+                () = events.closed() => return None,
+            };
+            match line {
+                Some(OutputLine::Stdout(text)) => {
+                    if let Some(event) = cursor.accept(&text) {
+                        events.send(Ok(event)).await.ok()?;
+                        delivered = true;
+                    }
+                }
+                Some(OutputLine::Stderr(text)) => stderr.push(text),
+                None => break,
+            }
+        }
+
+        let result = completion.await.and_then(|status| {
+            if status.success {
+                Ok(())
+            } else {
+                Err(self.executor.failure(
+                    "docker events".to_string(),
+                    status.exit_code,
+                    "",
+                    &stderr.join("\n"),
+                ))
+            }
+        });
+        Some((delivered, result))
+    }
+
     /// Execute the events command
     ///
     /// # Errors
@@ -305,9 +521,530 @@ pub struct EventActor {
     pub attributes: std::collections::HashMap<String, String>,
 }
 
+impl DockerEvent {
+    /// Typed view of the event's type and action
+    #[must_use]
+    pub fn kind(&self) -> EventKind {
+        let action = self.action.as_str();
+        match EventType::from(self.event_type.as_str()) {
+            EventType::Container => EventKind::Container(action.into()),
+            EventType::Image => EventKind::Image(action.into()),
+            EventType::Network => EventKind::Network(action.into()),
+            EventType::Volume => EventKind::Volume(action.into()),
+            event_type => EventKind::Other {
+                event_type,
+                action: self.action.clone(),
+            },
+        }
+    }
+
+    /// Get an actor attribute
+    #[must_use]
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.actor.attributes.get(key).map(String::as_str)
+    }
+
+    /// Name of the container, image, network or volume, if reported
+    #[must_use]
+    pub fn name(&self) -> Option<&str> {
+        self.attribute("name")
+    }
+
+    /// Exit code of a container `die` event
+    #[must_use]
+    pub fn exit_code(&self) -> Option<i32> {
+        self.attribute("exitCode")?.parse().ok()
+    }
+}
+
+/// Type of object an event is about
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum EventType {
+    /// Container events
+    Container,
+    /// Image events
+    Image,
+    /// Network events
+    Network,
+    /// Volume events
+    Volume,
+    /// Daemon events
+    Daemon,
+    /// Plugin events
+    Plugin,
+    /// Swarm service events
+    Service,
+    /// Swarm node events
+    Node,
+    /// Swarm secret events
+    Secret,
+    /// Swarm config events
+    Config,
+    /// Any other type
+    Other(String),
+}
+
+impl From<&str> for EventType {
+    fn from(value: &str) -> Self {
+        match value {
+            "container" => Self::Container,
+            "image" => Self::Image,
+            "network" => Self::Network,
+            "volume" => Self::Volume,
+            "daemon" => Self::Daemon,
+            "plugin" => Self::Plugin,
+            "service" => Self::Service,
+            "node" => Self::Node,
+            "secret" => Self::Secret,
+            "config" => Self::Config,
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for EventType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Container => "container",
+            Self::Image => "image",
+            Self::Network => "network",
+            Self::Volume => "volume",
+            Self::Daemon => "daemon",
+            Self::Plugin => "plugin",
+            Self::Service => "service",
+            Self::Node => "node",
+            Self::Secret => "secret",
+            Self::Config => "config",
+            Self::Other(other) => other,
+        })
+    }
+}
+
+/// Typed event, as returned by [`DockerEvent::kind`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventKind {
+    /// Container event
+    Container(ContainerAction),
+    /// Image event
+    Image(ImageAction),
+    /// Network event
+    Network(NetworkAction),
+    /// Volume event
+    Volume(VolumeAction),
+    /// Event of another type
+    Other {
+        /// Type of the object
+        event_type: EventType,
+        /// Raw action
+        action: String,
+    },
+}
+
+/// Action of a container event
+///
+/// Displays as the name `--filter event=` expects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContainerAction {
+    /// `attach`
+    Attach,
+    /// `commit`
+    Commit,
+    /// `copy`
+    Copy,
+    /// `create`
+    Create,
+    /// `destroy`
+    Destroy,
+    /// `detach`
+    Detach,
+    /// `die`; see [`DockerEvent::exit_code`]
+    Die,
+    /// `exec_create`
+    ExecCreate,
+    /// `exec_detach`
+    ExecDetach,
+    /// `exec_die`
+    ExecDie,
+    /// `exec_start`
+    ExecStart,
+    /// `export`
+    Export,
+    /// `health_status` with the new status (e.g. `healthy`)
+    HealthStatus(String),
+    /// `kill`
+    Kill,
+    /// `oom`
+    Oom,
+    /// `pause`
+    Pause,
+    /// `rename`
+    Rename,
+    /// `resize`
+    Resize,
+    /// `restart`
+    Restart,
+    /// `start`
+    Start,
+    /// `stop`
+    Stop,
+    /// `top`
+    Top,
+    /// `unpause`
+    Unpause,
+    /// `update`
+    Update,
+    /// Any other action
+    Other(String),
+}
+
+impl From<&str> for ContainerAction {
+    fn from(value: &str) -> Self {
+        // Some actions carry details after a colon, e.g. "health_status: healthy"
+        let (name, detail) = value
+            .split_once(':')
+            .map_or((value, ""), |(name, detail)| (name, detail.trim()));
+        match name {
+            "attach" => Self::Attach,
+            "commit" => Self::Commit,
+            "copy" => Self::Copy,
+            "create" => Self::Create,
+            "destroy" => Self::Destroy,
+            "detach" => Self::Detach,
+            "die" => Self::Die,
+            "exec_create" => Self::ExecCreate,
+            "exec_detach" => Self::ExecDetach,
+            "exec_die" => Self::ExecDie,
+            "exec_start" => Self::ExecStart,
+            "export" => Self::Export,
+            "health_status" => Self::HealthStatus(detail.to_string()),
+            "kill" => Self::Kill,
+            "oom" => Self::Oom,
+            "pause" => Self::Pause,
+            "rename" => Self::Rename,
+            "resize" => Self::Resize,
+            "restart" => Self::Restart,
+            "start" => Self::Start,
+            "stop" => Self::Stop,
+            "top" => Self::Top,
+            "unpause" => Self::Unpause,
+            "update" => Self::Update,
+            _ => Self::Other(value.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ContainerAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Attach => "attach",
+            Self::Commit => "commit",
+            Self::Copy => "copy",
+            Self::Create => "create",
+            Self::Destroy => "destroy",
+            Self::Detach => "detach",
+            Self::Die => "die",
+            Self::ExecCreate => "exec_create",
+            Self::ExecDetach => "exec_detach",
+            Self::ExecDie => "exec_die",
+            Self::ExecStart => "exec_start",
+            Self::Export => "export",
+            Self::HealthStatus(_) => "health_status",
+            Self::Kill => "kill",
+            Self::Oom => "oom",
+            Self::Pause => "pause",
+            Self::Rename => "rename",
+            Self::Resize => "resize",
+            Self::Restart => "restart",
+            Self::Start => "start",
+            Self::Stop => "stop",
+            Self::Top => "top",
+            Self::Unpause => "unpause",
+            Self::Update => "update",
+            Self::Other(other) => other,
+        })
+    }
+}
+
+/// Action of an image event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageAction {
+    /// `delete`
+    Delete,
+    /// `import`
+    Import,
+    /// `load`
+    Load,
+    /// `pull`
+    Pull,
+    /// `push`
+    Push,
+    /// `save`
+    Save,
+    /// `tag`
+    Tag,
+    /// `untag`
+    Untag,
+    /// Any other action
+    Other(String),
+}
+
+impl From<&str> for ImageAction {
+    fn from(value: &str) -> Self {
+        match value {
+            "delete" => Self::Delete,
+            "import" => Self::Import,
+            "load" => Self::Load,
+            "pull" => Self::Pull,
+            "push" => Self::Push,
+            "save" => Self::Save,
+            "tag" => Self::Tag,
+            "untag" => Self::Untag,
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ImageAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Delete => "delete",
+            Self::Import => "import",
+            Self::Load => "load",
+            Self::Pull => "pull",
+            Self::Push => "push",
+            Self::Save => "save",
+            Self::Tag => "tag",
+            Self::Untag => "untag",
+            Self::Other(other) => other,
+        })
+    }
+}
+
+/// Action of a network event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetworkAction {
+    /// `create`
+    Create,
+    /// `connect`; the container is in the `container` attribute
+    Connect,
+    /// `destroy`
+    Destroy,
+    /// `disconnect`; the container is in the `container` attribute
+    Disconnect,
+    /// `remove`
+    Remove,
+    /// `update`
+    Update,
+    /// Any other action
+    Other(String),
+}
+
+impl From<&str> for NetworkAction {
+    fn from(value: &str) -> Self {
+        match value {
+            "create" => Self::Create,
+            "connect" => Self::Connect,
+            "destroy" => Self::Destroy,
+            "disconnect" => Self::Disconnect,
+            "remove" => Self::Remove,
+            "update" => Self::Update,
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for NetworkAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Create => "create",
+            Self::Connect => "connect",
+            Self::Destroy => "destroy",
+            Self::Disconnect => "disconnect",
+            Self::Remove => "remove",
+            Self::Update => "update",
+            Self::Other(other) => other,
+        })
+    }
+}
+
+/// Action of a volume event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VolumeAction {
+    /// `create`
+    Create,
+    /// `destroy`
+    Destroy,
+    /// `mount`; the container is in the `container` attribute
+    Mount,
+    /// `unmount`
+    Unmount,
+    /// Any other action
+    Other(String),
+}
+
+impl From<&str> for VolumeAction {
+    fn from(value: &str) -> Self {
+        match value {
+            "create" => Self::Create,
+            "destroy" => Self::Destroy,
+            "mount" => Self::Mount,
+            "unmount" => Self::Unmount,
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for VolumeAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Create => "create",
+            Self::Destroy => "destroy",
+            Self::Mount => "mount",
+            Self::Unmount => "unmount",
+            Self::Other(other) => other,
+        })
+    }
+}
+
+/// Typed filter for [`EventsCommand::event_filter`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventFilter {
+    /// Only objects of this type (`type=`)
+    Type(EventType),
+    /// Only this action (`event=`); see [`EventFilter::event`]
+    Event(String),
+    /// Only this container, by name or ID (`container=`)
+    Container(String),
+    /// Only this image, by name or ID (`image=`)
+    Image(String),
+    /// Only objects with this label, as `key` or `key=value` (`label=`)
+    Label(String),
+    /// Only this network, by name or ID (`network=`)
+    Network(String),
+    /// Only this volume (`volume=`)
+    Volume(String),
+    /// Only `local` or `swarm` scoped events (`scope=`)
+    Scope(String),
+}
+
+impl EventFilter {
+    /// Filter on an action, typed or raw
+    ///
+    /// ```
+    /// use docker_wrapper::{ContainerAction, EventFilter};
+    ///
+    /// assert_eq!(
+    ///     EventFilter::event(ContainerAction::Die),
+    ///     EventFilter::Event("die".to_string())
+    /// );
+    /// ```
+    pub fn event(action: impl fmt::Display) -> Self {
+        Self::Event(action.to_string())
+    }
+
+    /// Key and value of the `--filter` option
+    fn into_key_value(self) -> (&'static str, String) {
+        match self {
+            Self::Type(event_type) => ("type", event_type.to_string()),
+            Self::Event(value) => ("event", value),
+            Self::Container(value) => ("container", value),
+            Self::Image(value) => ("image", value),
+            Self::Label(value) => ("label", value),
+            Self::Network(value) => ("network", value),
+            Self::Volume(value) => ("volume", value),
+            Self::Scope(value) => ("scope", value),
+        }
+    }
+}
+
+/// Live events from [`EventsCommand::subscribe`]
+///
+/// Dropping the stream stops the CLI.
+#[derive(Debug)]
+pub struct EventStream {
+    events: mpsc::Receiver<Result<DockerEvent>>,
+}
+
+impl EventStream {
+    /// Wait for the next event, or `None` once the stream has ended
+    pub async fn next_event(&mut self) -> Option<Result<DockerEvent>> {
+        self.events.recv().await
+    }
+}
+
+impl Stream for EventStream {
+    type Item = Result<DockerEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.events.poll_recv(cx)
+    }
+}
+
+/// Position in the event log, used to resume after a reconnect
+///
+/// `--since` is resolved by the daemon, so a resumed CLI may replay events
+/// from the last timestamp seen. Those are recognised and skipped.
+#[derive(Debug, Default)]
+struct EventCursor {
+    /// `timeNano` of the newest event delivered
+    last_nano: i64,
+    /// Events delivered at `last_nano`
+    seen_at_last: Vec<(String, String, String)>,
+}
+
+impl EventCursor {
+    /// `--since` value resuming at the newest event delivered
+    fn since(&self) -> Option<String> {
+        const NANOS: i64 = 1_000_000_000;
+        (self.last_nano > 0).then(|| {
+            format!(
+                "{}.{:09}",
+                self.last_nano.div_euclid(NANOS),
+                self.last_nano.rem_euclid(NANOS)
+            )
+        })
+    }
+
+    /// Parse `line` and return the event unless it was delivered before
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    fn accept(&mut self, line: &str) -> Option<DockerEvent> {
+        let line = line.trim();
+        if line.is_empty() {
+            return None;
+        }
+        let event: DockerEvent = match serde_json::from_str(line) {
+            Ok(event) => event,
+            Err(e) => {
+                debug!(error = %e, "skipping line that is not an event");
+                return None;
+            }
+        };
+        if event.time_nano <= 0 {
+            return Some(event);
+        }
+
+        let key = (
+            event.event_type.clone(),
+            event.action.clone(),
+            event.actor.id.clone(),
+        );
+        match event.time_nano.cmp(&self.last_nano) {
+            Ordering::Less => return None,
+            Ordering::Equal if self.seen_at_last.contains(&key) => return None,
+            Ordering::Equal => self.seen_at_last.push(key),
+            Ordering::Greater => {
+                self.last_nano = event.time_nano;
+                self.seen_at_last = vec![key];
+            }
+        }
+        Some(event)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
 
     #[test]
     fn test_events_basic() {
@@ -402,4 +1139,193 @@ mod tests {
         let events = EventsCommand::parse_json_events("");
         assert!(events.is_empty());
     }
+
+    fn event(event_type: &str, action: &str, attributes: &[(&str, &str)]) -> DockerEvent {
+        DockerEvent {
+            event_type: event_type.to_string(),
+            action: action.to_string(),
+            actor: EventActor {
+                id: "abc123".to_string(),
+                attributes: attributes
+                    .iter()
+                    .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+                    .collect(),
+            },
+            time: 0,
+            time_nano: 0,
+        }
+    }
+
+    #[test]
+    fn test_event_kind() {
+        let die = event("container", "die", &[("name", "web"), ("exitCode", "137")]);
+        assert_eq!(die.kind(), EventKind::Container(ContainerAction::Die));
+        assert_eq!(die.name(), Some("web"));
+        assert_eq!(die.exit_code(), Some(137));
+
+        assert_eq!(
+            event("container", "health_status: unhealthy", &[]).kind(),
+            EventKind::Container(ContainerAction::HealthStatus("unhealthy".to_string()))
+        );
+        assert_eq!(
+            event("container", "exec_start: sh -c ls", &[]).kind(),
+            EventKind::Container(ContainerAction::ExecStart)
+        );
+        assert_eq!(
+            event("image", "pull", &[]).kind(),
+            EventKind::Image(ImageAction::Pull)
+        );
+        assert_eq!(
+            event("network", "connect", &[]).kind(),
+            EventKind::Network(NetworkAction::Connect)
+        );
+        assert_eq!(
+            event("volume", "mount", &[]).kind(),
+            EventKind::Volume(VolumeAction::Mount)
+        );
+        assert_eq!(
+            event("daemon", "reload", &[]).kind(),
+            EventKind::Other {
+                event_type: EventType::Daemon,
+                action: "reload".to_string()
+            }
+        );
+        assert_eq!(ContainerAction::from("oom").to_string(), "oom");
+    }
+
+    #[test]
+    fn test_event_filters() {
+        let cmd = EventsCommand::new()
+            .event_filter(EventFilter::Type(EventType::Container))
+            .event_filter(EventFilter::event(ContainerAction::HealthStatus(
+                String::new(),
+            )))
+            .event_filter(EventFilter::Label("env=prod".to_string()));
+        assert_eq!(
+            cmd.build_command_args(),
+            vec![
+                "events",
+                "--filter",
+                "type=container",
+                "--filter",
+                "event=health_status",
+                "--filter",
+                "label=env=prod"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cursor_resumes_without_repeats() {
+        let line = |action: &str, nano: i64| {
+            format!(
+                r#"{{"Type":"container","Action":"{action}","Actor":{{"ID":"abc123","Attributes":{{}}}},"time":{},"timeNano":{nano}}}"#,
+                nano / 1_000_000_000
+            )
+        };
+        let mut cursor = EventCursor::default();
+        assert_eq!(cursor.since(), None);
+
+        assert!(cursor
+            .accept(&line("start", 1_700_000_000_000_000_500))
+            .is_some());
+        assert!(cursor
+            .accept(&line("die", 1_700_000_000_000_000_600))
+            .is_some());
+        assert!(cursor.accept("not json").is_none());
+        assert_eq!(cursor.since().as_deref(), Some("1700000000.000000600"));
+
+        // Replayed after a reconnect
+        assert!(cursor
+            .accept(&line("start", 1_700_000_000_000_000_500))
+            .is_none());
+        assert!(cursor
+            .accept(&line("die", 1_700_000_000_000_000_600))
+            .is_none());
+        // Same timestamp, different event
+        assert!(cursor
+            .accept(&line("destroy", 1_700_000_000_000_000_600))
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_reconnects_since_last_event() {
+        const FIRST: &str = r#"{"Type":"container","Action":"start","Actor":{"ID":"a","Attributes":{"name":"web"}},"time":1700000000,"timeNano":1700000000000000500}
+{"Type":"container","Action":"oom","Actor":{"ID":"a","Attributes":{"name":"web"}},"time":1700000000,"timeNano":1700000000000000600}
+"#;
+        const SECOND: &str = r#"{"Type":"container","Action":"oom","Actor":{"ID":"a","Attributes":{"name":"web"}},"time":1700000000,"timeNano":1700000000000000600}
+{"Type":"container","Action":"die","Actor":{"ID":"a","Attributes":{"name":"web","exitCode":"137"}},"time":1700000001,"timeNano":1700000001000000000}
+"#;
+        let fake = FakeRunner::new().on_sequence(
+            ArgMatcher::Any,
+            [
+                FakeResponse::success(FIRST),
+                FakeResponse::success(SECOND).with_delay(Duration::from_secs(30)),
+            ],
+        );
+        let mut cmd = EventsCommand::new();
+        cmd.with_runner(fake.clone());
+        let mut events = cmd
+            .event_filter(EventFilter::Type(EventType::Container))
+            .reconnect(RetryPolicy::new().backoff(BackoffStrategy::Fixed(Duration::ZERO)))
+            .subscribe();
+
+        let mut actions = Vec::new();
+        for _ in 0..3 {
+            let event = tokio::time::timeout(Duration::from_secs(5), events.next_event())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            actions.push(event.kind());
+        }
+        drop(events);
+
+        assert_eq!(
+            actions,
+            [
+                EventKind::Container(ContainerAction::Start),
+                EventKind::Container(ContainerAction::Oom),
+                EventKind::Container(ContainerAction::Die),
+            ]
+        );
+        let starts: Vec<_> = fake.invocations().into_iter().map(|i| i.args).collect();
+        assert_eq!(
+            starts[0],
+            ["events", "--filter", "type=container", "--format", "json"]
+        );
+        assert_eq!(
+            starts[1][starts[1].len() - 2..],
+            ["--since", "1700000000.000000600"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_gives_up_after_failed_reconnects() {
+        let fake = FakeRunner::new().on(
+            ArgMatcher::Any,
+            FakeResponse::failure(1, "error during connect\n"),
+        );
+        let retries = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let policy = RetryPolicy::new()
+            .max_attempts(3)
+            .backoff(BackoffStrategy::Fixed(Duration::ZERO))
+            .retry_on([ErrorKind::DaemonNotRunning])
+            .on_retry({
+                let retries = std::sync::Arc::clone(&retries);
+                move |attempt, _| retries.lock().unwrap().push(attempt)
+            });
+        let mut cmd = EventsCommand::new();
+        cmd.with_runner(fake.clone());
+        let mut events = cmd.reconnect(policy).subscribe();
+
+        let last = tokio::time::timeout(Duration::from_secs(5), events.next_event())
+            .await
+            .unwrap();
+
+        assert!(matches!(last, Some(Err(Error::DaemonNotRunning { .. }))));
+        assert!(events.next_event().await.is_none());
+        assert_eq!(fake.invocations().len(), 3);
+        assert_eq!(*retries.lock().unwrap(), [1, 2]);
+    }
 }
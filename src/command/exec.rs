@@ -4,12 +4,18 @@
 //! with support for all native options and an extensible architecture for any additional options.
 
 use super::{CommandExecutor, DockerCommand, EnvironmentBuilder};
-use crate::error::Result;
+use crate::error::{Error, Result};
+use crate::parse::ArgParser;
+use crate::stream::ExecSession;
 use async_trait::async_trait;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::path::PathBuf;
 
 /// Docker exec command builder with fluent API
-#[derive(Debug, Clone)]
+///
+/// Serializes to its options; see [`crate::command`] for the format.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(remote = "Self", default = "ExecCommand::unset", deny_unknown_fields)]
 #[allow(clippy::struct_excessive_bools)]
 pub struct ExecCommand {
     /// The container to execute the command in
@@ -17,6 +23,7 @@ pub struct ExecCommand {
     /// The command to execute
     command: Vec<String>,
     /// Command executor for extensibility
+    #[serde(rename = "extra_args", with = "crate::command::raw_args")]
     pub executor: CommandExecutor,
     /// Run in detached mode
     detach: bool,
@@ -108,6 +115,12 @@ impl ExecCommand {
         }
     }
 
+    /// An exec command without a container or command, filled in by parsing
+    /// or deserializing
+    fn unset() -> Self {
+        Self::new("", Vec::new())
+    }
+
     /// Run in detached mode (background)
     ///
     /// # Examples
@@ -157,6 +170,13 @@ impl ExecCommand {
         self
     }
 
+    /// Pass an environment variable through from the host (`--env KEY`)
+    #[must_use]
+    pub fn env_pass_through(mut self, key: impl Into<String>) -> Self {
+        self.environment = self.environment.pass_through(key);
+        self
+    }
+
     /// Add multiple environment variables
     ///
     /// # Examples
@@ -291,6 +311,135 @@ impl ExecCommand {
     pub fn it(self) -> Self {
         self.interactive().tty()
     }
+
+    /// Start the command as an interactive session
+    ///
+    /// Runs `docker exec --interactive` without a TTY, overriding
+    /// [`tty`](Self::tty) and [`detach`](Self::detach), and hands out the
+    /// process's stdin, stdout and stderr. This drives a shell or REPL
+    /// inside the container over a single exec.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use docker_wrapper::ExecCommand;
+    /// use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut session = ExecCommand::new("web", vec!["sh".to_string()]).session()?;
+    /// let mut stdout = BufReader::new(&mut session.stdout).lines();
+    ///
+    /// session.stdin.write_all(b"echo $((6 * 7))\n").await?;
+    /// assert_eq!(stdout.next_line().await?.as_deref(), Some("42"));
+    /// session.stdin.write_all(b"exit 3\n").await?;
+    ///
+    /// drop(stdout);
+    /// assert_eq!(session.wait().await?.exit_code, 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the command cannot be started or an interceptor
+    /// vetoes it
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a Tokio runtime.
+    pub fn session(&self) -> Result<ExecSession> {
+        let mut command = self.clone();
+        command.interactive = true;
+        command.tty = false;
+        command.detach = false;
+        crate::stream::spawn_session(&command.executor, command.build_command_args())
+    }
+}
+
+impl ExecCommand {
+    /// Build an exec command from `docker exec` arguments
+    ///
+    /// The leading `docker` and `exec` (or `container exec`) are optional.
+    /// See [`crate::parse`] for the accepted syntax.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use docker_wrapper::ExecCommand;
+    ///
+    /// let exec_cmd = ExecCommand::from_args(["exec", "-it", "web", "sh"]).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for unknown flags, missing or invalid values, and a
+    /// missing container or command.
+    pub fn from_args<I, S>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut parser = ArgParser::new("exec", &[&["exec"], &["container", "exec"]], args);
+        let mut cmd = Self::unset();
+
+        while let Some(flag) = parser.next_flag() {
+            let p = &mut parser;
+            match flag.name.as_str() {
+                "-d" | "--detach" => cmd.detach = p.switch(&flag)?,
+                "--detach-keys" => cmd = cmd.detach_keys(p.value(&flag)?),
+                "-e" | "--env" => match p.env(&flag)? {
+                    (key, Some(value)) => cmd = cmd.env(key, value),
+                    (key, None) => cmd = cmd.env_pass_through(key),
+                },
+                "--env-file" => cmd = cmd.env_file(p.value(&flag)?),
+                "-i" | "--interactive" => cmd.interactive = p.switch(&flag)?,
+                "--privileged" => cmd.privileged = p.switch(&flag)?,
+                "-t" | "--tty" => cmd.tty = p.switch(&flag)?,
+                "-u" | "--user" => cmd = cmd.user(p.value(&flag)?),
+                "-w" | "--workdir" => cmd = cmd.workdir(p.value(&flag)?),
+                _ => return Err(p.unknown(&flag)),
+            }
+        }
+
+        let mut positionals = parser.finish().into_iter();
+        cmd.container = positionals
+            .next()
+            .ok_or_else(|| Error::invalid_config("docker exec requires a container"))?;
+        cmd.command = positionals.collect();
+        if cmd.command.is_empty() {
+            return Err(Error::invalid_config("docker exec requires a command"));
+        }
+
+        Ok(cmd)
+    }
+}
+
+impl std::str::FromStr for ExecCommand {
+    type Err = Error;
+
+    /// Parse a `docker exec ...` shell line
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_args(crate::parse::split(s)?)
+    }
+}
+
+impl Serialize for ExecCommand {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        Self::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ExecCommand {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let cmd = Self::deserialize(deserializer)?;
+        if cmd.container.is_empty() {
+            return Err(de::Error::missing_field("container"));
+        }
+        if cmd.command.is_empty() {
+            return Err(de::Error::missing_field("command"));
+        }
+        Ok(cmd)
+    }
 }
 
 #[async_trait]
@@ -319,10 +468,7 @@ impl DockerCommand for ExecCommand {
         }
 
         // Add environment variables
-        for (key, value) in self.environment.as_map() {
-            args.push("--env".to_string());
-            args.push(format!("{key}={value}"));
-        }
+        args.extend(self.environment.build_args());
 
         // Add environment files
         for env_file in &self.env_files {
@@ -380,6 +526,55 @@ impl DockerCommand for ExecCommand {
 mod tests {
     use super::*;
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_session_drives_process_over_pipes() {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let runner = crate::runner::ShellRunner::new(
+            r#"while read line; do echo "out $line"; echo "err $line" >&2; done; exit 3"#,
+        );
+        let mut cmd = ExecCommand::new("web", vec!["sh".to_string()])
+            .it()
+            .detach();
+        cmd.with_runner(runner.clone());
+
+        let mut session = cmd.session().unwrap();
+        let mut stdout = BufReader::new(&mut session.stdout).lines();
+        let mut stderr = BufReader::new(&mut session.stderr).lines();
+
+        session.stdin.write_all(b"ping\n").await.unwrap();
+        assert_eq!(stdout.next_line().await.unwrap().unwrap(), "out ping");
+        assert_eq!(stderr.next_line().await.unwrap().unwrap(), "err ping");
+        session.stdin.write_all(b"pong\n").await.unwrap();
+        assert_eq!(stdout.next_line().await.unwrap().unwrap(), "out pong");
+        session.stdin.write_all(b"last\n").await.unwrap();
+
+        drop((stdout, stderr));
+        let status = session.wait().await.unwrap();
+        assert_eq!(status.exit_code, 3);
+        assert!(!status.success);
+        assert_eq!(status.stdout.as_deref(), Some("out last\n"));
+        assert_eq!(status.stderr.as_deref(), Some("err pong\nerr last\n"));
+        assert_eq!(
+            runner.invocations()[0].args,
+            ["exec", "--interactive", "web", "sh"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_session_exit_resolves_without_wait() {
+        use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
+
+        let mut cmd = ExecCommand::new("web", vec!["true".to_string()]);
+        cmd.with_runner(FakeRunner::new().on(ArgMatcher::Any, FakeResponse::success("")));
+
+        let session = cmd.session().unwrap();
+        let status = session.exit.await.unwrap();
+        assert!(status.success);
+        assert!(status.stdout.is_none());
+    }
+
     #[test]
     fn test_exec_command_builder() {
         let cmd = ExecCommand::new("test-container", vec!["ls".to_string(), "-la".to_string()])
@@ -501,4 +696,127 @@ mod tests {
         assert!(args.contains(&"value".to_string()));
         assert!(args.contains(&"extra-arg".to_string()));
     }
+
+    #[test]
+    fn test_exec_command_from_args_round_trip() {
+        let cmd = ExecCommand::new(
+            "web",
+            vec!["sh".to_string(), "-c".to_string(), "ls -la".to_string()],
+        )
+        .detach()
+        .detach_keys("ctrl-a")
+        .env("LEVEL", "debug")
+        .env_pass_through("TOKEN")
+        .env_file("/etc/app.env")
+        .interactive()
+        .privileged()
+        .tty()
+        .user("root")
+        .workdir("/srv");
+        let args = cmd.build_command_args();
+
+        let parsed = ExecCommand::from_args(args.clone()).unwrap();
+        assert_eq!(parsed.build_command_args(), args);
+    }
+
+    #[test]
+    fn test_exec_command_parse_shell_line() {
+        let cmd: ExecCommand = "docker exec -it -u postgres db psql -c 'select 1'"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            cmd.build_command_args(),
+            [
+                "exec",
+                "--interactive",
+                "--tty",
+                "--user",
+                "postgres",
+                "db",
+                "psql",
+                "-c",
+                "select 1"
+            ]
+        );
+        assert!("docker exec web".parse::<ExecCommand>().is_err());
+        assert!("docker exec --latest web ls"
+            .parse::<ExecCommand>()
+            .is_err());
+    }
+
+    /// Serialized form of the command in `test_exec_command_serde_schema`
+    const EXEC_SCHEMA: &str = r#"{
+  "container": "web",
+  "command": [
+    "ls"
+  ],
+  "extra_args": [
+    "--extra"
+  ],
+  "detach": false,
+  "detach_keys": null,
+  "environment": {
+    "A": "1",
+    "B": "2",
+    "TOKEN": null
+  },
+  "env_files": [],
+  "interactive": false,
+  "privileged": false,
+  "tty": true,
+  "user": null,
+  "workdir": "/srv"
+}"#;
+
+    #[test]
+    fn test_exec_command_serde_schema() {
+        let mut cmd = ExecCommand::new("web", vec!["ls".to_string()])
+            .env("B", "2")
+            .env("A", "1")
+            .env_pass_through("TOKEN")
+            .tty()
+            .workdir("/srv");
+        cmd.arg("--extra");
+
+        let json = serde_json::to_value(&cmd).unwrap();
+        assert_eq!(
+            json,
+            serde_json::from_str::<serde_json::Value>(EXEC_SCHEMA).unwrap()
+        );
+        assert!(serde_json::to_string(&cmd)
+            .unwrap()
+            .contains(r#""environment":{"A":"1","B":"2","TOKEN":null}"#));
+
+        let restored: ExecCommand = serde_json::from_value(json).unwrap();
+        let mut expected = cmd.build_command_args();
+        let mut actual = restored.build_command_args();
+        expected.sort();
+        actual.sort();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_exec_command_serde_fixture() {
+        let cmd: ExecCommand = serde_json::from_str(EXEC_SCHEMA).unwrap();
+        assert_eq!(serde_json::to_string_pretty(&cmd).unwrap(), EXEC_SCHEMA);
+    }
+
+    #[test]
+    fn test_exec_command_deserialize_validates() {
+        let err = serde_json::from_str::<ExecCommand>(r#"{"command": ["ls"]}"#).unwrap_err();
+        assert!(
+            err.to_string().contains("missing field `container`"),
+            "{err}"
+        );
+
+        let err = serde_json::from_str::<ExecCommand>(r#"{"container": "web"}"#).unwrap_err();
+        assert!(err.to_string().contains("missing field `command`"), "{err}");
+
+        let err = serde_json::from_str::<ExecCommand>(
+            r#"{"container": "web", "command": ["ls"], "env": {"A": "1"}}"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("unknown field `env`"), "{err}");
+    }
 }
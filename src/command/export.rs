@@ -4,6 +4,7 @@
 
 use super::{CommandExecutor, CommandOutput, DockerCommand};
 use crate::error::Result;
+use crate::stream::ByteStream;
 use async_trait::async_trait;
 
 /// Docker export command builder
@@ -109,6 +110,39 @@ impl ExportCommand {
             output_file: self.output.clone(),
         })
     }
+
+    /// Start the export and read the tar archive as it is written
+    ///
+    /// Any [`output`](Self::output) file is ignored; the archive is written
+    /// to stdout and read from the returned stream.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use docker_wrapper::ExportCommand;
+    /// use tokio::net::TcpStream;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut archive = ExportCommand::new("web-server").reader()?;
+    /// let mut upload = TcpStream::connect("backup.internal:9000").await?;
+    /// tokio::io::copy(&mut archive, &mut upload).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the command cannot be started or an interceptor
+    /// vetoes it
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a Tokio runtime.
+    pub fn reader(&self) -> Result<ByteStream> {
+        let mut command = self.clone();
+        command.output = None;
+        crate::stream::spawn_byte_stream(&command.executor, command.build_command_args())
+    }
 }
 
 #[async_trait]
@@ -199,6 +233,29 @@ impl ExportResult {
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn test_reader_yields_raw_bytes() {
+        use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
+        use tokio::io::AsyncReadExt;
+
+        let fake = FakeRunner::new().on(
+            ArgMatcher::Any,
+            FakeResponse::success(b"\xff\0tar\n".to_vec()).with_stderr("warning\n"),
+        );
+        let mut cmd = ExportCommand::new("web").output("ignored.tar");
+        cmd.with_runner(fake.clone());
+
+        let mut archive = cmd.reader().unwrap();
+        let mut bytes = Vec::new();
+        archive.read_to_end(&mut bytes).await.unwrap();
+        assert_eq!(bytes, b"\xff\0tar\n");
+
+        let status = archive.wait().await.unwrap();
+        assert!(status.success);
+        assert_eq!(status.stderr.as_deref(), Some("warning\n"));
+        assert_eq!(fake.invocations()[0].args, ["export", "web"]);
+    }
+
     #[test]
     fn test_export_basic() {
         let cmd = ExportCommand::new("test-container");
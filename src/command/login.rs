@@ -3,7 +3,7 @@
 //! This module provides functionality to authenticate with Docker registries.
 //! It supports both Docker Hub and private registries with various authentication methods.
 
-use super::{CommandExecutor, CommandOutput, DockerCommand};
+use super::{CommandExecutor, CommandOutput, DockerCommand, StdinSource};
 use crate::error::Result;
 use async_trait::async_trait;
 use std::fmt;
@@ -103,15 +103,17 @@ impl LoginCommand {
 
     /// Enables reading password from stdin for security
     ///
-    /// When enabled, the password field is ignored and Docker will
-    /// prompt for password input via stdin.
+    /// When enabled, the password is written to the command's stdin when it
+    /// runs instead of being passed on the command line, so it never shows up
+    /// in process listings. If the password is empty, a custom source can be
+    /// supplied with [`DockerCommand::with_stdin`].
     ///
     /// # Examples
     ///
     /// ```rust
     /// use docker_wrapper::LoginCommand;
     ///
-    /// let login = LoginCommand::new("user", "")
+    /// let login = LoginCommand::new("user", "secret-token")
     ///     .password_stdin();
     /// ```
     #[must_use]
@@ -234,7 +236,14 @@ impl DockerCommand for LoginCommand {
 
     async fn execute(&self) -> Result<Self::Output> {
         let args = self.build_command_args();
-        let output = self.execute_command(args).await?;
+        let output = if self.password_stdin && !self.password.is_empty() {
+            // Attached here so a later executor swap cannot drop the password
+            let mut login = self.clone();
+            login.executor.stdin = Some(StdinSource::from(self.password.as_str()));
+            login.execute_command(args).await?
+        } else {
+            self.execute_command(args).await?
+        };
 
         Ok(LoginOutput { output })
     }
@@ -263,6 +272,7 @@ impl fmt::Display for LoginCommand {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
 
     #[test]
     fn test_login_command_basic() {
@@ -312,6 +322,28 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_login_command_password_stdin_feeds_password() {
+        let fake =
+            FakeRunner::new().on(ArgMatcher::Any, FakeResponse::success("Login Succeeded\n"));
+        let mut login = LoginCommand::new("user", "s3cret")
+            .password_stdin()
+            .executor(CommandExecutor::new());
+        login.with_runner(fake.clone());
+
+        login.execute().await.unwrap();
+
+        let invocation = &fake.invocations()[0];
+        let stdin = invocation.stdin.as_ref().unwrap();
+        assert_eq!(stdin.as_bytes(), Some(&b"s3cret"[..]));
+        assert!(!invocation.args.contains(&"s3cret".to_string()));
+
+        let mut login = LoginCommand::new("user", "").password_stdin();
+        login.with_runner(fake.clone());
+        login.execute().await.unwrap();
+        assert!(fake.invocations()[1].stdin.is_none());
+    }
+
     #[test]
     fn test_login_command_with_private_registry() {
         let login = LoginCommand::new("admin", "secret").registry("my-registry.example.com:5000");
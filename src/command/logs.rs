@@ -1,13 +1,44 @@
 //! Docker logs command implementation.
 //!
 //! This module provides the `docker logs` command for viewing container logs.
-
+//!
+//! [`LogsCommand::entries`] parses the output of `docker logs --timestamps`
+//! into [`LogEntry`]s, and [`MergedLogs`] follows several containers at once,
+//! interleaving their entries by timestamp:
+//!
+//! ```rust,no_run
+//! use docker_wrapper::{LogsCommand, MergedLogs};
+//! use std::time::{Duration, SystemTime};
+//!
+//! # async fn example() -> docker_wrapper::Result<()> {
+//! let since = SystemTime::now() - Duration::from_secs(300);
+//! let mut logs = MergedLogs::new()
+//!     .logs(LogsCommand::new("api").since_time(since).follow())
+//!     .logs(LogsCommand::new("db").since_time(since).follow())
+//!     .stream();
+//!
+//! while let Some(entry) = logs.next_entry().await {
+//!     let entry = entry?;
+//!     println!("{:>4} | {}", entry.container, entry.message);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use super::parse_util::parse_timestamp;
 use super::{CommandExecutor, CommandOutput, DockerCommand};
-use crate::error::Result;
-use crate::stream::{OutputLine, StreamResult, StreamableCommand};
+use crate::error::{Error, Result};
+use crate::stream::{OutputLine, StreamResult, StreamableCommand, OUTPUT_STREAM_CAPACITY};
+use crate::tracing_compat::debug;
 use async_trait::async_trait;
-use tokio::process::Command as TokioCommand;
+use futures_core::Stream;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::mpsc;
+use tokio::time::Instant;
 
 /// Docker logs command builder
 #[derive(Debug, Clone)]
@@ -81,6 +112,12 @@ impl LogsCommand {
         self
     }
 
+    /// Show logs since a point in time
+    #[must_use]
+    pub fn since_time(self, time: SystemTime) -> Self {
+        self.since(unix_time(time))
+    }
+
     /// Show logs until timestamp
     #[must_use]
     pub fn until(mut self, timestamp: impl Into<String>) -> Self {
@@ -88,6 +125,12 @@ impl LogsCommand {
         self
     }
 
+    /// Show logs until a point in time
+    #[must_use]
+    pub fn until_time(self, time: SystemTime) -> Self {
+        self.until(unix_time(time))
+    }
+
     /// Show extra details provided to logs
     #[must_use]
     pub fn details(mut self) -> Self {
@@ -105,6 +148,346 @@ impl LogsCommand {
     pub async fn run(&self) -> Result<CommandOutput> {
         self.execute().await
     }
+
+    /// Fetch the logs as entries in timestamp order
+    ///
+    /// Runs with `--timestamps`, which is added if not set. Lines without a
+    /// timestamp are skipped. Do not combine with [`follow`](Self::follow);
+    /// use [`entry_stream`](Self::entry_stream) instead.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The Docker daemon is not running
+    /// - The specified container doesn't exist
+    pub async fn entries(&self) -> Result<Vec<LogEntry>> {
+        let mut command = self.clone();
+        command.timestamps = true;
+        let output = command.execute().await?;
+
+        let mut entries: Vec<LogEntry> = output
+            .stdout
+            .lines()
+            .filter_map(|line| LogEntry::parse(&self.container, LogStream::Stdout, line))
+            .chain(
+                output
+                    .stderr
+                    .lines()
+                    .filter_map(|line| LogEntry::parse(&self.container, LogStream::Stderr, line)),
+            )
+            .collect();
+        entries.sort_by_key(|entry| entry.timestamp);
+        Ok(entries)
+    }
+
+    /// Stream the logs as entries while the CLI runs
+    ///
+    /// Useful with [`follow`](Self::follow). Since stdout and stderr are read
+    /// separately, entries are held for a short window to put them in
+    /// timestamp order; see [`MergedLogs::reorder_window`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a Tokio runtime.
+    #[must_use]
+    pub fn entry_stream(&self) -> LogEntryStream {
+        MergedLogs::new().logs(self.clone()).stream()
+    }
+
+    /// Run the CLI, sending its entries until it exits or `entries` closes
+    async fn forward_entries(self, entries: mpsc::Sender<Result<LogEntry>>) {
+        let (mut lines, completion) = match self.stream_output() {
+            Ok(stream) => stream,
+            Err(e) => {
+                let _ = entries.send(Err(e)).await;
+                return;
+            }
+        };
+        let mut stderr = Vec::new();
+
+        loop {
+            let line = tokio::select! {
+                line = lines.next_line() => line,
+                () = entries.closed() => return,
+            };
+            let (stream, text) = match line {
+                Some(OutputLine::Stdout(text)) => (LogStream::Stdout, text),
+                Some(OutputLine::Stderr(text)) => (LogStream::Stderr, text),
+                None => break,
+            };
+            let Some(entry) = LogEntry::parse(&self.container, stream, &text) else {
+                if stream == LogStream::Stderr {
+                    stderr.push(text);
+                } else {
+                    debug!(container = %self.container, "skipping log line without timestamp");
+                }
+                continue;
+            };
+            if entries.send(Ok(entry)).await.is_err() {
+                return;
+            }
+        }
+
+        let result = completion.await.and_then(|status| {
+            if status.success {
+                Ok(())
+            } else {
+                Err(self.executor.failure(
+                    "docker logs".to_string(),
+                    status.exit_code,
+                    "",
+                    &stderr.join("\n"),
+                ))
+            }
+        });
+        match result {
+            Ok(()) | Err(Error::Interrupted) => {}
+            Err(e) => {
+                let _ = entries.send(Err(e)).await;
+            }
+        }
+    }
+}
+
+/// `--since`/`--until` value for a point in time
+fn unix_time(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    format!(
+        "{}.{:09}",
+        since_epoch.as_secs(),
+        since_epoch.subsec_nanos()
+    )
+}
+
+/// Stream a log line was written to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LogStream {
+    /// Standard output
+    Stdout,
+    /// Standard error
+    Stderr,
+}
+
+/// One line of a container's logs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    /// Container the line came from, as it was requested
+    pub container: String,
+    /// When the container wrote the line
+    pub timestamp: SystemTime,
+    /// Stream the line was written to
+    pub stream: LogStream,
+    /// The line, without its timestamp
+    pub message: String,
+}
+
+impl LogEntry {
+    /// Parse a line of `docker logs --timestamps` output
+    ///
+    /// Returns `None` if the line does not start with a timestamp.
+    #[must_use]
+    pub fn parse(container: &str, stream: LogStream, line: &str) -> Option<Self> {
+        let (timestamp, message) = line.split_once(' ').unwrap_or((line, ""));
+        Some(Self {
+            container: container.to_string(),
+            timestamp: parse_timestamp(timestamp)?,
+            stream,
+            message: message.trim_end_matches('\r').to_string(),
+        })
+    }
+}
+
+/// Logs of several containers, merged in timestamp order
+///
+/// Each [`LogsCommand`] keeps its own options; `--timestamps` is added to
+/// all of them.
+#[derive(Debug, Clone)]
+pub struct MergedLogs {
+    commands: Vec<LogsCommand>,
+    reorder_window: Duration,
+}
+
+impl MergedLogs {
+    /// Create a merge with no containers
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            commands: Vec::new(),
+            reorder_window: Duration::from_millis(100),
+        }
+    }
+
+    /// Add a container's logs
+    #[must_use]
+    pub fn logs(mut self, command: LogsCommand) -> Self {
+        self.commands.push(command);
+        self
+    }
+
+    /// How long [`stream`](Self::stream) holds an entry back for earlier
+    /// entries to arrive (default 100ms)
+    ///
+    /// Entries arriving further apart than this may be yielded out of
+    /// timestamp order; a zero window yields them as they arrive.
+    #[must_use]
+    pub fn reorder_window(mut self, window: Duration) -> Self {
+        self.reorder_window = window;
+        self
+    }
+
+    /// Fetch the logs of every container and merge them
+    ///
+    /// Entries with equal timestamps keep the order the containers were
+    /// added in.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of any container; see [`LogsCommand::entries`]
+    pub async fn entries(&self) -> Result<Vec<LogEntry>> {
+        let tasks: Vec<_> = self
+            .commands
+            .iter()
+            .cloned()
+            .map(|command| tokio::spawn(async move { command.entries().await }))
+            .collect();
+        let mut entries = Vec::new();
+        for task in tasks {
+            let container_entries = task
+                .await
+                .map_err(|e| Error::custom(format!("log task failed: {e}")))??;
+            entries.extend(container_entries);
+        }
+        entries.sort_by_key(|entry| entry.timestamp);
+        Ok(entries)
+    }
+
+    /// Stream the entries of every container as they are written
+    ///
+    /// The stream ends when every CLI has exited. The error of a container
+    /// whose CLI failed is yielded in place; the others keep going. Dropping
+    /// the stream stops the CLIs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a Tokio runtime.
+    #[must_use]
+    pub fn stream(&self) -> LogEntryStream {
+        let (tx, rx) = mpsc::channel(OUTPUT_STREAM_CAPACITY);
+        tokio::spawn(self.clone().merge(tx));
+        LogEntryStream { entries: rx }
+    }
+
+    async fn merge(self, output: mpsc::Sender<Result<LogEntry>>) {
+        let (tx, mut rx) = mpsc::channel(OUTPUT_STREAM_CAPACITY);
+        for mut command in self.commands {
+            command.timestamps = true;
+            tokio::spawn(command.forward_entries(tx.clone()));
+        }
+        drop(tx);
+
+        let mut held = BinaryHeap::new();
+        let mut sequence = 0u64;
+        let mut open = true;
+        while open || !held.is_empty() {
+            let release_at = held
+                .peek()
+                .map(|Reverse(held): &Reverse<HeldEntry>| held.received + self.reorder_window);
+            tokio::select! {
+                received = rx.recv(), if open => match received {
+                    Some(Ok(entry)) => {
+                        held.push(Reverse(HeldEntry { entry, sequence, received: Instant::now() }));
+                        sequence += 1;
+                        continue;
+                    }
+                    Some(Err(e)) => {
+                        if output.send(Err(e)).await.is_err() {
+                            return;
+                        }
+                        continue;
+                    }
+                    None => open = false,
+                },
+                () = sleep_until(release_at) => {}
+                () = output.closed() => return,
+            }
+
+            let now = Instant::now();
+            while let Some(Reverse(next)) = held.peek() {
+                if open && next.received + self.reorder_window > now {
+                    break;
+                }
+                let Reverse(next) = held.pop().expect("peeked entry");
+                if output.send(Ok(next.entry)).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+impl Default for MergedLogs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sleep until `deadline`, or forever without one
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// An entry waiting in the reorder window
+#[derive(Debug)]
+struct HeldEntry {
+    entry: LogEntry,
+    /// Arrival order, to keep equal timestamps stable
+    sequence: u64,
+    received: Instant,
+}
+
+impl Ord for HeldEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.entry.timestamp, self.sequence).cmp(&(other.entry.timestamp, other.sequence))
+    }
+}
+
+impl PartialOrd for HeldEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for HeldEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for HeldEntry {}
+
+/// Live entries from [`MergedLogs::stream`] or [`LogsCommand::entry_stream`]
+///
+/// Dropping the stream stops the CLIs.
+#[derive(Debug)]
+pub struct LogEntryStream {
+    entries: mpsc::Receiver<Result<LogEntry>>,
+}
+
+impl LogEntryStream {
+    /// Wait for the next entry, or `None` once the stream has ended
+    pub async fn next_entry(&mut self) -> Option<Result<LogEntry>> {
+        self.entries.recv().await
+    }
+}
+
+impl Stream for LogEntryStream {
+    type Item = Result<LogEntry>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.entries.poll_recv(cx)
+    }
 }
 
 #[async_trait]
@@ -171,25 +554,13 @@ impl StreamableCommand for LogsCommand {
     where
         F: FnMut(OutputLine) + Send + 'static,
     {
-        let mut cmd = TokioCommand::new("docker");
-        cmd.arg("logs");
-
-        for arg in self.build_command_args() {
-            cmd.arg(arg);
-        }
-
-        crate::stream::stream_command(cmd, handler, "logs").await
+        crate::stream::stream_command(&self.executor, self.build_command_args(), handler, "logs")
+            .await
     }
 
     async fn stream_channel(&self) -> Result<(mpsc::Receiver<OutputLine>, StreamResult)> {
-        let mut cmd = TokioCommand::new("docker");
-        cmd.arg("logs");
-
-        for arg in self.build_command_args() {
-            cmd.arg(arg);
-        }
-
-        crate::stream::stream_command_channel(cmd, "logs").await
+        crate::stream::stream_command_channel(&self.executor, self.build_command_args(), "logs")
+            .await
     }
 }
 
@@ -229,6 +600,7 @@ impl LogsCommand {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
 
     #[test]
     fn test_logs_basic() {
@@ -265,6 +637,132 @@ mod tests {
         assert_eq!(args, vec!["logs", "--since", "10m", "test-container"]);
     }
 
+    #[test]
+    fn test_logs_since_until_time() {
+        let since = UNIX_EPOCH + Duration::new(1_714_564_800, 5);
+        let cmd = LogsCommand::new("test-container")
+            .since_time(since)
+            .until_time(since + Duration::from_secs(60));
+        let args = cmd.build_command_args();
+        assert_eq!(
+            args,
+            vec![
+                "logs",
+                "--since",
+                "1714564800.000000005",
+                "--until",
+                "1714564860.000000005",
+                "test-container"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_log_entry_parse() {
+        let entry = LogEntry::parse(
+            "web",
+            LogStream::Stderr,
+            "2024-05-01T12:00:00.250000000Z listening on :8080\r",
+        )
+        .unwrap();
+        assert_eq!(entry.container, "web");
+        assert_eq!(
+            entry.timestamp,
+            UNIX_EPOCH + Duration::new(1_714_564_800, 250_000_000)
+        );
+        assert_eq!(entry.stream, LogStream::Stderr);
+        assert_eq!(entry.message, "listening on :8080");
+
+        let empty = LogEntry::parse("web", LogStream::Stdout, "2024-05-01T12:00:00Z").unwrap();
+        assert_eq!(empty.message, "");
+        assert!(LogEntry::parse("web", LogStream::Stdout, "no timestamp here").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_entries_merges_stdout_and_stderr() {
+        let fake = FakeRunner::new().on(
+            ArgMatcher::Any,
+            FakeResponse::success("2024-05-01T12:00:01Z first\n2024-05-01T12:00:03Z third\n")
+                .with_stderr("2024-05-01T12:00:02Z second\n"),
+        );
+        let mut cmd = LogsCommand::new("web");
+        cmd.with_runner(fake.clone());
+
+        let entries = cmd.entries().await.unwrap();
+        let messages: Vec<(&str, LogStream)> = entries
+            .iter()
+            .map(|e| (e.message.as_str(), e.stream))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                ("first", LogStream::Stdout),
+                ("second", LogStream::Stderr),
+                ("third", LogStream::Stdout)
+            ]
+        );
+        assert_eq!(fake.invocations()[0].args, ["logs", "--timestamps", "web"]);
+    }
+
+    /// A followed logs command whose CLI responds with `response`, provided
+    /// it was asked for timestamps
+    fn scripted(container: &str, response: FakeResponse) -> LogsCommand {
+        let mut cmd = LogsCommand::new(container).follow();
+        cmd.with_runner(FakeRunner::new().on(ArgMatcher::contains(["--timestamps"]), response));
+        cmd
+    }
+
+    #[tokio::test]
+    async fn test_merged_stream_orders_containers_by_timestamp() {
+        let web = scripted(
+            "web",
+            FakeResponse::success("2024-05-01T12:00:01Z web one\n")
+                .with_stderr("2024-05-01T12:00:03Z web two\n"),
+        );
+        let db = scripted(
+            "db",
+            FakeResponse::success("2024-05-01T12:00:02Z db one\nnot a log line\n")
+                .with_delay(Duration::from_millis(50)),
+        );
+        let mut logs = MergedLogs::new()
+            .logs(web)
+            .logs(db)
+            .reorder_window(Duration::from_millis(500))
+            .stream();
+
+        let mut merged = Vec::new();
+        while let Some(entry) = logs.next_entry().await {
+            let entry = entry.unwrap();
+            merged.push(format!("{}: {}", entry.container, entry.message));
+        }
+        assert_eq!(merged, ["web: web one", "db: db one", "web: web two"]);
+    }
+
+    #[tokio::test]
+    async fn test_merged_stream_yields_container_errors() {
+        let web = scripted("web", FakeResponse::success("2024-05-01T12:00:01Z up\n"));
+        let gone = scripted(
+            "gone",
+            FakeResponse::failure(1, "Error response from daemon: No such container: gone\n"),
+        );
+        let mut logs = MergedLogs::new()
+            .logs(web)
+            .logs(gone)
+            .reorder_window(Duration::ZERO)
+            .stream();
+
+        let mut messages = Vec::new();
+        let mut errors = 0;
+        while let Some(entry) = logs.next_entry().await {
+            match entry {
+                Ok(entry) => messages.push(entry.message),
+                Err(_) => errors += 1,
+            }
+        }
+        assert_eq!(messages, ["up"]);
+        assert_eq!(errors, 1);
+    }
+
     #[test]
     fn test_logs_all_options() {
         let cmd = LogsCommand::new("test-container")
@@ -0,0 +1,118 @@
+//! Parsers for values that appear in the output of several commands.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Parse a size as printed by the Docker CLI, such as `1.2kB` or `512MiB`
+///
+/// `kB`, `MB`, ... are powers of 1000 and `KiB`, `MiB`, ... powers of 1024;
+/// the CLI uses the former for I/O and the latter for memory.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub(crate) fn parse_size(size: &str) -> Option<u64> {
+    let size = size.trim();
+    let unit_start = size
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(size.len());
+    let (number, unit) = size.split_at(unit_start);
+    let value: f64 = number.parse().ok()?;
+    let multiplier = match unit.trim() {
+        "" | "B" => 1.0,
+        "kB" | "KB" | "k" | "K" => 1e3,
+        "MB" | "M" => 1e6,
+        "GB" | "G" => 1e9,
+        "TB" | "T" => 1e12,
+        "PB" | "P" => 1e15,
+        "KiB" => 1024.0,
+        "MiB" => 1024.0 * 1024.0,
+        "GiB" => 1024.0 * 1024.0 * 1024.0,
+        "TiB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        "PiB" => 1024.0 * 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((value * multiplier).round() as u64)
+}
+
+/// Parse an RFC 3339 timestamp as Go writes it, such as
+/// `2024-05-01T12:00:00.123456789Z` or `2024-05-01T14:00:00+02:00`
+pub(crate) fn parse_timestamp(text: &str) -> Option<SystemTime> {
+    let (date, time) = text.split_once('T')?;
+    let mut date = date.splitn(3, '-');
+    let year: i64 = date.next()?.parse().ok()?;
+    let month: i64 = date.next()?.parse().ok()?;
+    let day: i64 = date.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let (clock, offset) = if let Some(clock) = time.strip_suffix('Z') {
+        (clock, 0)
+    } else {
+        let sign_at = time.rfind(['+', '-'])?;
+        let (clock, zone) = time.split_at(sign_at);
+        let (hours, minutes) = zone[1..].split_once(':')?;
+        let offset = hours.parse::<i64>().ok()? * 3600 + minutes.parse::<i64>().ok()? * 60;
+        (
+            clock,
+            if zone.starts_with('-') {
+                -offset
+            } else {
+                offset
+            },
+        )
+    };
+    let (hms, fraction) = clock.split_once('.').unwrap_or((clock, ""));
+    let mut hms = hms.splitn(3, ':');
+    let hours: i64 = hms.next()?.parse().ok()?;
+    let minutes: i64 = hms.next()?.parse().ok()?;
+    let seconds: i64 = hms.next()?.parse().ok()?;
+    let nanos: u32 = if fraction.is_empty() {
+        0
+    } else {
+        format!("{fraction:0<9}").get(..9)?.parse().ok()?
+    };
+
+    // Days since 1970-01-01 in the proleptic Gregorian calendar
+    let shifted_year = if month <= 2 { year - 1 } else { year };
+    let era = shifted_year.div_euclid(400);
+    let year_of_era = shifted_year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    let secs = days * 86_400 + hours * 3600 + minutes * 60 + seconds - offset;
+    Some(UNIX_EPOCH + Duration::new(u64::try_from(secs).ok()?, nanos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size_units() {
+        assert_eq!(parse_size("0B"), Some(0));
+        assert_eq!(parse_size("1.2kB"), Some(1_200));
+        assert_eq!(parse_size("6.7MB"), Some(6_700_000));
+        assert_eq!(parse_size("512MiB"), Some(512 * 1024 * 1024));
+        assert_eq!(parse_size("1.5GiB"), Some(3 * 512 * 1024 * 1024));
+        assert_eq!(parse_size("--"), None);
+        assert_eq!(parse_size("12 parsecs"), None);
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        let epoch = |secs, nanos| Some(UNIX_EPOCH + Duration::new(secs, nanos));
+        assert_eq!(parse_timestamp("1970-01-01T00:00:00Z"), epoch(0, 0));
+        assert_eq!(
+            parse_timestamp("2024-05-01T12:00:00.123456789Z"),
+            epoch(1_714_564_800, 123_456_789)
+        );
+        assert_eq!(
+            parse_timestamp("2024-05-01T14:00:00.5+02:00"),
+            epoch(1_714_564_800, 500_000_000)
+        );
+        assert_eq!(
+            parse_timestamp("2024-02-29T00:00:00-01:30"),
+            epoch(1_709_170_200, 0)
+        );
+        assert_eq!(parse_timestamp("yesterday"), None);
+    }
+}
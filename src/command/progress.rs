@@ -0,0 +1,546 @@
+//! Progress of image pulls and pushes.
+//!
+//! `docker pull` and `docker push` report on every layer as it moves through
+//! its phases. [`ProgressEvent::parse`] turns those lines into typed events
+//! and [`TransferProgress`] folds them into overall progress:
+//!
+//! ```rust
+//! use docker_wrapper::{LayerPhase, ProgressEvent, TransferProgress};
+//!
+//! let mut progress = TransferProgress::new();
+//! for line in [
+//!     "a2abf6c4d29d: Pulling fs layer",
+//!     "a2abf6c4d29d: Downloading [==>    ]  1.2MB/3.4MB",
+//!     "Digest: sha256:2f0f2c5f9cf5b2d2a4e1d3e0c1b4b0c8b0f5f0b7c4d9d2f6f5e2e6f0a9b8c7d6",
+//! ] {
+//!     if let Some(event) = ProgressEvent::parse(line) {
+//!         progress.update(&event);
+//!     }
+//! }
+//!
+//! assert_eq!(progress.layers()[0].phase, LayerPhase::Downloading);
+//! assert_eq!(progress.transferred_bytes(), 1_200_000);
+//! assert!(progress.digest().is_some());
+//! ```
+//!
+//! [`PullCommand::run_with_progress`](crate::PullCommand::run_with_progress)
+//! and [`PushCommand::run_with_progress`](crate::PushCommand::run_with_progress)
+//! deliver the events while the command runs.
+//!
+//! The CLI only prints byte counts when its output is a terminal; through a
+//! pipe, layers report their phases without sizes.
+
+use super::parse_util::parse_size;
+use super::{CommandOutput, DockerCommand};
+use crate::error::Result;
+use crate::stream::OutputLine;
+
+/// Phase of a layer during a pull or push
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayerPhase {
+    /// Queued for download (`Pulling fs layer`)
+    Queued,
+    /// Waiting for another layer
+    Waiting,
+    /// Being downloaded
+    Downloading,
+    /// Download being verified (`Verifying Checksum`)
+    Verifying,
+    /// Downloaded (`Download complete`)
+    Downloaded,
+    /// Being unpacked
+    Extracting,
+    /// Pulled and unpacked (`Pull complete`)
+    Pulled,
+    /// Being prepared for upload
+    Preparing,
+    /// Being uploaded
+    Pushing,
+    /// Uploaded
+    Pushed,
+    /// Already present at the destination (`Already exists` when pulling,
+    /// `Layer already exists` when pushing)
+    AlreadyExists,
+    /// Mounted from another repository in the same registry
+    MountedFrom(String),
+    /// Transfer failed and will be retried (`Retrying in 5 seconds`)
+    Retrying,
+    /// Any other phase, as printed
+    Other(String),
+}
+
+impl LayerPhase {
+    /// Parse a phase as printed by the CLI
+    fn parse(text: &str) -> Self {
+        match text {
+            "Pulling fs layer" => Self::Queued,
+            "Waiting" => Self::Waiting,
+            "Downloading" => Self::Downloading,
+            "Verifying Checksum" => Self::Verifying,
+            "Download complete" => Self::Downloaded,
+            "Extracting" => Self::Extracting,
+            "Pull complete" => Self::Pulled,
+            "Preparing" => Self::Preparing,
+            "Pushing" => Self::Pushing,
+            "Pushed" => Self::Pushed,
+            "Already exists" | "Layer already exists" => Self::AlreadyExists,
+            _ => {
+                if let Some(source) = text.strip_prefix("Mounted from ") {
+                    Self::MountedFrom(source.to_string())
+                } else if text.starts_with("Retrying in ") {
+                    Self::Retrying
+                } else {
+                    Self::Other(text.to_string())
+                }
+            }
+        }
+    }
+
+    /// Whether the layer's bytes have all been transferred
+    #[must_use]
+    pub fn is_transferred(&self) -> bool {
+        matches!(self, Self::Verifying | Self::Downloaded | Self::Extracting) || self.is_done()
+    }
+
+    /// Whether nothing is left to do for the layer
+    #[must_use]
+    pub fn is_done(&self) -> bool {
+        matches!(
+            self,
+            Self::Pulled | Self::Pushed | Self::AlreadyExists | Self::MountedFrom(_)
+        )
+    }
+}
+
+/// Progress of one layer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerProgress {
+    /// Short layer ID
+    pub id: String,
+    /// Current phase
+    pub phase: LayerPhase,
+    /// Bytes transferred or extracted so far, when reported
+    pub current: Option<u64>,
+    /// Size of the layer in bytes, when reported
+    pub total: Option<u64>,
+}
+
+/// One line of `docker pull` or `docker push` output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgressEvent {
+    /// A layer changed phase or made progress
+    Layer(LayerProgress),
+    /// Digest of the image that was pulled or pushed
+    Digest(String),
+    /// Outcome of a pull, e.g. `Downloaded newer image for redis:7`
+    Status(String),
+    /// Any other line, such as `7: Pulling from library/redis`
+    Message(String),
+}
+
+impl ProgressEvent {
+    /// Parse a line of output, returning `None` for blank lines
+    #[must_use]
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = strip_terminal_codes(line);
+        let line = line.trim();
+        if line.is_empty() {
+            return None;
+        }
+
+        if let Some(digest) = line.strip_prefix("Digest: ") {
+            return Some(Self::Digest(digest.trim().to_string()));
+        }
+        if let Some(status) = line.strip_prefix("Status: ") {
+            return Some(Self::Status(status.trim().to_string()));
+        }
+        // Push summary: `<tag>: digest: sha256:... size: 1234`
+        if let Some((_, summary)) = line.split_once(": digest: ") {
+            let digest = summary.split_whitespace().next().unwrap_or_default();
+            return Some(Self::Digest(digest.to_string()));
+        }
+
+        match line.split_once(": ") {
+            Some((id, rest)) if is_layer_id(id) => Some(Self::Layer(parse_layer(id, rest))),
+            _ => Some(Self::Message(line.to_string())),
+        }
+    }
+}
+
+/// Whether `id` is a short layer ID (12 hex digits)
+fn is_layer_id(id: &str) -> bool {
+    id.len() == 12 && id.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Parse the phase and byte counts following a layer ID
+///
+/// Byte counts follow the phase, after an optional progress bar:
+/// `Downloading [=====>      ]  1.2MB/3.4MB`.
+fn parse_layer(id: &str, rest: &str) -> LayerProgress {
+    let (phase, counts) = match rest.find('[') {
+        Some(bar) => (
+            &rest[..bar],
+            rest[bar..].split_once(']').map_or("", |(_, counts)| counts),
+        ),
+        None => match rest.find(|c: char| c.is_ascii_digit()) {
+            Some(start)
+                if !rest.starts_with("Retrying in ") && !rest.starts_with("Mounted from ") =>
+            {
+                rest.split_at(start)
+            }
+            _ => (rest, ""),
+        },
+    };
+
+    let counts = counts.split_whitespace().next().unwrap_or_default();
+    let (current, total) = match counts.split_once('/') {
+        Some((current, total)) => (parse_size(current), parse_size(total)),
+        None => (parse_size(counts), None),
+    };
+
+    LayerProgress {
+        id: id.to_string(),
+        phase: LayerPhase::parse(phase.trim()),
+        current,
+        total,
+    }
+}
+
+/// Drop ANSI escape sequences and everything before the last carriage
+/// return, leaving the text a terminal would show
+fn strip_terminal_codes(line: &str) -> String {
+    let line = line.rsplit('\r').next().unwrap_or_default();
+    let mut text = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            if chars.next() == Some('[') {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+        } else {
+            text.push(c);
+        }
+    }
+    text
+}
+
+/// Overall progress of a pull or push, built from its events
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransferProgress {
+    layers: Vec<LayerProgress>,
+    digest: Option<String>,
+    status: Option<String>,
+}
+
+impl TransferProgress {
+    /// Create progress with no layers
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply an event
+    ///
+    /// A layer keeps the last size it reported when later events have none.
+    pub fn update(&mut self, event: &ProgressEvent) {
+        match event {
+            ProgressEvent::Layer(layer) => {
+                match self.layers.iter_mut().find(|known| known.id == layer.id) {
+                    Some(known) => {
+                        known.phase = layer.phase.clone();
+                        known.current = layer.current;
+                        known.total = layer.total.or(known.total);
+                    }
+                    None => self.layers.push(layer.clone()),
+                }
+            }
+            ProgressEvent::Digest(digest) => self.digest = Some(digest.clone()),
+            ProgressEvent::Status(status) => self.status = Some(status.clone()),
+            ProgressEvent::Message(_) => {}
+        }
+    }
+
+    /// Layers in the order they first appeared
+    #[must_use]
+    pub fn layers(&self) -> &[LayerProgress] {
+        &self.layers
+    }
+
+    /// Number of layers with nothing left to do
+    #[must_use]
+    pub fn completed_layers(&self) -> usize {
+        self.layers.iter().filter(|l| l.phase.is_done()).count()
+    }
+
+    /// Whether layers were reported and all of them are done
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        !self.layers.is_empty() && self.completed_layers() == self.layers.len()
+    }
+
+    /// Bytes transferred so far, counting transferred layers in full
+    #[must_use]
+    pub fn transferred_bytes(&self) -> u64 {
+        self.layers
+            .iter()
+            .filter_map(|layer| {
+                if layer.phase.is_transferred() {
+                    layer.total.or(layer.current)
+                } else {
+                    layer.current
+                }
+            })
+            .sum()
+    }
+
+    /// Sum of the layer sizes reported so far
+    #[must_use]
+    pub fn total_bytes(&self) -> u64 {
+        self.layers.iter().filter_map(|layer| layer.total).sum()
+    }
+
+    /// Overall progress from 0.0 to 1.0, or `None` before any layer is seen
+    ///
+    /// Every layer counts equally: a transferred layer counts as 1, one in
+    /// flight as the fraction of its bytes transferred, when reported.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn fraction(&self) -> Option<f64> {
+        if self.layers.is_empty() {
+            return None;
+        }
+        let sum: f64 = self
+            .layers
+            .iter()
+            .map(|layer| match (layer.current, layer.total) {
+                _ if layer.phase.is_transferred() => 1.0,
+                (Some(current), Some(total)) if total > 0 => {
+                    (current as f64 / total as f64).min(1.0)
+                }
+                _ => 0.0,
+            })
+            .sum();
+        Some(sum / self.layers.len() as f64)
+    }
+
+    /// Digest of the image, once reported
+    #[must_use]
+    pub fn digest(&self) -> Option<&str> {
+        self.digest.as_deref()
+    }
+
+    /// Outcome of the pull, once reported
+    #[must_use]
+    pub fn status(&self) -> Option<&str> {
+        self.status.as_deref()
+    }
+}
+
+impl FromIterator<ProgressEvent> for TransferProgress {
+    fn from_iter<I: IntoIterator<Item = ProgressEvent>>(events: I) -> Self {
+        let mut progress = Self::new();
+        for event in events {
+            progress.update(&event);
+        }
+        progress
+    }
+}
+
+/// Progress described by the output of a finished pull or push
+pub(crate) fn progress_from_output(output: &CommandOutput) -> TransferProgress {
+    output
+        .stdout
+        .lines()
+        .filter_map(ProgressEvent::parse)
+        .collect()
+}
+
+/// Run `command`, passing each progress event to `on_progress` as it is
+/// printed
+///
+/// Returns the output, as [`DockerCommand::execute`] would, and the final
+/// progress.
+pub(crate) async fn run_with_progress<C, F>(
+    command: &C,
+    mut on_progress: F,
+) -> Result<(CommandOutput, TransferProgress)>
+where
+    C: DockerCommand + Sync,
+    F: FnMut(&ProgressEvent) + Send,
+{
+    let (mut lines, completion) = command.stream_output()?;
+    let mut progress = TransferProgress::new();
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+
+    while let Some(line) = lines.next_line().await {
+        match line {
+            OutputLine::Stdout(text) => {
+                if let Some(event) = ProgressEvent::parse(&text) {
+                    progress.update(&event);
+                    on_progress(&event);
+                }
+                stdout.push(text);
+            }
+            OutputLine::Stderr(text) => stderr.push(text),
+        }
+    }
+
+    let status = completion.await?;
+    let output = CommandOutput {
+        stdout: stdout.join("\n"),
+        stderr: stderr.join("\n"),
+        exit_code: status.exit_code,
+        success: status.success,
+    };
+    if !output.success {
+        let args = command.build_command_args();
+        return Err(command.get_executor().failure(
+            format!("docker {}", args.first().map_or("", String::as_str)),
+            output.exit_code,
+            &output.stdout,
+            &output.stderr,
+        ));
+    }
+    Ok((output, progress))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layer(line: &str) -> LayerProgress {
+        match ProgressEvent::parse(line) {
+            Some(ProgressEvent::Layer(layer)) => layer,
+            other => panic!("expected a layer event, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_pull_lines() {
+        assert_eq!(
+            ProgressEvent::parse("7: Pulling from library/redis"),
+            Some(ProgressEvent::Message(
+                "7: Pulling from library/redis".to_string()
+            ))
+        );
+        assert_eq!(
+            layer("a2abf6c4d29d: Pulling fs layer").phase,
+            LayerPhase::Queued
+        );
+        assert_eq!(
+            layer("a2abf6c4d29d: Downloading [=====>      ]  1.2MB/3.4MB"),
+            LayerProgress {
+                id: "a2abf6c4d29d".to_string(),
+                phase: LayerPhase::Downloading,
+                current: Some(1_200_000),
+                total: Some(3_400_000),
+            }
+        );
+        let extracting = layer("a2abf6c4d29d: Extracting  32.77kB/3.4MB");
+        assert_eq!(extracting.phase, LayerPhase::Extracting);
+        assert_eq!(extracting.current, Some(32_770));
+        assert_eq!(
+            layer("c7b0b0b2e9f1: Verifying Checksum").phase,
+            LayerPhase::Verifying
+        );
+        assert_eq!(
+            layer("c7b0b0b2e9f1: Already exists").phase,
+            LayerPhase::AlreadyExists
+        );
+        assert_eq!(
+            ProgressEvent::parse("Digest: sha256:abc"),
+            Some(ProgressEvent::Digest("sha256:abc".to_string()))
+        );
+        assert_eq!(
+            ProgressEvent::parse("Status: Image is up to date for redis:7"),
+            Some(ProgressEvent::Status(
+                "Image is up to date for redis:7".to_string()
+            ))
+        );
+        assert_eq!(ProgressEvent::parse("  "), None);
+    }
+
+    #[test]
+    fn test_parse_push_lines() {
+        assert_eq!(
+            layer("5f70bf18a086: Pushing [>      ]  512B/2kB"),
+            LayerProgress {
+                id: "5f70bf18a086".to_string(),
+                phase: LayerPhase::Pushing,
+                current: Some(512),
+                total: Some(2_000),
+            }
+        );
+        assert_eq!(
+            layer("5f70bf18a086: Layer already exists").phase,
+            LayerPhase::AlreadyExists
+        );
+        assert_eq!(
+            layer("5f70bf18a086: Mounted from library/redis7").phase,
+            LayerPhase::MountedFrom("library/redis7".to_string())
+        );
+        let retrying = layer("5f70bf18a086: Retrying in 5 seconds");
+        assert_eq!(retrying.phase, LayerPhase::Retrying);
+        assert_eq!(retrying.current, None);
+        assert_eq!(
+            ProgressEvent::parse("v1: digest: sha256:def size: 1570"),
+            Some(ProgressEvent::Digest("sha256:def".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_strips_terminal_codes() {
+        let event = layer("\u{1b}[2A\u{1b}[2Ka2abf6c4d29d: Downloading  1MB/2MB\u{1b}[2B");
+        assert_eq!(event.phase, LayerPhase::Downloading);
+        assert_eq!(event.total, Some(2_000_000));
+    }
+
+    #[test]
+    fn test_transfer_progress_aggregates_layers() {
+        let progress: TransferProgress = [
+            "a2abf6c4d29d: Pulling fs layer",
+            "c7b0b0b2e9f1: Pulling fs layer",
+            "a2abf6c4d29d: Downloading [==>   ]  1MB/4MB",
+            "c7b0b0b2e9f1: Downloading [=>    ]  1MB/2MB",
+            "c7b0b0b2e9f1: Download complete",
+        ]
+        .into_iter()
+        .filter_map(ProgressEvent::parse)
+        .collect();
+
+        assert_eq!(progress.layers().len(), 2);
+        assert_eq!(progress.layers()[1].total, Some(2_000_000));
+        assert_eq!(progress.transferred_bytes(), 3_000_000);
+        assert_eq!(progress.total_bytes(), 6_000_000);
+        assert_eq!(progress.fraction(), Some(0.625));
+        assert_eq!(progress.completed_layers(), 0);
+        assert!(!progress.is_complete());
+    }
+
+    #[test]
+    fn test_transfer_progress_completes() {
+        let progress: TransferProgress = [
+            "a2abf6c4d29d: Already exists",
+            "c7b0b0b2e9f1: Pull complete",
+            "Digest: sha256:abc",
+            "Status: Downloaded newer image for redis:7",
+        ]
+        .into_iter()
+        .filter_map(ProgressEvent::parse)
+        .collect();
+
+        assert!(progress.is_complete());
+        assert_eq!(progress.fraction(), Some(1.0));
+        assert_eq!(progress.digest(), Some("sha256:abc"));
+        assert_eq!(
+            progress.status(),
+            Some("Downloaded newer image for redis:7")
+        );
+        assert_eq!(TransferProgress::new().fraction(), None);
+    }
+}
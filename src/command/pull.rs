@@ -41,6 +41,7 @@
 //! }
 //! ```
 
+use super::progress::{self, ProgressEvent, TransferProgress};
 use super::{CommandExecutor, CommandOutput, DockerCommand};
 use crate::error::Result;
 use async_trait::async_trait;
@@ -294,6 +295,80 @@ impl PullCommand {
     pub fn get_executor_mut(&mut self) -> &mut CommandExecutor {
         &mut self.executor
     }
+
+    /// Pull the image and return its digest
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Docker daemon is not running, the image does
+    /// not exist, or the registry refuses access
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use docker_wrapper::PullCommand;
+    ///
+    /// # async fn example() -> docker_wrapper::Result<()> {
+    /// let result = PullCommand::new("redis:7").run().await?;
+    /// println!("Pulled {} at {:?}", result.image, result.digest);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn run(&self) -> Result<PullResult> {
+        let output = self.execute().await?;
+        let progress = progress::progress_from_output(&output);
+        Ok(self.result(output, &progress))
+    }
+
+    /// Pull the image, passing each progress event to `on_progress` as the
+    /// CLI prints it
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Docker daemon is not running, the image does
+    /// not exist, or the registry refuses access
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a Tokio runtime.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use docker_wrapper::{ProgressEvent, PullCommand, TransferProgress};
+    ///
+    /// # async fn example() -> docker_wrapper::Result<()> {
+    /// let mut progress = TransferProgress::new();
+    /// let result = PullCommand::new("postgres:16")
+    ///     .run_with_progress(|event: &ProgressEvent| {
+    ///         progress.update(event);
+    ///         println!(
+    ///             "{}/{} layers done",
+    ///             progress.completed_layers(),
+    ///             progress.layers().len()
+    ///         );
+    ///     })
+    ///     .await?;
+    /// println!("Digest: {:?}", result.digest);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn run_with_progress<F>(&self, on_progress: F) -> Result<PullResult>
+    where
+        F: FnMut(&ProgressEvent) + Send,
+    {
+        let (output, progress) = progress::run_with_progress(self, on_progress).await?;
+        Ok(self.result(output, &progress))
+    }
+
+    fn result(&self, output: CommandOutput, progress: &TransferProgress) -> PullResult {
+        PullResult {
+            output,
+            image: self.image.clone(),
+            digest: progress.digest().map(str::to_string),
+            status: progress.status().map(str::to_string),
+        }
+    }
 }
 
 impl Default for PullCommand {
@@ -353,10 +428,90 @@ impl DockerCommand for PullCommand {
     }
 }
 
+/// Result of [`PullCommand::run`] and [`PullCommand::run_with_progress`]
+#[derive(Debug, Clone)]
+pub struct PullResult {
+    /// Raw command output
+    pub output: CommandOutput,
+    /// Image that was pulled, as requested
+    pub image: String,
+    /// Digest of the pulled image; not reported by a quiet pull
+    pub digest: Option<String>,
+    /// Outcome, e.g. `Downloaded newer image for redis:7`
+    pub status: Option<String>,
+}
+
+impl PullResult {
+    /// Check if the pull was successful
+    #[must_use]
+    pub fn success(&self) -> bool {
+        self.output.success
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn test_pull_run_returns_digest_and_status() {
+        use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
+
+        let fake = FakeRunner::new().on(
+            ArgMatcher::prefix(["pull"]),
+            FakeResponse::success(
+                "7: Pulling from library/redis\n\
+                 a2abf6c4d29d: Pull complete\n\
+                 Digest: sha256:abc123\n\
+                 Status: Downloaded newer image for redis:7\n\
+                 docker.io/library/redis:7\n",
+            ),
+        );
+        let mut pull = PullCommand::new("redis:7");
+        pull.with_runner(fake);
+
+        let result = pull.run().await.unwrap();
+        assert!(result.success());
+        assert_eq!(result.image, "redis:7");
+        assert_eq!(result.digest.as_deref(), Some("sha256:abc123"));
+        assert_eq!(
+            result.status.as_deref(),
+            Some("Downloaded newer image for redis:7")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pull_run_with_progress_delivers_events() {
+        use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
+        use crate::{LayerPhase, TransferProgress};
+
+        const OUTPUT: &str = "7: Pulling from library/redis
+a2abf6c4d29d: Pulling fs layer
+a2abf6c4d29d: Downloading [==>   ]  1MB/4MB
+a2abf6c4d29d: Pull complete
+Digest: sha256:abc123
+Status: Downloaded newer image for redis:7
+";
+        let mut pull = PullCommand::new("redis:7");
+        pull.with_runner(FakeRunner::new().on(ArgMatcher::Any, FakeResponse::success(OUTPUT)));
+
+        let mut progress = TransferProgress::new();
+        let mut fractions = Vec::new();
+        let result = pull
+            .run_with_progress(|event| {
+                progress.update(event);
+                fractions.extend(progress.fraction());
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(fractions, [0.0, 0.25, 1.0, 1.0, 1.0]);
+        assert_eq!(progress.layers()[0].phase, LayerPhase::Pulled);
+        assert_eq!(progress.layers()[0].total, Some(4_000_000));
+        assert_eq!(result.digest.as_deref(), Some("sha256:abc123"));
+        assert!(result.output.stdout.starts_with("7: Pulling from"));
+    }
+
     #[test]
     fn test_pull_command_basic() {
         let pull_cmd = PullCommand::new("nginx:latest");
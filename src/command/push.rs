@@ -42,6 +42,7 @@
 //! }
 //! ```
 
+use super::progress::{self, ProgressEvent, TransferProgress};
 use super::{CommandExecutor, CommandOutput, DockerCommand};
 use crate::error::Result;
 use async_trait::async_trait;
@@ -302,6 +303,77 @@ impl PushCommand {
     pub fn get_executor_mut(&mut self) -> &mut CommandExecutor {
         &mut self.executor
     }
+
+    /// Push the image and return its digest
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Docker daemon is not running, the image does
+    /// not exist locally, or the registry refuses access
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use docker_wrapper::PushCommand;
+    ///
+    /// # async fn example() -> docker_wrapper::Result<()> {
+    /// let result = PushCommand::new("registry.example.com/app:1.0").run().await?;
+    /// println!("Pushed {} as {:?}", result.image, result.digest);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn run(&self) -> Result<PushResult> {
+        let output = self.execute().await?;
+        let progress = progress::progress_from_output(&output);
+        Ok(self.result(output, &progress))
+    }
+
+    /// Push the image, passing each progress event to `on_progress` as the
+    /// CLI prints it
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Docker daemon is not running, the image does
+    /// not exist locally, or the registry refuses access
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a Tokio runtime.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use docker_wrapper::{LayerPhase, ProgressEvent, PushCommand};
+    ///
+    /// # async fn example() -> docker_wrapper::Result<()> {
+    /// let result = PushCommand::new("registry.example.com/app:1.0")
+    ///     .run_with_progress(|event: &ProgressEvent| {
+    ///         if let ProgressEvent::Layer(layer) = event {
+    ///             if layer.phase == LayerPhase::Pushed {
+    ///                 println!("{} pushed", layer.id);
+    ///             }
+    ///         }
+    ///     })
+    ///     .await?;
+    /// println!("Digest: {:?}", result.digest);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn run_with_progress<F>(&self, on_progress: F) -> Result<PushResult>
+    where
+        F: FnMut(&ProgressEvent) + Send,
+    {
+        let (output, progress) = progress::run_with_progress(self, on_progress).await?;
+        Ok(self.result(output, &progress))
+    }
+
+    fn result(&self, output: CommandOutput, progress: &TransferProgress) -> PushResult {
+        PushResult {
+            output,
+            image: self.image.clone(),
+            digest: progress.digest().map(str::to_string),
+        }
+    }
 }
 
 impl Default for PushCommand {
@@ -361,10 +433,75 @@ impl DockerCommand for PushCommand {
     }
 }
 
+/// Result of [`PushCommand::run`] and [`PushCommand::run_with_progress`]
+#[derive(Debug, Clone)]
+pub struct PushResult {
+    /// Raw command output
+    pub output: CommandOutput,
+    /// Image that was pushed, as requested
+    pub image: String,
+    /// Digest of the pushed manifest, unless the push was quiet; with
+    /// `all_tags`, the digest of the last tag pushed
+    pub digest: Option<String>,
+}
+
+impl PushResult {
+    /// Check if the push was successful
+    #[must_use]
+    pub fn success(&self) -> bool {
+        self.output.success
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn test_push_run_returns_digest() {
+        use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
+
+        let fake = FakeRunner::new().on(
+            ArgMatcher::prefix(["push"]),
+            FakeResponse::success(
+                "The push refers to repository [registry.example.com/app]\n\
+                 5f70bf18a086: Preparing\n\
+                 5f70bf18a086: Pushed\n\
+                 1.0: digest: sha256:abc123 size: 528\n",
+            ),
+        );
+        let mut push = PushCommand::new("registry.example.com/app:1.0");
+        push.with_runner(fake);
+
+        let result = push.run().await.unwrap();
+        assert!(result.success());
+        assert_eq!(result.image, "registry.example.com/app:1.0");
+        assert_eq!(result.digest.as_deref(), Some("sha256:abc123"));
+    }
+
+    #[tokio::test]
+    async fn test_push_run_with_progress_reports_failure() {
+        use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
+
+        let mut push = PushCommand::new("registry.example.com/app:1.0");
+        push.with_runner(
+            FakeRunner::new().on(
+                ArgMatcher::Any,
+                FakeResponse::failure(1, "denied: requested access to the resource is denied\n")
+                    .with_stdout("5f70bf18a086: Preparing\n"),
+            ),
+        );
+
+        let mut events = Vec::new();
+        let error = push
+            .run_with_progress(|event| events.push(event.clone()))
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().contains("denied"));
+        assert_eq!(events.len(), 1);
+    }
+
     #[test]
     fn test_push_command_basic() {
         let push_cmd = PushCommand::new("myapp:latest");
@@ -6,19 +6,24 @@
 use super::{CommandExecutor, DockerCommand, EnvironmentBuilder, PortBuilder};
 use crate::command::port::{PortCommand, PortMapping as PortMappingInfo};
 use crate::error::{Error, Result};
+use crate::parse::ArgParser;
 use crate::stream::{OutputLine, StreamResult, StreamableCommand};
 use async_trait::async_trait;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::path::PathBuf;
-use tokio::process::Command as TokioCommand;
 use tokio::sync::mpsc;
 
 /// Docker run command builder with fluent API
-#[derive(Debug, Clone)]
+///
+/// Serializes to its options; see [`crate::command`] for the format.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(remote = "Self", default = "RunCommand::unset", deny_unknown_fields)]
 #[allow(clippy::struct_excessive_bools)]
 pub struct RunCommand {
     /// The Docker image to run
     image: String,
     /// Command executor for extensibility
+    #[serde(rename = "extra_args", with = "crate::command::raw_args")]
     pub executor: CommandExecutor,
     /// Container name
     name: Option<String>,
@@ -266,7 +271,8 @@ pub struct RunCommand {
 }
 
 /// Volume mount configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct VolumeMount {
     /// Source path on host or volume name
     pub source: String,
@@ -279,7 +285,8 @@ pub struct VolumeMount {
 }
 
 /// Type of volume mount
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum MountType {
     /// Bind mount from host filesystem
     Bind,
@@ -296,6 +303,41 @@ impl std::fmt::Display for VolumeMount {
     }
 }
 
+impl std::str::FromStr for VolumeMount {
+    type Err = Error;
+
+    /// Parse the `--volume` syntax: `source:target[:ro|rw]`
+    ///
+    /// Sources that look like paths become bind mounts, anything else a named
+    /// volume. Anonymous volumes and other mount options are not supported.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::invalid_config(format!("unsupported volume: {s}"));
+        let mut parts = s.split(':');
+        let (Some(source), Some(target)) = (parts.next(), parts.next()) else {
+            return Err(invalid());
+        };
+        let readonly = match parts.next() {
+            None | Some("rw") => false,
+            Some("ro") => true,
+            Some(_) => return Err(invalid()),
+        };
+        if source.is_empty() || target.is_empty() || parts.next().is_some() {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            source: source.to_string(),
+            target: target.to_string(),
+            mount_type: if source.starts_with(['/', '.', '~']) {
+                MountType::Bind
+            } else {
+                MountType::Volume
+            },
+            readonly,
+        })
+    }
+}
+
 /// Container ID returned by docker run
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ContainerId(pub String);
@@ -545,6 +587,11 @@ impl RunCommand {
         }
     }
 
+    /// A run command without an image, filled in by parsing or deserializing
+    fn unset() -> Self {
+        Self::new("")
+    }
+
     /// Set the container name
     #[must_use]
     pub fn name(mut self, name: impl Into<String>) -> Self {
@@ -566,6 +613,13 @@ impl RunCommand {
         self
     }
 
+    /// Pass an environment variable through from the host (`--env KEY`)
+    #[must_use]
+    pub fn env_pass_through(mut self, key: impl Into<String>) -> Self {
+        self.environment = self.environment.pass_through(key);
+        self
+    }
+
     /// Add multiple environment variables
     #[must_use]
     pub fn envs(mut self, vars: std::collections::HashMap<String, String>) -> Self {
@@ -1334,6 +1388,228 @@ impl RunCommand {
     }
 }
 
+impl RunCommand {
+    /// Build a run command from `docker run` arguments
+    ///
+    /// The leading `docker` and `run` (or `container run`) are optional. See
+    /// [`crate::parse`] for the accepted syntax.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use docker_wrapper::RunCommand;
+    ///
+    /// let run = RunCommand::from_args(["run", "--rm", "-it", "alpine", "sh"]).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for unknown flags, missing or invalid values, and a
+    /// missing image.
+    #[allow(clippy::too_many_lines)]
+    pub fn from_args<I, S>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut parser = ArgParser::new("run", &[&["run"], &["container", "run"]], args);
+        let mut cmd = Self::unset();
+
+        while let Some(flag) = parser.next_flag() {
+            let p = &mut parser;
+            match flag.name.as_str() {
+                "-d" | "--detach" => cmd.detach = p.switch(&flag)?,
+                "-i" | "--interactive" => cmd.interactive = p.switch(&flag)?,
+                "-t" | "--tty" => cmd.tty = p.switch(&flag)?,
+                "--rm" => cmd.remove = p.switch(&flag)?,
+                "--name" => cmd = cmd.name(p.value(&flag)?),
+                "-w" | "--workdir" => cmd = cmd.workdir(p.value(&flag)?),
+                "--entrypoint" => cmd = cmd.entrypoint(p.value(&flag)?),
+                "-e" | "--env" => match p.env(&flag)? {
+                    (key, Some(value)) => cmd = cmd.env(key, value),
+                    (key, None) => cmd = cmd.env_pass_through(key),
+                },
+                "-p" | "--publish" => {
+                    let value = p.value(&flag)?;
+                    let mapping = value.parse().map_err(|_| p.invalid(&flag, &value))?;
+                    cmd.ports = cmd.ports.mapping(mapping);
+                }
+                "-v" | "--volume" => {
+                    let value = p.value(&flag)?;
+                    let volume = value.parse().map_err(|_| p.invalid(&flag, &value))?;
+                    cmd.volumes.push(volume);
+                }
+
+                // Resource Limits
+                "-m" | "--memory" => cmd = cmd.memory(p.value(&flag)?),
+                "--cpus" => cmd = cmd.cpus(p.value(&flag)?),
+                "-c" | "--cpu-shares" => cmd = cmd.cpu_shares(p.parse(&flag)?),
+                "--cpu-period" => cmd = cmd.cpu_period(p.parse(&flag)?),
+                "--cpu-quota" => cmd = cmd.cpu_quota(p.parse(&flag)?),
+                "--cpuset-cpus" => cmd = cmd.cpuset_cpus(p.value(&flag)?),
+                "--cpuset-mems" => cmd = cmd.cpuset_mems(p.value(&flag)?),
+                "--memory-swap" => cmd = cmd.memory_swap(p.value(&flag)?),
+                "--memory-reservation" => cmd = cmd.memory_reservation(p.value(&flag)?),
+
+                // Security & User Context
+                "-u" | "--user" => cmd = cmd.user(p.value(&flag)?),
+                "--privileged" => cmd.privileged = p.switch(&flag)?,
+                "-h" | "--hostname" => cmd = cmd.hostname(p.value(&flag)?),
+
+                // Lifecycle Management
+                "--restart" => cmd = cmd.restart(p.value(&flag)?),
+
+                // System Integration
+                "--platform" => cmd = cmd.platform(p.value(&flag)?),
+                "--runtime" => cmd = cmd.runtime(p.value(&flag)?),
+                "--isolation" => cmd = cmd.isolation(p.value(&flag)?),
+                "--pull" => cmd = cmd.pull(p.value(&flag)?),
+                "--cidfile" => cmd = cmd.cidfile(p.value(&flag)?),
+                "--domainname" => cmd = cmd.domainname(p.value(&flag)?),
+                "--mac-address" => cmd = cmd.mac_address(p.value(&flag)?),
+
+                // Logging & Drivers
+                "--log-driver" => cmd = cmd.log_driver(p.value(&flag)?),
+                "--volume-driver" => cmd = cmd.volume_driver(p.value(&flag)?),
+
+                // Namespaces
+                "--userns" => cmd = cmd.userns(p.value(&flag)?),
+                "--uts" => cmd = cmd.uts(p.value(&flag)?),
+                "--pid" => cmd = cmd.pid(p.value(&flag)?),
+                "--ipc" => cmd = cmd.ipc(p.value(&flag)?),
+                "--cgroupns" => cmd = cmd.cgroupns(p.value(&flag)?),
+                "--cgroup-parent" => cmd = cmd.cgroup_parent(p.value(&flag)?),
+
+                // Advanced Memory & Performance
+                "--kernel-memory" => cmd = cmd.kernel_memory(p.value(&flag)?),
+                "--memory-swappiness" => cmd = cmd.memory_swappiness(p.parse(&flag)?),
+                "--oom-score-adj" => cmd = cmd.oom_score_adj(p.parse(&flag)?),
+                "--pids-limit" => cmd = cmd.pids_limit(p.parse(&flag)?),
+                "--shm-size" => cmd = cmd.shm_size(p.value(&flag)?),
+
+                // Process Control
+                "--stop-signal" => cmd = cmd.stop_signal(p.value(&flag)?),
+                "--stop-timeout" => cmd = cmd.stop_timeout(p.parse(&flag)?),
+                "--detach-keys" => cmd = cmd.detach_keys(p.value(&flag)?),
+
+                // Simple Flags
+                "--sig-proxy" => cmd.sig_proxy = p.switch(&flag)?,
+                "--read-only" => cmd.read_only = p.switch(&flag)?,
+                "--init" => cmd.init = p.switch(&flag)?,
+                "--oom-kill-disable" => cmd.oom_kill_disable = p.switch(&flag)?,
+                "--no-healthcheck" => cmd.no_healthcheck = p.switch(&flag)?,
+                "--disable-content-trust" => cmd.disable_content_trust = p.switch(&flag)?,
+                "-P" | "--publish-all" => cmd.publish_all = p.switch(&flag)?,
+                "-q" | "--quiet" => cmd.quiet = p.switch(&flag)?,
+
+                // DNS & Network
+                "--dns" => cmd = cmd.dns(p.value(&flag)?),
+                "--dns-option" | "--dns-opt" => cmd = cmd.dns_option(p.value(&flag)?),
+                "--dns-search" => cmd = cmd.dns_search(p.value(&flag)?),
+                "--add-host" => cmd = cmd.add_host(p.value(&flag)?),
+
+                // Security & Capabilities
+                "--cap-add" => cmd = cmd.cap_add(p.value(&flag)?),
+                "--cap-drop" => cmd = cmd.cap_drop(p.value(&flag)?),
+                "--security-opt" => cmd = cmd.security_opt(p.value(&flag)?),
+
+                // Device & Filesystem
+                "--device" => cmd = cmd.device(p.value(&flag)?),
+                "--tmpfs" => cmd = cmd.tmpfs(p.value(&flag)?),
+                "--expose" => cmd = cmd.expose(p.value(&flag)?),
+
+                // Environment & Labels
+                "--env-file" => cmd = cmd.env_file(p.value(&flag)?),
+                "-l" | "--label" => cmd = cmd.label(p.value(&flag)?),
+                "--label-file" => cmd = cmd.label_file(p.value(&flag)?),
+
+                // Additional List/Vec Options
+                "--network-alias" | "--net-alias" => cmd = cmd.network_alias(p.value(&flag)?),
+                "--group-add" => cmd = cmd.group_add(p.value(&flag)?),
+                "-a" | "--attach" => cmd = cmd.attach(p.value(&flag)?),
+                "--log-opt" => cmd = cmd.log_opt(p.value(&flag)?),
+                "--storage-opt" => cmd = cmd.storage_opt(p.value(&flag)?),
+                "--ulimit" => cmd = cmd.ulimit(p.value(&flag)?),
+                "--volumes-from" => cmd = cmd.volumes_from(p.value(&flag)?),
+                "--link" => cmd = cmd.link(p.value(&flag)?),
+                "--link-local-ip" => cmd = cmd.link_local_ip(p.value(&flag)?),
+
+                // Health Check Options
+                "--health-cmd" => cmd = cmd.health_cmd(p.value(&flag)?),
+                "--health-interval" => cmd = cmd.health_interval(p.value(&flag)?),
+                "--health-retries" => cmd = cmd.health_retries(p.parse(&flag)?),
+                "--health-timeout" => cmd = cmd.health_timeout(p.value(&flag)?),
+                "--health-start-period" => cmd = cmd.health_start_period(p.value(&flag)?),
+                "--health-start-interval" => cmd = cmd.health_start_interval(p.value(&flag)?),
+
+                // Advanced options
+                "--mount" => cmd = cmd.mount(p.value(&flag)?),
+                "--network" | "--net" => cmd = cmd.network(p.value(&flag)?),
+                "--gpus" => cmd = cmd.gpus(p.value(&flag)?),
+                "--annotation" => {
+                    let (key, value) = p.key_value(&flag)?;
+                    cmd = cmd.annotation(key, value);
+                }
+                "--sysctl" => {
+                    let (key, value) = p.key_value(&flag)?;
+                    cmd = cmd.sysctl(key, value);
+                }
+
+                // Advanced System Options
+                "--blkio-weight" => cmd = cmd.blkio_weight(p.parse(&flag)?),
+                "--blkio-weight-device" => cmd = cmd.blkio_weight_device(p.value(&flag)?),
+                "--device-read-bps" => cmd = cmd.device_read_bps(p.value(&flag)?),
+                "--device-write-bps" => cmd = cmd.device_write_bps(p.value(&flag)?),
+                "--device-read-iops" => cmd = cmd.device_read_iops(p.value(&flag)?),
+                "--device-write-iops" => cmd = cmd.device_write_iops(p.value(&flag)?),
+                "--cpu-rt-period" => cmd = cmd.cpu_rt_period(p.parse(&flag)?),
+                "--cpu-rt-runtime" => cmd = cmd.cpu_rt_runtime(p.parse(&flag)?),
+                "--ip" => cmd = cmd.ip(p.value(&flag)?),
+                "--ip6" => cmd = cmd.ip6(p.value(&flag)?),
+                "--device-cgroup-rule" => cmd = cmd.device_cgroup_rule(p.value(&flag)?),
+
+                _ => return Err(p.unknown(&flag)),
+            }
+        }
+
+        let mut positionals = parser.finish().into_iter();
+        cmd.image = positionals
+            .next()
+            .ok_or_else(|| Error::invalid_config("docker run requires an image"))?;
+        let command: Vec<String> = positionals.collect();
+        if !command.is_empty() {
+            cmd.command = Some(command);
+        }
+
+        Ok(cmd)
+    }
+}
+
+impl std::str::FromStr for RunCommand {
+    type Err = Error;
+
+    /// Parse a `docker run ...` shell line
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_args(crate::parse::split(s)?)
+    }
+}
+
+impl Serialize for RunCommand {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        Self::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for RunCommand {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let cmd = Self::deserialize(deserializer)?;
+        if cmd.image.is_empty() {
+            return Err(de::Error::missing_field("image"));
+        }
+        Ok(cmd)
+    }
+}
+
 #[async_trait]
 impl DockerCommand for RunCommand {
     type Output = ContainerId;
@@ -1826,14 +2102,8 @@ impl StreamableCommand for RunCommand {
             ));
         }
 
-        let mut cmd = TokioCommand::new("docker");
-        cmd.arg("run");
-
-        for arg in self.build_command_args() {
-            cmd.arg(arg);
-        }
-
-        crate::stream::stream_command(cmd, handler, "run").await
+        crate::stream::stream_command(&self.executor, self.build_command_args(), handler, "run")
+            .await
     }
 
     async fn stream_channel(&self) -> Result<(mpsc::Receiver<OutputLine>, StreamResult)> {
@@ -1844,14 +2114,8 @@ impl StreamableCommand for RunCommand {
             ));
         }
 
-        let mut cmd = TokioCommand::new("docker");
-        cmd.arg("run");
-
-        for arg in self.build_command_args() {
-            cmd.arg(arg);
-        }
-
-        crate::stream::stream_command_channel(cmd, "run").await
+        crate::stream::stream_command_channel(&self.executor, self.build_command_args(), "run")
+            .await
     }
 }
 
@@ -2854,10 +3118,37 @@ mod tests {
     #[test]
     #[allow(clippy::too_many_lines)]
     fn test_run_command_complete_100_percent_coverage() {
+        // Test demonstrating ALL 96 Docker run options are now implemented
+        let cmd = every_option_command();
+        let args = cmd.build_command_args();
+
+        // Verify we have a substantial command with all option types
+        assert!(args.len() > 150); // Should be a very long command
+
+        // Verify key options from each batch are present
+        assert!(args.contains(&"--detach".to_string()));
+        assert!(args.contains(&"--memory".to_string()));
+        assert!(args.contains(&"--dns".to_string()));
+        assert!(args.contains(&"--network-alias".to_string()));
+        assert!(args.contains(&"--health-cmd".to_string()));
+        assert!(args.contains(&"--blkio-weight".to_string()));
+
+        // Verify image is still at the end
+        let image_pos = args
+            .iter()
+            .position(|x| x == "enterprise-app:latest")
+            .unwrap();
+        assert!(image_pos > 100); // Should be very far into the command
+        assert_eq!(args[args.len() - 1], "enterprise-app:latest");
+
+        println!("COMPLETE! All 96 Docker run options implemented and tested!");
+    }
+
+    /// A run command using every option the builder supports
+    fn every_option_command() -> RunCommand {
         use std::path::PathBuf;
 
-        // Test demonstrating ALL 96 Docker run options are now implemented
-        let cmd = RunCommand::new("enterprise-app:latest")
+        RunCommand::new("enterprise-app:latest")
             .name("production-enterprise")
             // Basic options
             .detach()
@@ -2970,29 +3261,307 @@ mod tests {
             .cpu_rt_runtime(950_000)
             .ip("10.0.1.50")
             .ip6("2001:db8::50")
-            .device_cgroup_rule("c 1:1 rwm");
+            .device_cgroup_rule("c 1:1 rwm")
+    }
 
+    #[test]
+    fn test_run_command_from_args_round_trip() {
+        let cmd = every_option_command();
         let args = cmd.build_command_args();
 
-        // Verify we have a substantial command with all option types
-        assert!(args.len() > 150); // Should be a very long command
+        let parsed = RunCommand::from_args(args.clone()).unwrap();
+        assert_eq!(parsed.build_command_args(), args);
 
-        // Verify key options from each batch are present
-        assert!(args.contains(&"--detach".to_string()));
-        assert!(args.contains(&"--memory".to_string()));
-        assert!(args.contains(&"--dns".to_string()));
-        assert!(args.contains(&"--network-alias".to_string()));
-        assert!(args.contains(&"--health-cmd".to_string()));
-        assert!(args.contains(&"--blkio-weight".to_string()));
+        let parsed =
+            RunCommand::from_args(["docker".to_string()].into_iter().chain(args.clone())).unwrap();
+        assert_eq!(parsed.build_command_args(), args);
+    }
 
-        // Verify image is still at the end
-        let image_pos = args
-            .iter()
-            .position(|x| x == "enterprise-app:latest")
+    #[test]
+    fn test_run_command_parse_shell_line() {
+        let cmd: RunCommand = r#"docker container run -dit --rm --name=web \
+            -p 127.0.0.1:8080:80 -p 53:53/udp -P \
+            -e "GREETING=hello world" -v ./site:/usr/share/nginx/html:ro \
+            --sig-proxy=false -m512m --cpus 1.5 --oom-score-adj -100 \
+            nginx:alpine nginx -g 'daemon off;'"#
+            .parse()
             .unwrap();
-        assert!(image_pos > 100); // Should be very far into the command
-        assert_eq!(args[args.len() - 1], "enterprise-app:latest");
 
-        println!("COMPLETE! All 96 Docker run options implemented and tested!");
+        assert_eq!(
+            cmd.build_command_args(),
+            [
+                "run",
+                "--detach",
+                "--interactive",
+                "--tty",
+                "--rm",
+                "--name",
+                "web",
+                "--env",
+                "GREETING=hello world",
+                "--publish",
+                "127.0.0.1:8080:80",
+                "--publish",
+                "53:53/udp",
+                "--volume",
+                "./site:/usr/share/nginx/html:ro",
+                "--memory",
+                "512m",
+                "--cpus",
+                "1.5",
+                "--oom-score-adj",
+                "-100",
+                "--sig-proxy=false",
+                "--publish-all",
+                "nginx:alpine",
+                "nginx",
+                "-g",
+                "daemon off;",
+            ]
+        );
+        assert_eq!(cmd.volumes[0].mount_type, MountType::Bind);
+        assert!(cmd.volumes[0].readonly);
+    }
+
+    #[test]
+    fn test_run_command_parse_errors() {
+        let err = "docker run --frobnicate nginx"
+            .parse::<RunCommand>()
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("unknown flag for docker run: --frobnicate"));
+
+        for line in [
+            "docker run -d",
+            "docker run --name",
+            "docker run -e =VALUE nginx",
+            "docker run -p 8000-8010:80 nginx",
+            "docker run -v /data nginx",
+            "docker run --cpu-shares lots nginx",
+            "docker run --rm=perhaps nginx",
+        ] {
+            assert!(line.parse::<RunCommand>().is_err(), "{line}");
+        }
+    }
+
+    #[test]
+    fn test_run_command_parse_pass_through_env() {
+        let run: RunCommand = "docker run -e TOKEN --env MODE=prod nginx".parse().unwrap();
+        assert_eq!(
+            run.build_command_args(),
+            ["run", "--env", "MODE=prod", "--env", "TOKEN", "nginx"]
+        );
+    }
+
+    #[test]
+    fn test_run_command_serde_round_trip() {
+        let mut cmd = every_option_command()
+            .env("A_FIRST", "1")
+            .port_dyn(9090)
+            .cmd(vec!["serve".to_string(), "--verbose".to_string()]);
+        cmd.arg("--extra");
+
+        let json = serde_json::to_string(&cmd).unwrap();
+        let restored: RunCommand = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(
+            sorted(restored.build_command_args()),
+            sorted(cmd.build_command_args())
+        );
+        assert_eq!(serde_json::to_string(&restored).unwrap(), json);
+    }
+
+    #[test]
+    fn test_run_command_deserialize_defaults() {
+        let cmd: RunCommand = serde_json::from_str(
+            r#"{"image": "nginx", "detach": true, "ports": [{"host_port": 8080, "container_port": 80, "protocol": "tcp"}]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            cmd.build_command_args(),
+            ["run", "--detach", "--publish", "8080:80", "nginx"]
+        );
+    }
+
+    /// Serialized form of the command in `test_run_command_serde_schema`
+    ///
+    /// Pins the wire format: field names, their order and the shape of
+    /// ports, volumes and the environment.
+    const RUN_SCHEMA: &str = r#"{
+  "image": "nginx",
+  "extra_args": [],
+  "name": "web",
+  "detach": false,
+  "environment": {
+    "MODE": "prod",
+    "TOKEN": null
+  },
+  "ports": [
+    {
+      "host_port": 8080,
+      "container_port": 80,
+      "protocol": "tcp",
+      "host_ip": null
+    },
+    {
+      "host_port": 5353,
+      "container_port": 53,
+      "protocol": "udp",
+      "host_ip": "127.0.0.1"
+    }
+  ],
+  "volumes": [
+    {
+      "source": "data",
+      "target": "/data",
+      "mount_type": "volume",
+      "readonly": false
+    }
+  ],
+  "workdir": null,
+  "entrypoint": null,
+  "command": null,
+  "interactive": false,
+  "tty": false,
+  "remove": false,
+  "memory": null,
+  "cpus": null,
+  "cpu_shares": null,
+  "cpu_period": null,
+  "cpu_quota": null,
+  "cpuset_cpus": null,
+  "cpuset_mems": null,
+  "memory_swap": null,
+  "memory_reservation": null,
+  "user": null,
+  "privileged": false,
+  "hostname": null,
+  "restart": null,
+  "platform": null,
+  "runtime": null,
+  "isolation": null,
+  "pull": null,
+  "cidfile": null,
+  "domainname": null,
+  "mac_address": null,
+  "log_driver": null,
+  "volume_driver": null,
+  "userns": null,
+  "uts": null,
+  "pid": null,
+  "ipc": null,
+  "cgroupns": null,
+  "cgroup_parent": null,
+  "kernel_memory": null,
+  "memory_swappiness": null,
+  "oom_score_adj": null,
+  "pids_limit": null,
+  "shm_size": null,
+  "stop_signal": null,
+  "stop_timeout": null,
+  "detach_keys": null,
+  "sig_proxy": true,
+  "read_only": false,
+  "init": false,
+  "oom_kill_disable": false,
+  "no_healthcheck": false,
+  "disable_content_trust": true,
+  "publish_all": false,
+  "quiet": false,
+  "dns": [],
+  "dns_option": [],
+  "dns_search": [],
+  "add_host": [],
+  "cap_add": [],
+  "cap_drop": [],
+  "security_opt": [],
+  "device": [],
+  "tmpfs": [],
+  "expose": [],
+  "env_file": [],
+  "label": [],
+  "label_file": [],
+  "network_alias": [],
+  "group_add": [],
+  "attach": [],
+  "log_opt": [],
+  "storage_opt": [],
+  "ulimit": [],
+  "volumes_from": [],
+  "link": [],
+  "link_local_ip": [],
+  "health_cmd": null,
+  "health_interval": null,
+  "health_retries": null,
+  "health_timeout": null,
+  "health_start_period": null,
+  "health_start_interval": null,
+  "mount": [],
+  "network": [],
+  "gpus": null,
+  "annotation": [],
+  "sysctl": [],
+  "blkio_weight": null,
+  "blkio_weight_device": [],
+  "device_read_bps": [],
+  "device_write_bps": [],
+  "device_read_iops": [],
+  "device_write_iops": [],
+  "cpu_rt_period": null,
+  "cpu_rt_runtime": null,
+  "ip": null,
+  "ip6": null,
+  "device_cgroup_rule": []
+}"#;
+
+    #[test]
+    fn test_run_command_serde_schema() {
+        let mut cmd = RunCommand::new("nginx")
+            .name("web")
+            .env("MODE", "prod")
+            .env_pass_through("TOKEN")
+            .port(8080, 80)
+            .volume("data", "/data");
+        cmd.ports = cmd.ports.mapping("127.0.0.1:5353:53/udp".parse().unwrap());
+
+        assert_eq!(
+            serde_json::to_value(&cmd).unwrap(),
+            serde_json::from_str::<serde_json::Value>(RUN_SCHEMA).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_run_command_serde_fixture() {
+        let cmd: RunCommand = serde_json::from_str(RUN_SCHEMA).unwrap();
+        assert_eq!(serde_json::to_string_pretty(&cmd).unwrap(), RUN_SCHEMA);
+    }
+
+    #[test]
+    fn test_run_command_deserialize_validates() {
+        let err = serde_json::from_str::<RunCommand>(r#"{"detach": true}"#).unwrap_err();
+        assert!(err.to_string().contains("missing field `image`"), "{err}");
+
+        let err = serde_json::from_str::<RunCommand>(r#"{"image": "nginx", "detached": true}"#)
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("unknown field `detached`"),
+            "{err}"
+        );
+
+        let err = serde_json::from_str::<RunCommand>(
+            r#"{"image": "nginx", "ports": [{"container_port": 80, "protocol": "tcp", "host_port": null, "host_ip": null, "hostport": 1}]}"#,
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("unknown field `hostport`"),
+            "{err}"
+        );
+    }
+
+    fn sorted(mut args: Vec<String>) -> Vec<String> {
+        args.sort();
+        args
     }
 }
@@ -4,6 +4,7 @@
 
 use super::{CommandExecutor, CommandOutput, DockerCommand};
 use crate::error::Result;
+use crate::stream::ByteStream;
 use async_trait::async_trait;
 use std::path::Path;
 
@@ -137,6 +138,47 @@ impl SaveCommand {
             output_file: self.output.clone(),
         })
     }
+
+    /// Start the save and read the tar archive as it is written
+    ///
+    /// Any [`output`](Self::output) file is ignored; the archive is written
+    /// to stdout and read from the returned stream.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use docker_wrapper::SaveCommand;
+    /// use tokio::io::AsyncReadExt;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut archive = SaveCommand::new_multiple(vec!["alpine", "nginx"]).reader()?;
+    /// let mut buf = vec![0; 64 * 1024];
+    /// let mut size = 0;
+    /// loop {
+    ///     let n = archive.read(&mut buf).await?;
+    ///     if n == 0 {
+    ///         break;
+    ///     }
+    ///     size += n;
+    /// }
+    /// println!("archive is {size} bytes");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the command cannot be started or an interceptor
+    /// vetoes it
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a Tokio runtime.
+    pub fn reader(&self) -> Result<ByteStream> {
+        let mut command = self.clone();
+        command.output = None;
+        crate::stream::spawn_byte_stream(&command.executor, command.build_command_args())
+    }
 }
 
 #[async_trait]
@@ -211,6 +253,27 @@ impl SaveResult {
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn test_reader_fails_on_non_zero_exit() {
+        use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
+        use tokio::io::AsyncReadExt;
+
+        let mut cmd = SaveCommand::new("missing:latest");
+        cmd.with_runner(FakeRunner::new().on(
+            ArgMatcher::Any,
+            FakeResponse::failure(1, "reference does not exist\n").with_stdout("partial"),
+        ));
+
+        let mut archive = cmd.reader().unwrap();
+        let mut bytes = Vec::new();
+        let error = archive.read_to_end(&mut bytes).await.unwrap_err();
+        assert_eq!(bytes, b"partial");
+        assert!(error.to_string().contains("reference does not exist"));
+
+        let error = archive.wait().await.unwrap_err();
+        assert_eq!(error.output().unwrap().exit_code, 1);
+    }
+
     #[test]
     fn test_save_single_image() {
         let cmd = SaveCommand::new("alpine:latest");
@@ -2,11 +2,40 @@
 //!
 //! This module provides the `docker stats` command for displaying real-time
 //! resource usage statistics of containers.
-
+//!
+//! [`StatsCommand::sample`] keeps the CLI streaming and yields typed
+//! [`StatsSample`]s with sizes in bytes and per-second I/O rates:
+//!
+//! ```rust,no_run
+//! use docker_wrapper::StatsCommand;
+//! use std::time::Duration;
+//!
+//! # async fn example() -> docker_wrapper::Result<()> {
+//! let mut stats = StatsCommand::new().container("web").sample();
+//!
+//! // ... start the load test ...
+//! for summary in stats.summarize(Duration::from_secs(60)).await? {
+//!     println!(
+//!         "{}: peak memory {:?} bytes, average CPU {:?}%",
+//!         summary.name, summary.peak_memory, summary.average_cpu
+//!     );
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use super::parse_util::parse_size;
 use super::{CommandExecutor, CommandOutput, DockerCommand};
-use crate::error::Result;
+use crate::error::{Error, Result};
+use crate::stream::{OutputLine, OUTPUT_STREAM_CAPACITY};
 use async_trait::async_trait;
+use futures_core::Stream;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
 
 /// Docker stats command builder
 ///
@@ -159,6 +188,97 @@ impl StatsCommand {
         self
     }
 
+    /// Sample resource usage continuously as a typed stream
+    ///
+    /// Runs `docker stats --format json` without `--no-stream`, so the
+    /// [`format`](Self::format) and [`no_stream`](Self::no_stream) settings
+    /// are ignored. Each container yields a [`StatsSample`] about once a
+    /// second; from its second sample on, the sample also carries
+    /// [rates](StatsSample::rates) since the one before. Lines that are not
+    /// stats are skipped.
+    ///
+    /// The stream ends when it is dropped, when the executor's cancellation
+    /// token is cancelled, or when the CLI exits, after yielding the error
+    /// if it failed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a Tokio runtime.
+    #[must_use]
+    pub fn sample(&self) -> StatsStream {
+        let (tx, rx) = mpsc::channel(OUTPUT_STREAM_CAPACITY);
+        let mut command = self.clone();
+        command.format = Some("json".to_string());
+        command.no_stream = false;
+        tokio::spawn(command.forward_samples(tx));
+        StatsStream { samples: rx }
+    }
+
+    /// Run the CLI, sending samples until it exits or the receiver is gone
+    async fn forward_samples(self, samples: mpsc::Sender<Result<StatsSample>>) {
+        let (mut lines, completion) = match self.stream_output() {
+            Ok(stream) => stream,
+            Err(e) => {
+                let _ = samples.send(Err(e)).await;
+                return;
+            }
+        };
+        let mut previous = HashMap::<String, StatsSample>::new();
+        let mut stderr = Vec::new();
+
+        loop {
+            let line = tokio::select! {
+                line = lines.next_line() => line,
+                () = samples.closed() => return,
+            };
+            match line {
+                Some(OutputLine::Stdout(text)) => {
+                    let Some(stats) = Self::parse_stream_line(&text) else {
+                        continue;
+                    };
+                    let mut sample = StatsSample::from_stats(&stats, Instant::now());
+                    if let Some(before) = previous.get(&sample.container_id) {
+                        sample.rates = sample.rates_since(before);
+                    }
+                    previous.insert(sample.container_id.clone(), sample.clone());
+                    if samples.send(Ok(sample)).await.is_err() {
+                        return;
+                    }
+                }
+                Some(OutputLine::Stderr(text)) => stderr.push(text),
+                None => break,
+            }
+        }
+
+        let result = completion.await.and_then(|status| {
+            if status.success {
+                Ok(())
+            } else {
+                Err(self.executor.failure(
+                    "docker stats".to_string(),
+                    status.exit_code,
+                    "",
+                    &stderr.join("\n"),
+                ))
+            }
+        });
+        match result {
+            Ok(()) | Err(Error::Interrupted) => {}
+            Err(e) => {
+                let _ = samples.send(Err(e)).await;
+            }
+        }
+    }
+
+    /// Parse one line of streaming output
+    ///
+    /// The CLI clears the screen before each frame, so the first line of a
+    /// frame starts with terminal escapes.
+    fn parse_stream_line(line: &str) -> Option<ContainerStats> {
+        let start = line.find('{')?;
+        serde_json::from_str(line[start..].trim_end()).ok()
+    }
+
     /// Execute the stats command
     ///
     /// # Errors
@@ -366,6 +486,239 @@ impl ContainerStats {
     pub fn pid_count(&self) -> Option<u32> {
         self.pids.parse().ok()
     }
+
+    /// Get memory usage and limit in bytes
+    #[must_use]
+    pub fn memory_bytes(&self) -> Option<(u64, u64)> {
+        parse_size_pair(&self.memory_usage)
+    }
+
+    /// Get bytes received and sent over the network
+    #[must_use]
+    pub fn network_bytes(&self) -> Option<(u64, u64)> {
+        parse_size_pair(&self.network_io)
+    }
+
+    /// Get bytes read from and written to block devices
+    #[must_use]
+    pub fn block_io_bytes(&self) -> Option<(u64, u64)> {
+        parse_size_pair(&self.block_io)
+    }
+}
+
+/// Parse a `used / total` or `in / out` pair of sizes
+fn parse_size_pair(pair: &str) -> Option<(u64, u64)> {
+    let (first, second) = pair.split_once('/')?;
+    Some((parse_size(first)?, parse_size(second)?))
+}
+
+/// One reading of a container's resource usage
+///
+/// Values the CLI could not report, such as the `--` it prints for a
+/// container that is stopping, are `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatsSample {
+    /// Container ID, or the name the container was requested by
+    pub container_id: String,
+    /// Container name
+    pub name: String,
+    /// When the sample was read
+    pub at: Instant,
+    /// CPU usage percentage; above 100 when several cores are busy
+    pub cpu_percent: Option<f64>,
+    /// Memory in use, in bytes
+    pub memory_usage: Option<u64>,
+    /// Memory limit, in bytes
+    pub memory_limit: Option<u64>,
+    /// Memory usage as a percentage of the limit
+    pub memory_percent: Option<f64>,
+    /// Bytes received over the network since the container started
+    pub net_rx: Option<u64>,
+    /// Bytes sent over the network since the container started
+    pub net_tx: Option<u64>,
+    /// Bytes read from block devices since the container started
+    pub block_read: Option<u64>,
+    /// Bytes written to block devices since the container started
+    pub block_write: Option<u64>,
+    /// Number of processes
+    pub pids: Option<u32>,
+    /// Rates since the previous sample of the same container
+    pub rates: Option<StatsRates>,
+}
+
+impl StatsSample {
+    /// Parse the sizes and percentages of `stats`, read at `at`
+    ///
+    /// The sample has no rates; see [`rates_since`](Self::rates_since).
+    #[must_use]
+    pub fn from_stats(stats: &ContainerStats, at: Instant) -> Self {
+        let memory = stats.memory_bytes();
+        let network = stats.network_bytes();
+        let block_io = stats.block_io_bytes();
+        Self {
+            container_id: stats.container_id.clone(),
+            name: stats.name.clone(),
+            at,
+            cpu_percent: stats.cpu_percentage(),
+            memory_usage: memory.map(|(usage, _)| usage),
+            memory_limit: memory.map(|(_, limit)| limit),
+            memory_percent: stats.memory_percentage(),
+            net_rx: network.map(|(rx, _)| rx),
+            net_tx: network.map(|(_, tx)| tx),
+            block_read: block_io.map(|(read, _)| read),
+            block_write: block_io.map(|(_, write)| write),
+            pids: stats.pid_count(),
+            rates: None,
+        }
+    }
+
+    /// Per-second I/O rates from `previous` to this sample
+    ///
+    /// Returns `None` unless `previous` was read earlier. A counter that went
+    /// down, as it does when the container restarts, has a rate of zero.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn rates_since(&self, previous: &Self) -> Option<StatsRates> {
+        let elapsed = self
+            .at
+            .checked_duration_since(previous.at)
+            .filter(|elapsed| !elapsed.is_zero())?;
+        let seconds = elapsed.as_secs_f64();
+        let rate = |now: Option<u64>, before: Option<u64>| {
+            Some(now?.saturating_sub(before?) as f64 / seconds)
+        };
+        Some(StatsRates {
+            elapsed,
+            net_rx_per_sec: rate(self.net_rx, previous.net_rx),
+            net_tx_per_sec: rate(self.net_tx, previous.net_tx),
+            block_read_per_sec: rate(self.block_read, previous.block_read),
+            block_write_per_sec: rate(self.block_write, previous.block_write),
+        })
+    }
+}
+
+/// I/O rates between two samples of a container, in bytes per second
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StatsRates {
+    /// Time between the two samples
+    pub elapsed: Duration,
+    /// Network bytes received per second
+    pub net_rx_per_sec: Option<f64>,
+    /// Network bytes sent per second
+    pub net_tx_per_sec: Option<f64>,
+    /// Block device bytes read per second
+    pub block_read_per_sec: Option<f64>,
+    /// Block device bytes written per second
+    pub block_write_per_sec: Option<f64>,
+}
+
+/// Resource usage of one container over a window of samples
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatsSummary {
+    /// Container ID, or the name the container was requested by
+    pub container_id: String,
+    /// Container name
+    pub name: String,
+    /// Number of samples summarized
+    pub samples: usize,
+    /// Time from the first to the last sample
+    pub duration: Duration,
+    /// Highest memory usage, in bytes
+    pub peak_memory: Option<u64>,
+    /// Mean CPU usage percentage
+    pub average_cpu: Option<f64>,
+    /// Highest CPU usage percentage
+    pub peak_cpu: Option<f64>,
+    /// Network bytes received during the window
+    pub net_rx: Option<u64>,
+    /// Network bytes sent during the window
+    pub net_tx: Option<u64>,
+    /// Block device bytes read during the window
+    pub block_read: Option<u64>,
+    /// Block device bytes written during the window
+    pub block_write: Option<u64>,
+}
+
+impl StatsSummary {
+    /// Summarize `samples` per container, in the order containers first appear
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn from_samples(samples: &[StatsSample]) -> Vec<Self> {
+        let mut groups: Vec<(&str, Vec<&StatsSample>)> = Vec::new();
+        for sample in samples {
+            match groups.iter_mut().find(|(id, _)| *id == sample.container_id) {
+                Some((_, group)) => group.push(sample),
+                None => groups.push((&sample.container_id, vec![sample])),
+            }
+        }
+
+        groups
+            .into_iter()
+            .map(|(_, group)| {
+                let first = group[0];
+                let last = group[group.len() - 1];
+                let cpu: Vec<f64> = group.iter().filter_map(|s| s.cpu_percent).collect();
+                let growth = |counter: fn(&StatsSample) -> Option<u64>| {
+                    let mut values = group.iter().filter_map(|s| counter(s));
+                    let start = values.next()?;
+                    Some(values.next_back().unwrap_or(start).saturating_sub(start))
+                };
+                Self {
+                    container_id: first.container_id.clone(),
+                    name: last.name.clone(),
+                    samples: group.len(),
+                    duration: last.at.saturating_duration_since(first.at),
+                    peak_memory: group.iter().filter_map(|s| s.memory_usage).max(),
+                    average_cpu: (!cpu.is_empty())
+                        .then(|| cpu.iter().sum::<f64>() / cpu.len() as f64),
+                    peak_cpu: cpu.iter().copied().reduce(f64::max),
+                    net_rx: growth(|s| s.net_rx),
+                    net_tx: growth(|s| s.net_tx),
+                    block_read: growth(|s| s.block_read),
+                    block_write: growth(|s| s.block_write),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Live samples from [`StatsCommand::sample`]
+///
+/// Dropping the stream stops the CLI.
+#[derive(Debug)]
+pub struct StatsStream {
+    samples: mpsc::Receiver<Result<StatsSample>>,
+}
+
+impl StatsStream {
+    /// Wait for the next sample, or `None` once the stream has ended
+    pub async fn next_sample(&mut self) -> Option<Result<StatsSample>> {
+        self.samples.recv().await
+    }
+
+    /// Collect samples for `window` and summarize them per container
+    ///
+    /// Returns early if the stream ends first. The stream stays usable, so
+    /// consecutive windows can be summarized.
+    ///
+    /// # Errors
+    /// Returns the error that ended the stream during the window.
+    pub async fn summarize(&mut self, window: Duration) -> Result<Vec<StatsSummary>> {
+        let deadline = tokio::time::Instant::now() + window;
+        let mut samples = Vec::new();
+        while let Ok(Some(sample)) = tokio::time::timeout_at(deadline, self.samples.recv()).await {
+            samples.push(sample?);
+        }
+        Ok(StatsSummary::from_samples(&samples))
+    }
+}
+
+impl Stream for StatsStream {
+    type Item = Result<StatsSample>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.samples.poll_recv(cx)
+    }
 }
 
 #[cfg(test)]
@@ -481,4 +834,139 @@ mod tests {
         let stats = StatsCommand::parse_json_stats("");
         assert!(stats.is_empty());
     }
+
+    #[test]
+    fn test_container_stats_bytes() {
+        let stats = StatsCommand::parse_json_stats(
+            r#"{"Container":"abc123","Name":"test","CPUPerc":"1.23%","MemUsage":"512MiB / 2GiB","MemPerc":"25.00%","NetIO":"1.2kB / 3.4kB","BlockIO":"4.5MB / 0B","PIDs":"42"}"#,
+        );
+        assert_eq!(
+            stats[0].memory_bytes(),
+            Some((512 * 1024 * 1024, 2 * 1024 * 1024 * 1024))
+        );
+        assert_eq!(stats[0].network_bytes(), Some((1_200, 3_400)));
+        assert_eq!(stats[0].block_io_bytes(), Some((4_500_000, 0)));
+    }
+
+    fn sample(container: &str, at: Instant, cpu: &str, memory: &str, network: &str) -> StatsSample {
+        StatsSample::from_stats(
+            &ContainerStats {
+                container_id: container.to_string(),
+                name: container.to_string(),
+                cpu_percent: cpu.to_string(),
+                memory_usage: memory.to_string(),
+                memory_percent: "0.00%".to_string(),
+                network_io: network.to_string(),
+                block_io: "0B / 0B".to_string(),
+                pids: "1".to_string(),
+            },
+            at,
+        )
+    }
+
+    #[test]
+    fn test_sample_rates() {
+        let start = Instant::now();
+        let before = sample("web", start, "0%", "1MiB / 1GiB", "1MB / 2MB");
+        let after = sample(
+            "web",
+            start + Duration::from_secs(2),
+            "0%",
+            "1MiB / 1GiB",
+            "3MB / 1MB",
+        );
+
+        let rates = after.rates_since(&before).unwrap();
+        assert_eq!(rates.elapsed, Duration::from_secs(2));
+        assert_eq!(rates.net_rx_per_sec, Some(1_000_000.0));
+        assert_eq!(rates.net_tx_per_sec, Some(0.0));
+        assert_eq!(rates.block_read_per_sec, Some(0.0));
+        assert!(before.rates_since(&after).is_none());
+    }
+
+    #[test]
+    fn test_summary_per_container() {
+        let start = Instant::now();
+        let second = Duration::from_secs(1);
+        let samples = [
+            sample("web", start, "10%", "100MiB / 1GiB", "1kB / 0B"),
+            sample("db", start, "50%", "1GiB / 2GiB", "0B / 0B"),
+            sample("web", start + second, "30%", "300MiB / 1GiB", "5kB / 1kB"),
+            sample(
+                "web",
+                start + 2 * second,
+                "--",
+                "200MiB / 1GiB",
+                "9kB / 2kB",
+            ),
+        ];
+
+        let summaries = StatsSummary::from_samples(&samples);
+        assert_eq!(summaries.len(), 2);
+        let web = &summaries[0];
+        assert_eq!(web.container_id, "web");
+        assert_eq!(web.samples, 3);
+        assert_eq!(web.duration, 2 * second);
+        assert_eq!(web.peak_memory, Some(300 * 1024 * 1024));
+        assert_eq!(web.average_cpu, Some(20.0));
+        assert_eq!(web.peak_cpu, Some(30.0));
+        assert_eq!(web.net_rx, Some(8_000));
+        assert_eq!(web.net_tx, Some(2_000));
+        assert_eq!(summaries[1].container_id, "db");
+        assert_eq!(summaries[1].samples, 1);
+        assert_eq!(summaries[1].net_rx, Some(0));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_sample_streams_typed_samples_with_rates() {
+        const SCRIPT: &str = r#"printf '\033[2J\033[H%s\n' '{"Container":"web","Name":"web","CPUPerc":"10.00%","MemUsage":"100MiB / 1GiB","MemPerc":"9.77%","NetIO":"1kB / 0B","BlockIO":"0B / 0B","PIDs":"3"}'
+sleep 0.1
+printf '\033[2J\033[H%s\n' '{"Container":"web","Name":"web","CPUPerc":"30.00%","MemUsage":"300MiB / 1GiB","MemPerc":"29.30%","NetIO":"5kB / 1kB","BlockIO":"1MB / 0B","PIDs":"4"}'"#;
+        let runner = crate::runner::ShellRunner::new(SCRIPT);
+        let mut cmd = StatsCommand::new().container("web").no_stream();
+        cmd.with_runner(runner.clone());
+
+        let mut stats = cmd.sample();
+        let first = stats.next_sample().await.unwrap().unwrap();
+        assert_eq!(first.name, "web");
+        assert_eq!(first.memory_usage, Some(100 * 1024 * 1024));
+        assert_eq!(first.memory_limit, Some(1024 * 1024 * 1024));
+        assert_eq!(first.pids, Some(3));
+        assert!(first.rates.is_none());
+
+        let summaries = stats.summarize(Duration::from_secs(5)).await.unwrap();
+        assert_eq!(summaries[0].peak_memory, Some(300 * 1024 * 1024));
+        assert!(stats.next_sample().await.is_none());
+        assert_eq!(
+            runner.invocations()[0].args,
+            ["stats", "--format", "json", "web"]
+        );
+
+        let mut stats = cmd.sample();
+        stats.next_sample().await.unwrap().unwrap();
+        let second = stats.next_sample().await.unwrap().unwrap();
+        let rates = second.rates.unwrap();
+        assert!(rates.elapsed > Duration::ZERO);
+        assert!(rates.net_rx_per_sec.unwrap() > 0.0);
+        assert!(rates.block_read_per_sec.unwrap() > 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_sample_yields_cli_failure() {
+        use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
+
+        let mut cmd = StatsCommand::new().container("missing");
+        cmd.with_runner(FakeRunner::new().on(
+            ArgMatcher::Any,
+            FakeResponse::failure(
+                1,
+                "Error response from daemon: No such container: missing\n",
+            ),
+        ));
+
+        let mut stats = cmd.sample();
+        assert!(stats.next_sample().await.unwrap().is_err());
+        assert!(stats.next_sample().await.is_none());
+    }
 }
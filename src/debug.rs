@@ -1,11 +1,11 @@
 //! Debugging and reliability features for Docker commands.
 
 use crate::command::{CommandExecutor, CommandOutput};
-use crate::error::Result;
-use crate::tracing_compat::{debug, info, trace, warn};
+use crate::error::{Error, ErrorKind, Result};
+use crate::redact;
+use crate::tracing_compat::{info, trace};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use tokio::time::sleep;
 
 /// Configuration for dry-run mode and debugging
 #[derive(Debug, Clone)]
@@ -62,10 +62,10 @@ impl DebugConfig {
         self
     }
 
-    /// Log a command
+    /// Log a command, masking secrets with the default [`Redactor`](crate::redact::Redactor)
     pub fn log_command(&self, command: &str) {
         if let Ok(mut log) = self.command_log.lock() {
-            log.push(command.to_string());
+            log.push(redact::default_redactor().redact_command_line(command));
         }
     }
 
@@ -89,7 +89,30 @@ impl DebugConfig {
 /// Type alias for retry callback function
 pub type RetryCallback = Arc<dyn Fn(u32, &str) + Send + Sync>;
 
+/// Type alias for the predicate deciding whether an error is retried
+pub type RetryPredicate = Arc<dyn Fn(&Error) -> bool + Send + Sync>;
+
 /// Retry policy for handling transient failures
+///
+/// Attach it to any command with
+/// [`DockerCommand::with_retry`](crate::DockerCommand::with_retry), to an
+/// executor with [`CommandExecutor::retry`], or to every command of a
+/// [`DockerClient`](crate::DockerClient). By default only the known-transient
+/// [`TRANSIENT_KINDS`](Self::TRANSIENT_KINDS) are retried; opt other failures
+/// in with [`retry_if`](Self::retry_if) or [`retry_on`](Self::retry_on).
+///
+/// ```rust,no_run
+/// use docker_wrapper::{DockerCommand, ErrorKind, PullCommand, RetryPolicy};
+///
+/// # async fn example() -> docker_wrapper::Result<()> {
+/// let policy = RetryPolicy::new()
+///     .max_attempts(5)
+///     .retry_on([ErrorKind::Timeout, ErrorKind::CommandFailed]);
+///
+/// PullCommand::new("redis:7").with_retry(policy).execute().await?;
+/// # Ok(())
+/// # }
+/// ```
 #[derive(Clone)]
 pub struct RetryPolicy {
     /// Maximum number of attempts
@@ -100,6 +123,9 @@ pub struct RetryPolicy {
 
     /// Callback for retry events
     pub on_retry: Option<RetryCallback>,
+
+    /// Custom retry decision, replacing [`TRANSIENT_KINDS`](Self::TRANSIENT_KINDS)
+    pub retry_if: Option<RetryPredicate>,
 }
 
 impl std::fmt::Debug for RetryPolicy {
@@ -108,6 +134,7 @@ impl std::fmt::Debug for RetryPolicy {
             .field("max_attempts", &self.max_attempts)
             .field("backoff", &self.backoff)
             .field("on_retry", &self.on_retry.is_some())
+            .field("retry_if", &self.retry_if.is_some())
             .finish()
     }
 }
@@ -147,11 +174,20 @@ impl Default for RetryPolicy {
                 multiplier: 2.0,
             },
             on_retry: None,
+            retry_if: None,
         }
     }
 }
 
 impl RetryPolicy {
+    /// Error kinds retried when no [`retry_if`](Self::retry_if) predicate is set
+    ///
+    /// Registry and network failures ([`Error::Network`]) and timeouts.
+    /// Unclassified [`Error::CommandFailed`], I/O errors (mostly missing files
+    /// and spawn failures) and an unreachable daemon are not in the list,
+    /// since retrying them usually just repeats the failure.
+    pub const TRANSIENT_KINDS: &'static [ErrorKind] = &[ErrorKind::Network, ErrorKind::Timeout];
+
     /// Create a new retry policy
     #[must_use]
     pub fn new() -> Self {
@@ -182,6 +218,38 @@ impl RetryPolicy {
         self
     }
 
+    /// Retry only the errors for which `predicate` returns true
+    ///
+    /// Cancellation ([`Error::Interrupted`]) is never retried, whatever the
+    /// predicate says.
+    #[must_use]
+    pub fn retry_if<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&Error) -> bool + Send + Sync + 'static,
+    {
+        self.retry_if = Some(Arc::new(predicate));
+        self
+    }
+
+    /// Retry only errors of the given kinds
+    #[must_use]
+    pub fn retry_on(self, kinds: impl IntoIterator<Item = ErrorKind>) -> Self {
+        let kinds: Vec<ErrorKind> = kinds.into_iter().collect();
+        self.retry_if(move |error| kinds.contains(&error.kind()))
+    }
+
+    /// Decide whether `error` should be retried
+    #[must_use]
+    pub fn should_retry(&self, error: &Error) -> bool {
+        if matches!(error, Error::Interrupted) {
+            return false;
+        }
+        match &self.retry_if {
+            Some(predicate) => predicate(error),
+            None => Self::TRANSIENT_KINDS.contains(&error.kind()),
+        }
+    }
+
     /// Calculate delay for attempt number
     #[must_use]
     pub fn calculate_delay(&self, attempt: u32) -> Duration {
@@ -208,7 +276,8 @@ impl RetryPolicy {
         }
     }
 
-    /// Check if an error is retryable
+    /// Check if an error message looks like a transient failure
+    #[deprecated(note = "use `RetryPolicy::should_retry`, which decides on the classified error")]
     #[must_use]
     pub fn is_retryable(error_str: &str) -> bool {
         // Common retryable Docker errors
@@ -289,7 +358,9 @@ impl DebugExecutor {
         command_name: &str,
         args: Vec<String>,
     ) -> Result<CommandOutput> {
-        let command_str = format!("docker {} {}", command_name, args.join(" "));
+        let mut full_args = vec![command_name.to_string()];
+        full_args.extend(args.iter().cloned());
+        let command_str = self.executor.invocation(full_args)?.command_line();
 
         // Log the command
         self.debug_config.log_command(&command_str);
@@ -327,107 +398,37 @@ impl DebugExecutor {
     }
 
     /// Execute command with retry logic
-    #[cfg_attr(
-        feature = "tracing",
-        tracing::instrument(
-            name = "debug.retry",
-            skip(self, args, policy),
-            fields(
-                command = %command_name,
-                max_attempts = policy.max_attempts,
-            )
-        )
-    )]
+    ///
+    /// Retries are driven by the base executor; this only adds verbose output
+    /// for each retry.
     async fn execute_with_retry(
         &self,
         command_name: &str,
         args: Vec<String>,
         policy: &RetryPolicy,
     ) -> Result<CommandOutput> {
-        let mut attempt = 0;
-        let mut last_error = None;
-
-        debug!(
-            max_attempts = policy.max_attempts,
-            "starting command execution with retry"
-        );
-
-        while attempt < policy.max_attempts {
-            attempt += 1;
-
-            trace!(attempt = attempt, "executing attempt");
+        let mut policy = policy.clone();
 
-            if self.debug_config.verbose && attempt > 1 {
+        if self.debug_config.verbose {
+            let inner = policy.on_retry.take();
+            let delays = policy.clone();
+            policy = policy.on_retry(move |attempt, error| {
+                if let Some(ref callback) = inner {
+                    callback(attempt, error);
+                }
                 eprintln!(
-                    "[VERBOSE] Retry attempt {}/{}",
-                    attempt, policy.max_attempts
+                    "[VERBOSE] Attempt {attempt}/{} failed, waiting {:?} before retry",
+                    delays.max_attempts,
+                    delays.calculate_delay(attempt)
                 );
-            }
-
-            match self
-                .executor
-                .execute_command(command_name, args.clone())
-                .await
-            {
-                Ok(output) => {
-                    if attempt > 1 {
-                        info!(attempt = attempt, "command succeeded after retry");
-                    }
-                    return Ok(output);
-                }
-                Err(e) => {
-                    let error_str = e.to_string();
-
-                    // Check if retryable
-                    if !RetryPolicy::is_retryable(&error_str) {
-                        debug!(
-                            error = %error_str,
-                            "error is not retryable, failing immediately"
-                        );
-                        return Err(e);
-                    }
-
-                    // Last attempt?
-                    if attempt >= policy.max_attempts {
-                        warn!(
-                            attempt = attempt,
-                            max_attempts = policy.max_attempts,
-                            error = %error_str,
-                            "all retry attempts exhausted"
-                        );
-                        return Err(e);
-                    }
-
-                    // Call retry callback
-                    if let Some(ref callback) = policy.on_retry {
-                        callback(attempt, &error_str);
-                    }
-
-                    // Calculate and apply delay
-                    let delay = policy.calculate_delay(attempt);
-
-                    #[allow(clippy::cast_possible_truncation)]
-                    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
-                    let delay_ms = delay.as_millis() as u64;
-                    warn!(
-                        attempt = attempt,
-                        max_attempts = policy.max_attempts,
-                        error = %error_str,
-                        delay_ms = delay_ms,
-                        "command failed, will retry after delay"
-                    );
-
-                    if self.debug_config.verbose {
-                        eprintln!("[VERBOSE] Waiting {delay:?} before retry");
-                    }
-                    sleep(delay).await;
-
-                    last_error = Some(e);
-                }
-            }
+            });
         }
 
-        Err(last_error.unwrap_or_else(|| crate::error::Error::custom("Retry failed")))
+        self.executor
+            .clone()
+            .retry(policy)
+            .execute_command(command_name, args)
+            .await
     }
 
     /// Get command history
@@ -456,9 +457,17 @@ pub struct DryRunPreview {
 
 impl DryRunPreview {
     /// Create a preview of commands
+    ///
+    /// Secrets are masked with the default [`Redactor`](crate::redact::Redactor).
     #[must_use]
     pub fn new(commands: Vec<String>) -> Self {
-        Self { commands }
+        let redactor = redact::default_redactor();
+        Self {
+            commands: commands
+                .into_iter()
+                .map(|command| redactor.redact_command_line(&command))
+                .collect(),
+        }
     }
 
     /// Print the preview
@@ -534,6 +543,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn test_retryable_errors() {
         assert!(RetryPolicy::is_retryable("connection refused"));
         assert!(RetryPolicy::is_retryable("operation timeout"));
@@ -544,6 +554,53 @@ mod tests {
         assert!(!RetryPolicy::is_retryable("permission denied"));
     }
 
+    #[test]
+    fn test_should_retry() {
+        let policy = RetryPolicy::new();
+        assert!(policy.should_retry(&Error::timeout(5)));
+        assert!(policy.should_retry(&Error::Network { output: None }));
+        assert!(!policy.should_retry(&Error::command_failed("docker pull", 1, "", "EOF")));
+        assert!(!policy.should_retry(&Error::from(std::io::Error::from(
+            std::io::ErrorKind::NotFound
+        ))));
+        assert!(!policy.should_retry(&Error::daemon_not_running()));
+        assert!(!policy.should_retry(&Error::invalid_config("bad")));
+        assert!(!policy.should_retry(&Error::Interrupted));
+
+        let policy = RetryPolicy::new().retry_on([ErrorKind::InvalidConfig]);
+        assert!(policy.should_retry(&Error::invalid_config("bad")));
+        assert!(!policy.should_retry(&Error::timeout(5)));
+
+        let policy = RetryPolicy::new().retry_if(|_| true);
+        assert!(!policy.should_retry(&Error::Interrupted));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_masks_secrets() {
+        let executor = DebugExecutor::new().dry_run(true);
+        let output = executor
+            .execute_command(
+                "login",
+                vec![
+                    "-u".to_string(),
+                    "me".to_string(),
+                    "--password".to_string(),
+                    "hunter2".to_string(),
+                ],
+            )
+            .await
+            .unwrap();
+
+        assert!(!output.stdout.contains("hunter2"));
+        assert_eq!(
+            executor.get_command_log(),
+            vec!["docker login -u me --password ***"]
+        );
+
+        let preview = DryRunPreview::new(vec!["docker run -e API_KEY=abc app".to_string()]);
+        assert_eq!(preview.commands, vec!["docker run -e API_KEY=*** app"]);
+    }
+
     #[test]
     fn test_command_logging() {
         let config = DebugConfig::new();
@@ -10,27 +10,55 @@
 //! match RunCommand::new("nginx").execute().await {
 //!     Ok(id) => println!("Started: {}", id.short()),
 //!     Err(Error::DockerNotFound) => eprintln!("Docker is not installed"),
-//!     Err(Error::DaemonNotRunning) => eprintln!("Start the Docker daemon"),
+//!     Err(Error::DaemonNotRunning { .. }) => eprintln!("Start the Docker daemon"),
+//!     Err(Error::ImageNotFound { image, .. }) => eprintln!("No such image: {}", image),
 //!     Err(Error::CommandFailed { stderr, .. }) => eprintln!("Failed: {}", stderr),
 //!     Err(e) => eprintln!("Error: {}", e),
 //! }
 //! # }
 
+mod classify;
+
 use thiserror::Error;
 
 /// Result type for docker-wrapper operations
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Raw output of a failed CLI invocation
+///
+/// Attached to errors that were classified from a command's stderr, so the
+/// original message is never lost.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandFailure {
+    /// The command that failed
+    pub command: String,
+    /// Exit code returned by the command
+    pub exit_code: i32,
+    /// Standard output from the command
+    pub stdout: String,
+    /// Standard error from the command
+    pub stderr: String,
+}
+
 /// Main error type for all docker-wrapper operations
+///
+/// New variants may be added in minor releases, so matches need a wildcard
+/// arm. Variants classified from a command's stderr carry its raw output and
+/// are matched with `{ .. }`.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum Error {
     /// Docker binary not found in PATH
     #[error("Docker binary not found in PATH")]
     DockerNotFound,
 
     /// Docker daemon is not running
-    #[error("Docker daemon is not running")]
-    DaemonNotRunning,
+    #[error("Docker daemon is not running{}", failed_command(.output.as_deref()))]
+    #[non_exhaustive]
+    DaemonNotRunning {
+        /// Output of the command that could not reach the daemon
+        output: Option<Box<CommandFailure>>,
+    },
 
     /// Docker version is not supported
     #[error("Docker version {found} is not supported (minimum: {minimum})")]
@@ -41,6 +69,15 @@ pub enum Error {
         minimum: String,
     },
 
+    /// The container runtime does not support a command or flag
+    #[error("{feature} is not supported by {runtime}")]
+    UnsupportedByRuntime {
+        /// The runtime that lacks the feature
+        runtime: String,
+        /// The unsupported command, flag or option
+        feature: String,
+    },
+
     /// Failed to execute Docker command
     #[error("Docker command failed: {command}")]
     CommandFailed {
@@ -70,16 +107,85 @@ pub enum Error {
 
     /// Docker container not found
     #[error("Container not found: {container_id}")]
+    #[non_exhaustive]
     ContainerNotFound {
         /// The container ID that was not found
         container_id: String,
+        /// Output of the failed command, if produced by one
+        output: Option<Box<CommandFailure>>,
     },
 
     /// Docker image not found
     #[error("Image not found: {image}")]
+    #[non_exhaustive]
     ImageNotFound {
         /// The image name that was not found
         image: String,
+        /// Output of the failed command, if produced by one
+        output: Option<Box<CommandFailure>>,
+    },
+
+    /// Image manifest not found in the registry (e.g. unknown tag)
+    #[error("Manifest unknown: {reference}")]
+    #[non_exhaustive]
+    ManifestUnknown {
+        /// The image reference whose manifest was not found
+        reference: String,
+        /// Output of the failed command, if produced by one
+        output: Option<Box<CommandFailure>>,
+    },
+
+    /// A container name is already in use
+    #[error("Name already in use: {name}")]
+    #[non_exhaustive]
+    NameConflict {
+        /// The conflicting name
+        name: String,
+        /// Output of the failed command, if produced by one
+        output: Option<Box<CommandFailure>>,
+    },
+
+    /// A host port is already allocated
+    #[error("Port already allocated: {port}")]
+    #[non_exhaustive]
+    PortAllocated {
+        /// The host address and port, as reported by Docker
+        port: String,
+        /// Output of the failed command, if produced by one
+        output: Option<Box<CommandFailure>>,
+    },
+
+    /// Registry or daemon refused access
+    #[error("Unauthorized: access denied")]
+    #[non_exhaustive]
+    Unauthorized {
+        /// Output of the failed command, if produced by one
+        output: Option<Box<CommandFailure>>,
+    },
+
+    /// A registry or network connection failed in a way that may clear up
+    /// (timeouts, resets, rate limits, 5xx responses)
+    #[error("Network error{}", failed_command(.output.as_deref()))]
+    #[non_exhaustive]
+    Network {
+        /// Output of the failed command, if produced by one
+        output: Option<Box<CommandFailure>>,
+    },
+
+    /// The host ran out of disk space
+    #[error("No space left on device")]
+    #[non_exhaustive]
+    NoSpaceLeft {
+        /// Output of the failed command, if produced by one
+        output: Option<Box<CommandFailure>>,
+    },
+
+    /// A container or the daemon ran out of memory
+    #[error("Out of memory")]
+    #[non_exhaustive]
+    OutOfMemory {
+        /// Output of the failed command, if produced by one
+        output: Option<Box<CommandFailure>>,
     },
 
     /// IO error occurred during operation
@@ -121,6 +227,57 @@ pub enum Error {
     },
 }
 
+/// Fieldless discriminant of [`Error`]
+///
+/// Handy for matching errors without destructuring them, e.g. when deciding
+/// which failures a [`RetryPolicy`](crate::RetryPolicy) should retry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// [`Error::DockerNotFound`]
+    DockerNotFound,
+    /// [`Error::DaemonNotRunning`]
+    DaemonNotRunning,
+    /// [`Error::UnsupportedVersion`]
+    UnsupportedVersion,
+    /// [`Error::UnsupportedByRuntime`]
+    UnsupportedByRuntime,
+    /// [`Error::CommandFailed`]
+    CommandFailed,
+    /// [`Error::ParseError`]
+    ParseError,
+    /// [`Error::InvalidConfig`]
+    InvalidConfig,
+    /// [`Error::ContainerNotFound`]
+    ContainerNotFound,
+    /// [`Error::ImageNotFound`]
+    ImageNotFound,
+    /// [`Error::ManifestUnknown`]
+    ManifestUnknown,
+    /// [`Error::NameConflict`]
+    NameConflict,
+    /// [`Error::PortAllocated`]
+    PortAllocated,
+    /// [`Error::Unauthorized`]
+    Unauthorized,
+    /// [`Error::Network`]
+    Network,
+    /// [`Error::NoSpaceLeft`]
+    NoSpaceLeft,
+    /// [`Error::OutOfMemory`]
+    OutOfMemory,
+    /// [`Error::Io`]
+    Io,
+    /// [`Error::Json`]
+    Json,
+    /// [`Error::Timeout`]
+    Timeout,
+    /// [`Error::Interrupted`]
+    Interrupted,
+    /// [`Error::Custom`]
+    Custom,
+}
+
 impl Error {
     /// Create a new command failed error
     pub fn command_failed(
@@ -151,10 +308,17 @@ impl Error {
         }
     }
 
+    /// Create a new daemon not running error
+    #[must_use]
+    pub fn daemon_not_running() -> Self {
+        Self::DaemonNotRunning { output: None }
+    }
+
     /// Create a new container not found error
     pub fn container_not_found(container_id: impl Into<String>) -> Self {
         Self::ContainerNotFound {
             container_id: container_id.into(),
+            output: None,
         }
     }
 
@@ -162,6 +326,79 @@ impl Error {
     pub fn image_not_found(image: impl Into<String>) -> Self {
         Self::ImageNotFound {
             image: image.into(),
+            output: None,
+        }
+    }
+
+    /// Classify a failed command by its stderr
+    ///
+    /// Known daemon and CLI messages (missing container or image, name and
+    /// port conflicts, access denied, daemon unreachable, unknown manifest,
+    /// disk full, out of memory) become the matching variant with `failure`
+    /// attached. Anything else becomes [`Error::CommandFailed`].
+    #[must_use]
+    pub fn from_failure(failure: CommandFailure) -> Self {
+        classify::classify(failure)
+    }
+
+    /// Get the raw output of the failed command behind this error, if any
+    #[must_use]
+    pub fn output(&self) -> Option<CommandFailure> {
+        match self {
+            Self::CommandFailed {
+                command,
+                exit_code,
+                stdout,
+                stderr,
+            } => Some(CommandFailure {
+                command: command.clone(),
+                exit_code: *exit_code,
+                stdout: stdout.clone(),
+                stderr: stderr.clone(),
+            }),
+            _ => self.attached_output().cloned(),
+        }
+    }
+
+    /// Get the exit code of the failed command behind this error, if any
+    #[must_use]
+    pub fn exit_code(&self) -> Option<i32> {
+        match self {
+            Self::CommandFailed { exit_code, .. } => Some(*exit_code),
+            _ => self.attached_output().map(|output| output.exit_code),
+        }
+    }
+
+    /// Get the stderr of the failed command behind this error, if any
+    #[must_use]
+    pub fn stderr(&self) -> Option<&str> {
+        match self {
+            Self::CommandFailed { stderr, .. } => Some(stderr),
+            _ => self.attached_output().map(|output| output.stderr.as_str()),
+        }
+    }
+
+    fn attached_output(&self) -> Option<&CommandFailure> {
+        match self {
+            Self::DaemonNotRunning { output }
+            | Self::ContainerNotFound { output, .. }
+            | Self::ImageNotFound { output, .. }
+            | Self::ManifestUnknown { output, .. }
+            | Self::NameConflict { output, .. }
+            | Self::PortAllocated { output, .. }
+            | Self::Unauthorized { output }
+            | Self::Network { output }
+            | Self::NoSpaceLeft { output }
+            | Self::OutOfMemory { output } => output.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Create a new unsupported-by-runtime error
+    pub fn unsupported_by_runtime(runtime: impl Into<String>, feature: impl Into<String>) -> Self {
+        Self::UnsupportedByRuntime {
+            runtime: runtime.into(),
+            feature: feature.into(),
         }
     }
 
@@ -182,29 +419,74 @@ impl Error {
     #[must_use]
     pub fn category(&self) -> &'static str {
         match self {
-            Self::DockerNotFound | Self::DaemonNotRunning | Self::UnsupportedVersion { .. } => {
-                "prerequisites"
-            }
+            Self::DockerNotFound
+            | Self::DaemonNotRunning { .. }
+            | Self::UnsupportedVersion { .. }
+            | Self::UnsupportedByRuntime { .. } => "prerequisites",
             Self::CommandFailed { .. } | Self::Timeout { .. } | Self::Interrupted => "command",
             Self::ParseError { .. } | Self::Json { .. } => "parsing",
             Self::InvalidConfig { .. } => "config",
             Self::ContainerNotFound { .. } => "container",
-            Self::ImageNotFound { .. } => "image",
+            Self::ImageNotFound { .. } | Self::ManifestUnknown { .. } => "image",
+            Self::NameConflict { .. } | Self::PortAllocated { .. } => "conflict",
+            Self::Unauthorized { .. } => "auth",
+            Self::Network { .. } => "network",
+            Self::NoSpaceLeft { .. } | Self::OutOfMemory { .. } => "resources",
             Self::Io { .. } => "io",
             Self::Custom { .. } => "custom",
         }
     }
 
+    /// Get the kind of this error
+    #[must_use]
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::DockerNotFound => ErrorKind::DockerNotFound,
+            Self::DaemonNotRunning { .. } => ErrorKind::DaemonNotRunning,
+            Self::UnsupportedVersion { .. } => ErrorKind::UnsupportedVersion,
+            Self::UnsupportedByRuntime { .. } => ErrorKind::UnsupportedByRuntime,
+            Self::CommandFailed { .. } => ErrorKind::CommandFailed,
+            Self::ParseError { .. } => ErrorKind::ParseError,
+            Self::InvalidConfig { .. } => ErrorKind::InvalidConfig,
+            Self::ContainerNotFound { .. } => ErrorKind::ContainerNotFound,
+            Self::ImageNotFound { .. } => ErrorKind::ImageNotFound,
+            Self::ManifestUnknown { .. } => ErrorKind::ManifestUnknown,
+            Self::NameConflict { .. } => ErrorKind::NameConflict,
+            Self::PortAllocated { .. } => ErrorKind::PortAllocated,
+            Self::Unauthorized { .. } => ErrorKind::Unauthorized,
+            Self::Network { .. } => ErrorKind::Network,
+            Self::NoSpaceLeft { .. } => ErrorKind::NoSpaceLeft,
+            Self::OutOfMemory { .. } => ErrorKind::OutOfMemory,
+            Self::Io { .. } => ErrorKind::Io,
+            Self::Json { .. } => ErrorKind::Json,
+            Self::Timeout { .. } => ErrorKind::Timeout,
+            Self::Interrupted => ErrorKind::Interrupted,
+            Self::Custom { .. } => ErrorKind::Custom,
+        }
+    }
+
     /// Check if this error is retryable
+    ///
+    /// True for network errors, timeouts, I/O errors and command failures
+    /// that weren't classified as something more specific.
+    /// [`RetryPolicy`](crate::RetryPolicy) is stricter by default and only
+    /// retries its transient kinds.
     #[must_use]
     pub fn is_retryable(&self) -> bool {
         matches!(
-            self,
-            Self::CommandFailed { .. } | Self::Timeout { .. } | Self::Io { .. }
+            self.kind(),
+            ErrorKind::Network | ErrorKind::CommandFailed | ErrorKind::Timeout | ErrorKind::Io
         )
     }
 }
 
+/// Suffix naming the command that hit the error, for display
+fn failed_command(output: Option<&CommandFailure>) -> String {
+    output
+        .map(|output| format!(" ({} failed)", output.command))
+        .unwrap_or_default()
+}
+
 impl From<std::io::Error> for Error {
     fn from(err: std::io::Error) -> Self {
         Self::Io {
@@ -245,8 +527,38 @@ mod tests {
     fn test_retryable_errors() {
         assert!(Error::command_failed("test", 1, "", "").is_retryable());
         assert!(Error::timeout(30).is_retryable());
+        assert!(Error::from_failure(CommandFailure {
+            stderr: "Error response from daemon: Get \"https://registry-1.docker.io/v2/\": net/http: TLS handshake timeout".into(),
+            ..CommandFailure::default()
+        })
+        .is_retryable());
         assert!(!Error::DockerNotFound.is_retryable());
         assert!(!Error::invalid_config("test").is_retryable());
+        assert!(!Error::daemon_not_running().is_retryable());
+        assert!(!Error::Interrupted.is_retryable());
+        assert!(!Error::from_failure(CommandFailure {
+            stderr: "Error response from daemon: No such container: web".into(),
+            ..CommandFailure::default()
+        })
+        .is_retryable());
+    }
+
+    #[test]
+    fn test_daemon_not_running_names_failed_command() {
+        assert_eq!(
+            Error::daemon_not_running().to_string(),
+            "Docker daemon is not running"
+        );
+        let err = Error::from_failure(CommandFailure {
+            command: "docker push app:1".into(),
+            exit_code: 1,
+            stderr: "Cannot connect to the Docker daemon at unix:///var/run/docker.sock. Is the docker daemon running?".into(),
+            ..CommandFailure::default()
+        });
+        assert_eq!(
+            err.to_string(),
+            "Docker daemon is not running (docker push app:1 failed)"
+        );
     }
 
     #[test]
@@ -0,0 +1,469 @@
+//! Mapping of known daemon and CLI error messages to typed errors.
+
+use super::{CommandFailure, Error};
+use crate::redact;
+
+/// Subcommands whose stderr also carries the container's own output, or
+/// the output of build steps
+const CONTAINER_OUTPUT_COMMANDS: &[&str] =
+    &["attach", "build", "exec", "logs", "run", "start", "up"];
+
+/// Line prefixes the CLI and daemon put on their own messages (lowercase)
+const CLI_PREFIXES: &[&str] = &[
+    "error response from daemon:",
+    "docker:",
+    "error:",
+    "cannot connect to the docker daemon",
+    "error during connect",
+    "cannot connect to podman",
+];
+
+/// Classify a failed command by the messages in its stderr.
+///
+/// For commands that stream container or build step output (`exec`,
+/// attached `run`, `build`, ...) only the lines written by the CLI or daemon
+/// are considered, so an application printing "permission denied" or "out
+/// of memory" stays an [`Error::CommandFailed`].
+pub(super) fn classify(failure: CommandFailure) -> Error {
+    let messages = cli_messages(&failure);
+    // ASCII lowercasing keeps byte offsets valid for the original text
+    let lower = messages.to_ascii_lowercase();
+    let original = messages.as_str();
+
+    let kind = if is_daemon_unreachable(&lower) {
+        Kind::DaemonNotRunning
+    } else if is_network_failure(&lower) {
+        Kind::Network
+    } else if lower.contains("no space left on device") {
+        Kind::NoSpaceLeft
+    } else if lower.contains("out of memory")
+        || lower.contains("oomkilled")
+        || lower.contains("cannot allocate memory")
+    {
+        Kind::OutOfMemory
+    } else if lower.contains("manifest unknown")
+        || (lower.contains("manifest for ") && lower.contains(" not found"))
+    {
+        Kind::ManifestUnknown(between(original, &lower, "manifest for ", " not found"))
+    } else if is_access_denied(&lower) {
+        Kind::Unauthorized
+    } else if lower.contains("is already in use by") {
+        Kind::NameConflict(
+            between(original, &lower, "container name \"", "\"")
+                .trim_start_matches('/')
+                .to_string(),
+        )
+    } else if lower.contains("port is already allocated") {
+        Kind::PortAllocated(between(original, &lower, "bind for ", " failed"))
+    } else if let Some(pos) = lower.find(": bind: address already in use") {
+        let port = original[..pos]
+            .split_whitespace()
+            .last()
+            .unwrap_or_default()
+            .to_string();
+        Kind::PortAllocated(port)
+    } else if lower.contains("no container with name or id") {
+        Kind::ContainerNotFound(between(
+            original,
+            &lower,
+            "no container with name or id \"",
+            "\"",
+        ))
+    } else if lower.contains("no such container") {
+        Kind::ContainerNotFound(after(original, &lower, "no such container: "))
+    } else if lower.contains("no such image") {
+        Kind::ImageNotFound(after(original, &lower, "no such image: "))
+    } else if let Some(pos) = lower.find(": image not known") {
+        let image = original[..pos]
+            .rsplit(": ")
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        Kind::ImageNotFound(image)
+    } else {
+        Kind::Unclassified
+    };
+
+    let output = Some(Box::new(failure));
+    match kind {
+        Kind::DaemonNotRunning => Error::DaemonNotRunning { output },
+        Kind::NoSpaceLeft => Error::NoSpaceLeft { output },
+        Kind::OutOfMemory => Error::OutOfMemory { output },
+        Kind::ManifestUnknown(reference) => Error::ManifestUnknown { reference, output },
+        Kind::Unauthorized => Error::Unauthorized { output },
+        Kind::Network => Error::Network { output },
+        Kind::NameConflict(name) => Error::NameConflict { name, output },
+        Kind::PortAllocated(port) => Error::PortAllocated { port, output },
+        Kind::ContainerNotFound(container_id) => Error::ContainerNotFound {
+            container_id,
+            output,
+        },
+        Kind::ImageNotFound(image) => Error::ImageNotFound { image, output },
+        Kind::Unclassified => {
+            let failure = output.map(|boxed| *boxed).unwrap_or_default();
+            Error::CommandFailed {
+                command: failure.command,
+                exit_code: failure.exit_code,
+                stdout: failure.stdout,
+                stderr: failure.stderr,
+            }
+        }
+    }
+}
+
+enum Kind {
+    DaemonNotRunning,
+    NoSpaceLeft,
+    OutOfMemory,
+    ManifestUnknown(String),
+    Unauthorized,
+    Network,
+    NameConflict(String),
+    PortAllocated(String),
+    ContainerNotFound(String),
+    ImageNotFound(String),
+    Unclassified,
+}
+
+/// The part of stderr that the CLI or daemon wrote.
+fn cli_messages(failure: &CommandFailure) -> String {
+    if !carries_container_output(&failure.command) {
+        return failure.stderr.clone();
+    }
+    failure
+        .stderr
+        .lines()
+        .filter(|line| {
+            let line = line.trim_start().to_ascii_lowercase();
+            CLI_PREFIXES.iter().any(|prefix| line.starts_with(prefix))
+                || line.contains(" level=fatal ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Whether stderr of `command` may include output of the container itself.
+fn carries_container_output(command: &str) -> bool {
+    let mut words = command.split_whitespace();
+    let program = words.next().unwrap_or_default();
+    let args: Vec<String> = words.map(str::to_string).collect();
+    let rest = &args[redact::subcommand_index(&args)..];
+    let Some((subcommand, rest)) = rest.split_first() else {
+        return false;
+    };
+    if program.ends_with("compose") {
+        return CONTAINER_OUTPUT_COMMANDS.contains(&subcommand.as_str());
+    }
+    match subcommand.as_str() {
+        "builder" | "buildx" | "compose" | "container" | "image" => rest
+            .iter()
+            .any(|arg| CONTAINER_OUTPUT_COMMANDS.contains(&arg.as_str())),
+        other => CONTAINER_OUTPUT_COMMANDS.contains(&other),
+    }
+}
+
+fn is_daemon_unreachable(lower: &str) -> bool {
+    lower.contains("cannot connect to the docker daemon")
+        || lower.contains("is the docker daemon running")
+        || lower.contains("error during connect")
+        || lower.contains("unable to connect to podman")
+        || lower.contains("cannot connect to podman")
+}
+
+/// Registry and network failures that tend to clear up on their own
+fn is_network_failure(lower: &str) -> bool {
+    [
+        "tls handshake timeout",
+        "i/o timeout",
+        "connection reset by peer",
+        "connection refused",
+        "toomanyrequests",
+        "too many requests",
+        "temporary failure in name resolution",
+        "502 bad gateway",
+        "503 service unavailable",
+        "504 gateway timeout",
+        "unexpected eof",
+    ]
+    .iter()
+    .any(|pattern| lower.contains(pattern))
+}
+
+fn is_access_denied(lower: &str) -> bool {
+    lower.contains("unauthorized")
+        || lower.contains("authentication required")
+        || lower.contains("pull access denied")
+        || lower.contains("requested access to the resource is denied")
+        || lower.lines().any(|line| {
+            // Registry responses: `denied: <reason>`
+            let line = line.trim_start();
+            line.starts_with("denied: ") || line.starts_with("error response from daemon: denied: ")
+        })
+}
+
+/// Text following `marker` up to the end of the line, with quotes trimmed.
+fn after(original: &str, lower: &str, marker: &str) -> String {
+    let Some(start) = lower.find(marker).map(|pos| pos + marker.len()) else {
+        return String::new();
+    };
+    original[start..]
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .trim_matches(|c| c == '"' || c == '\'')
+        .to_string()
+}
+
+/// Text between `start_marker` and the next `end_marker` on the same line.
+fn between(original: &str, lower: &str, start_marker: &str, end_marker: &str) -> String {
+    let Some(start) = lower.find(start_marker).map(|pos| pos + start_marker.len()) else {
+        return String::new();
+    };
+    let line_end = lower[start..]
+        .find('\n')
+        .map_or(lower.len(), |pos| start + pos);
+    let end = lower[start..line_end]
+        .find(end_marker)
+        .map_or(line_end, |pos| start + pos);
+    original[start..end].trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failure(stderr: &str) -> CommandFailure {
+        CommandFailure {
+            command: "docker test".to_string(),
+            exit_code: 1,
+            stdout: String::new(),
+            stderr: stderr.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_no_such_container() {
+        let err = classify(failure(
+            "Error response from daemon: No such container: web-1\n",
+        ));
+        match err {
+            Error::ContainerNotFound {
+                container_id,
+                output,
+            } => {
+                assert_eq!(container_id, "web-1");
+                assert!(output.unwrap().stderr.contains("No such container"));
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+
+        let err = classify(failure(
+            "Error: no container with name or ID \"web\" found: no such container",
+        ));
+        assert!(
+            matches!(err, Error::ContainerNotFound { ref container_id, .. } if container_id == "web")
+        );
+    }
+
+    #[test]
+    fn test_no_such_image() {
+        let err = classify(failure(
+            "Error response from daemon: No such image: redis:7",
+        ));
+        assert!(matches!(err, Error::ImageNotFound { ref image, .. } if image == "redis:7"));
+
+        let err = classify(failure("Error: missing: image not known"));
+        assert!(matches!(err, Error::ImageNotFound { ref image, .. } if image == "missing"));
+    }
+
+    #[test]
+    fn test_name_conflict() {
+        let err = classify(failure(
+            "docker: Error response from daemon: Conflict. The container name \"/web\" is already in use by container \"0123abcd\". You have to remove (or rename) that container to be able to reuse that name.",
+        ));
+        assert!(matches!(err, Error::NameConflict { ref name, .. } if name == "web"));
+    }
+
+    #[test]
+    fn test_port_allocated() {
+        let err = classify(failure(
+            "docker: Error response from daemon: driver failed programming external connectivity on endpoint web: Bind for 0.0.0.0:8080 failed: port is already allocated.",
+        ));
+        assert!(matches!(err, Error::PortAllocated { ref port, .. } if port == "0.0.0.0:8080"));
+
+        let err = classify(failure(
+            "Error response from daemon: ports are not available: exposing port TCP 0.0.0.0:5432 -> 0.0.0.0:0: listen tcp4 0.0.0.0:5432: bind: address already in use",
+        ));
+        assert!(matches!(err, Error::PortAllocated { ref port, .. } if port == "0.0.0.0:5432"));
+    }
+
+    #[test]
+    fn test_unauthorized() {
+        for stderr in [
+            "Error response from daemon: pull access denied for private/app, repository does not exist or may require 'docker login': denied: requested access to the resource is denied",
+            "unauthorized: authentication required",
+            "denied: requested access to the resource is denied",
+        ] {
+            assert!(
+                matches!(classify(failure(stderr)), Error::Unauthorized { .. }),
+                "{stderr}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_daemon_not_running() {
+        let err = classify(failure(
+            "Cannot connect to the Docker daemon at unix:///var/run/docker.sock. Is the docker daemon running?",
+        ));
+        assert!(matches!(err, Error::DaemonNotRunning { output: Some(_) }));
+    }
+
+    #[test]
+    fn test_manifest_unknown() {
+        let err = classify(failure(
+            "Error response from daemon: manifest for alpine:nope not found: manifest unknown: manifest unknown",
+        ));
+        assert!(
+            matches!(err, Error::ManifestUnknown { ref reference, .. } if reference == "alpine:nope")
+        );
+    }
+
+    #[test]
+    fn test_resource_exhaustion() {
+        let err = classify(failure(
+            "write /var/lib/docker/tmp/GetImageBlob: no space left on device",
+        ));
+        assert!(matches!(err, Error::NoSpaceLeft { .. }));
+
+        let err = classify(failure("fork/exec /usr/bin/runc: cannot allocate memory"));
+        assert!(matches!(err, Error::OutOfMemory { .. }));
+    }
+
+    #[test]
+    fn test_unknown_message_stays_command_failed() {
+        let err = classify(failure("something unexpected happened"));
+        match err {
+            Error::CommandFailed {
+                command,
+                exit_code,
+                stderr,
+                ..
+            } => {
+                assert_eq!(command, "docker test");
+                assert_eq!(exit_code, 1);
+                assert_eq!(stderr, "something unexpected happened");
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    fn failure_of(command: &str, stderr: &str) -> CommandFailure {
+        CommandFailure {
+            command: command.to_string(),
+            ..failure(stderr)
+        }
+    }
+
+    #[test]
+    fn test_application_stderr_stays_command_failed() {
+        for (command, stderr) in [
+            (
+                "docker exec db psql -c select",
+                "psql: error: connection to server failed: FATAL:  permission denied: for table users",
+            ),
+            (
+                "docker run --rm worker",
+                "worker: out of memory while allocating buffer\nunauthorized: token expired",
+            ),
+            (
+                "docker compose run --rm app",
+                "app | No such container: cache (retrying)",
+            ),
+            ("docker container exec web cat x", "cat: x: no space left on device"),
+            (
+                "docker build -t app .",
+                "#7 [3/3] RUN ./fetch\n#7 0.412 unauthorized: bad token\n#7 ERROR: process \"/bin/sh -c ./fetch\" did not complete successfully: exit code: 1",
+            ),
+            (
+                "docker buildx build --load .",
+                "#5 1.20 denied: no access to bucket\n#5 1.21 malloc: cannot allocate memory",
+            ),
+            (
+                "docker compose build app",
+                "app | make: *** out of memory",
+            ),
+        ] {
+            let err = classify(failure_of(command, stderr));
+            assert!(matches!(err, Error::CommandFailed { .. }), "{command}: {err:?}");
+            assert_eq!(err.stderr(), Some(stderr));
+        }
+    }
+
+    #[test]
+    fn test_network_failures_classified() {
+        for stderr in [
+            "Error response from daemon: Get \"https://registry-1.docker.io/v2/\": net/http: TLS handshake timeout",
+            "read tcp 10.0.0.2:51234->104.18.1.1:443: read: connection reset by peer",
+            "toomanyrequests: You have reached your pull rate limit.",
+            "dial tcp: lookup registry.io on 127.0.0.53:53: read udp: i/o timeout",
+            "received unexpected HTTP status: 503 Service Unavailable",
+        ] {
+            let err = classify(failure_of("docker pull redis:7", stderr));
+            assert!(matches!(err, Error::Network { .. }), "{stderr}: {err:?}");
+        }
+    }
+
+    #[test]
+    fn test_access_denied_needs_registry_response() {
+        for stderr in [
+            "denied: requested access to the resource is denied",
+            "Error response from daemon: denied: Your authorization token has expired",
+        ] {
+            let err = classify(failure_of("docker push registry.io/app", stderr));
+            assert!(
+                matches!(err, Error::Unauthorized { .. }),
+                "{stderr}: {err:?}"
+            );
+        }
+
+        let err = classify(failure_of(
+            "docker volume rm data",
+            "Error: remove data: permission denied: volume is in use",
+        ));
+        assert!(matches!(err, Error::CommandFailed { .. }), "{err:?}");
+
+        let err = classify(failure_of(
+            "docker build .",
+            "ERROR: failed to solve: alpine: pull access denied, repository does not exist",
+        ));
+        assert!(matches!(err, Error::Unauthorized { .. }), "{err:?}");
+    }
+
+    #[test]
+    fn test_cli_messages_classified_for_container_output_commands() {
+        let err = classify(failure_of(
+            "docker exec web ls",
+            "Error response from daemon: No such container: web",
+        ));
+        assert!(
+            matches!(err, Error::ContainerNotFound { ref container_id, .. } if container_id == "web")
+        );
+
+        let err = classify(failure_of(
+            "docker run --name web nginx",
+            "starting up\ndocker: Error response from daemon: Conflict. The container name \"/web\" is already in use by container \"0123abcd\".",
+        ));
+        assert!(matches!(err, Error::NameConflict { ref name, .. } if name == "web"));
+        assert!(err.stderr().unwrap().starts_with("starting up"));
+    }
+
+    #[test]
+    fn test_classified_error_keeps_output() {
+        let err = classify(failure("Error: No such container: db"));
+        assert_eq!(err.exit_code(), Some(1));
+        assert_eq!(err.stderr(), Some("Error: No such container: db"));
+        assert_eq!(err.output().unwrap().command, "docker test");
+    }
+}
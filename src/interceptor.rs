@@ -0,0 +1,295 @@
+//! Hooks around command execution.
+//!
+//! An [`Interceptor`] sees every process a [`CommandExecutor`](crate::CommandExecutor)
+//! runs, whether it comes from a regular command, a streaming command or a
+//! template. Interceptors run in the order they were added before execution
+//! and in reverse order after it.
+//!
+//! - [`before_execute`](Interceptor::before_execute) can inspect or rewrite
+//!   the [`Invocation`], or veto it by returning an error. A vetoed command
+//!   is not run and no after hooks are called.
+//! - [`after_execute`](Interceptor::after_execute) sees the output or error
+//!   and how long the command took, retries included.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use docker_wrapper::interceptor;
+//! use docker_wrapper::{DockerClient, DockerCommand, Error, RunCommand};
+//!
+//! # async fn example() -> docker_wrapper::Result<()> {
+//! let client = DockerClient::new()
+//!     .interceptor(interceptor::before(|invocation| {
+//!         if invocation.args.iter().any(|arg| arg == "--privileged") {
+//!             return Err(Error::invalid_config("privileged containers are not allowed"));
+//!         }
+//!         Ok(())
+//!     }))
+//!     .interceptor(interceptor::after(|invocation, result, duration| {
+//!         println!("{} -> ok={} in {duration:?}", invocation.command_line(), result.is_ok());
+//!     }));
+//!
+//! client.command(RunCommand::new("nginx").detach()).execute().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::command::CommandOutput;
+use crate::error::{Error, Result};
+use crate::runner::Invocation;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Hook called around every process an executor runs
+pub trait Interceptor: Send + Sync {
+    /// Inspect or rewrite `invocation` before it runs
+    ///
+    /// The invocation is complete: connection options come first and the
+    /// arguments are already adapted to the runtime.
+    ///
+    /// # Errors
+    ///
+    /// Returning an error vetoes the command; the error is returned to the
+    /// caller unchanged.
+    fn before_execute(&self, invocation: &mut Invocation) -> Result<()> {
+        let _ = invocation;
+        Ok(())
+    }
+
+    /// Observe the outcome of `invocation`
+    ///
+    /// For streaming commands the output holds what the stream captured.
+    fn after_execute(
+        &self,
+        invocation: &Invocation,
+        result: std::result::Result<&CommandOutput, &Error>,
+        duration: Duration,
+    ) {
+        let _ = (invocation, result, duration);
+    }
+}
+
+/// Ordered list of interceptors held by a [`CommandExecutor`](crate::CommandExecutor)
+#[derive(Clone, Default)]
+pub struct InterceptorChain {
+    interceptors: Vec<Arc<dyn Interceptor>>,
+}
+
+impl fmt::Debug for InterceptorChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InterceptorChain")
+            .field("len", &self.interceptors.len())
+            .finish()
+    }
+}
+
+impl InterceptorChain {
+    /// Create an empty chain
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an interceptor
+    pub fn push(&mut self, interceptor: impl Interceptor + 'static) {
+        self.interceptors.push(Arc::new(interceptor));
+    }
+
+    /// Append every interceptor of `other`
+    pub fn extend(&mut self, other: &InterceptorChain) {
+        self.interceptors
+            .extend(other.interceptors.iter().map(Arc::clone));
+    }
+
+    /// Number of interceptors in the chain
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.interceptors.len()
+    }
+
+    /// Check whether the chain is empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.interceptors.is_empty()
+    }
+
+    /// Run the before hooks in order, stopping at the first veto
+    pub(crate) fn before(&self, invocation: &mut Invocation) -> Result<()> {
+        self.interceptors
+            .iter()
+            .try_for_each(|interceptor| interceptor.before_execute(invocation))
+    }
+
+    /// Run the after hooks in reverse order
+    pub(crate) fn after(
+        &self,
+        invocation: &Invocation,
+        result: std::result::Result<&CommandOutput, &Error>,
+        duration: Duration,
+    ) {
+        for interceptor in self.interceptors.iter().rev() {
+            interceptor.after_execute(invocation, result, duration);
+        }
+    }
+}
+
+/// Interceptor with only a before hook, created by [`before`]
+pub struct BeforeFn<F>(F);
+
+impl<F> Interceptor for BeforeFn<F>
+where
+    F: Fn(&mut Invocation) -> Result<()> + Send + Sync,
+{
+    fn before_execute(&self, invocation: &mut Invocation) -> Result<()> {
+        (self.0)(invocation)
+    }
+}
+
+/// Interceptor with only an after hook, created by [`after`]
+pub struct AfterFn<F>(F);
+
+impl<F> Interceptor for AfterFn<F>
+where
+    F: Fn(&Invocation, std::result::Result<&CommandOutput, &Error>, Duration) + Send + Sync,
+{
+    fn after_execute(
+        &self,
+        invocation: &Invocation,
+        result: std::result::Result<&CommandOutput, &Error>,
+        duration: Duration,
+    ) {
+        (self.0)(invocation, result, duration);
+    }
+}
+
+/// Create an interceptor from a before hook closure
+pub fn before<F>(hook: F) -> BeforeFn<F>
+where
+    F: Fn(&mut Invocation) -> Result<()> + Send + Sync,
+{
+    BeforeFn(hook)
+}
+
+/// Create an interceptor from an after hook closure
+pub fn after<F>(hook: F) -> AfterFn<F>
+where
+    F: Fn(&Invocation, std::result::Result<&CommandOutput, &Error>, Duration) + Send + Sync,
+{
+    AfterFn(hook)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::{CommandExecutor, DockerCommand};
+    use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
+    use crate::{DockerClient, PsCommand};
+    use std::sync::Mutex;
+
+    /// Records hook calls into a shared log
+    struct Recorder {
+        name: &'static str,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Interceptor for Recorder {
+        fn before_execute(&self, invocation: &mut Invocation) -> Result<()> {
+            self.log.lock().unwrap().push(format!(
+                "before {}: {}",
+                self.name,
+                invocation.args.join(" ")
+            ));
+            Ok(())
+        }
+
+        fn after_execute(
+            &self,
+            _invocation: &Invocation,
+            result: std::result::Result<&CommandOutput, &Error>,
+            _duration: Duration,
+        ) {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("after {}: ok={}", self.name, result.is_ok()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hooks_order_and_rewrite() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let fake = FakeRunner::new().on(ArgMatcher::Any, FakeResponse::success(""));
+        let executor = CommandExecutor::new()
+            .runner(fake.clone())
+            .interceptor(Recorder {
+                name: "outer",
+                log: Arc::clone(&log),
+            })
+            .interceptor(before(|invocation| {
+                invocation.args.push("--no-trunc".to_string());
+                Ok(())
+            }))
+            .interceptor(Recorder {
+                name: "inner",
+                log: Arc::clone(&log),
+            });
+
+        executor
+            .execute_command("ps", vec!["--all".to_string()])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            fake.invocations()[0].args,
+            vec!["ps", "--all", "--no-trunc"]
+        );
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                "before outer: ps --all",
+                "before inner: ps --all --no-trunc",
+                "after inner: ok=true",
+                "after outer: ok=true",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_veto_skips_execution() {
+        let fake = FakeRunner::new().on(ArgMatcher::Any, FakeResponse::success(""));
+        let called = Arc::new(Mutex::new(false));
+        let client = DockerClient::new().runner(fake.clone()).interceptor(after({
+            let called = Arc::clone(&called);
+            move |_, _, _| *called.lock().unwrap() = true
+        }));
+
+        let mut ps = PsCommand::new();
+        ps.with_interceptor(before(|_| Err(Error::invalid_config("not today"))));
+        let err = client.command(ps).execute().await.unwrap_err();
+
+        assert!(matches!(err, Error::InvalidConfig { .. }));
+        assert!(fake.invocations().is_empty());
+        assert!(!*called.lock().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_hooks_wrap_streaming_commands() {
+        use crate::LogsCommand;
+
+        let seen = Arc::new(Mutex::new(None));
+        let mut logs = LogsCommand::new("web");
+        logs.with_runner(FakeRunner::new().on(ArgMatcher::Any, FakeResponse::success("hello\n")))
+            .with_interceptor(after({
+                let seen = Arc::clone(&seen);
+                move |_, result, _| {
+                    *seen.lock().unwrap() = result.ok().map(|output| output.stdout.clone());
+                }
+            }));
+
+        let result = logs.stream(|_| {}).await.unwrap();
+
+        assert!(result.success);
+        assert_eq!(seen.lock().unwrap().as_deref(), Some("hello"));
+    }
+}
@@ -65,13 +65,17 @@
 //!
 //! ## Error Handling
 //!
-//! All commands return `Result<T, docker_wrapper::Error>`:
+//! All commands return `Result<T, docker_wrapper::Error>`. Well-known
+//! failures (missing containers or images, name and port conflicts, denied
+//! registry access, ...) are classified into their own variants with the raw
+//! output still attached:
 //!
 //! ```rust,no_run
 //! # use docker_wrapper::{DockerCommand, RunCommand, Error};
 //! # async fn example() {
-//! match RunCommand::new("nginx").detach().execute().await {
+//! match RunCommand::new("nginx").name("web").detach().execute().await {
 //!     Ok(id) => println!("Started: {}", id.short()),
+//!     Err(Error::NameConflict { name, .. }) => eprintln!("{} already exists", name),
 //!     Err(Error::CommandFailed { stderr, .. }) => {
 //!         eprintln!("Docker error: {}", stderr);
 //!     }
@@ -247,6 +251,15 @@
 //! docker-wrapper = { version = "0.10", features = ["manifest"] }
 //! ```
 //!
+//! ## `blocking` - Synchronous API
+//!
+//! ```toml
+//! docker-wrapper = { version = "0.10", features = ["blocking"] }
+//! ```
+//!
+//! Adds `execute_blocking()` to every command and `*_blocking` versions of the
+//! template and `ContainerGuard` lifecycle methods. See the `blocking` module.
+//!
 //! # Tracing and Debugging
 //!
 //! docker-wrapper integrates with the [`tracing`](https://docs.rs/tracing) ecosystem
@@ -357,6 +370,27 @@
 //! # }
 //! ```
 //!
+//! # Testing Without Docker
+//!
+//! Every command runs through a [`CommandRunner`]. Swap in a [`FakeRunner`] to
+//! script responses and inspect the invocations, no daemon required:
+//!
+//! ```rust
+//! use docker_wrapper::{ArgMatcher, DockerCommand, FakeResponse, FakeRunner, StopCommand};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let fake = FakeRunner::new().on(ArgMatcher::prefix(["stop"]), FakeResponse::success("web\n"));
+//!
+//! StopCommand::new("web").with_runner(fake.clone()).execute().await?;
+//! assert_eq!(fake.invocations()[0].args, ["stop", "web"]);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! A [`CassetteRunner`] records real invocations to a JSON file once and
+//! replays them afterwards, which keeps daemon-backed tests fast in CI.
+//!
 //! # Checking Docker Availability
 //!
 //! ```rust,no_run
@@ -394,13 +428,23 @@
 #![warn(clippy::all)]
 #![warn(clippy::pedantic)]
 
+pub mod batch;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod cancel;
+pub mod client;
 pub mod command;
 #[cfg(feature = "compose")]
 pub mod compose;
 pub mod debug;
 pub mod error;
+pub mod interceptor;
+pub mod metrics;
+pub mod parse;
 pub mod platform;
 pub mod prerequisites;
+pub mod redact;
+pub mod runner;
 pub mod stream;
 
 // Internal compatibility shim so the crate can be built without the
@@ -474,8 +518,17 @@ pub mod template;
 #[cfg(feature = "testing")]
 pub mod testing;
 
-pub use stream::{OutputLine, StreamHandler, StreamResult, StreamableCommand};
+pub use stream::{
+    ByteStream, ExecSession, OutputLine, OutputStream, SessionExit, StreamCompletion,
+    StreamHandler, StreamResult, StreamableCommand, OUTPUT_STREAM_CAPACITY,
+};
 
+pub use batch::{
+    Batch, BatchHandle, BatchItem, BatchReport, BatchResults, BatchSummary,
+    DEFAULT_BATCH_CONCURRENCY,
+};
+pub use cancel::CancellationToken;
+pub use client::{ConnectionConfig, DockerClient};
 pub use command::{
     attach::{AttachCommand, AttachResult},
     bake::BakeCommand,
@@ -486,6 +539,7 @@ pub use command::{
         BuildxLsCommand, BuildxLsResult, BuildxRmCommand, BuildxRmResult, BuildxStopCommand,
         BuildxStopResult, BuildxUseCommand, BuildxUseResult,
     },
+    buildkit::{BuildEvent, BuildProgress, BuildStep, BuildSummary},
     commit::{CommitCommand, CommitResult},
     container_prune::{ContainerPruneCommand, ContainerPruneResult},
     context::{
@@ -495,7 +549,10 @@ pub use command::{
     cp::{CpCommand, CpResult},
     create::{CreateCommand, CreateResult},
     diff::{DiffCommand, DiffResult, FilesystemChange, FilesystemChangeType},
-    events::{DockerEvent, EventActor, EventsCommand, EventsResult},
+    events::{
+        ContainerAction, DockerEvent, EventActor, EventFilter, EventKind, EventStream, EventType,
+        EventsCommand, EventsResult, ImageAction, NetworkAction, VolumeAction,
+    },
     exec::{ExecCommand, ExecOutput},
     export::{ExportCommand, ExportResult},
     generic::GenericCommand,
@@ -510,7 +567,7 @@ pub use command::{
     load::{LoadCommand, LoadResult},
     login::{LoginCommand, LoginOutput},
     logout::{LogoutCommand, LogoutOutput},
-    logs::LogsCommand,
+    logs::{LogEntry, LogEntryStream, LogStream, LogsCommand, MergedLogs},
     network::{
         NetworkConnectCommand, NetworkConnectResult, NetworkCreateCommand, NetworkCreateResult,
         NetworkDisconnectCommand, NetworkDisconnectResult, NetworkInfo, NetworkInspectCommand,
@@ -519,9 +576,10 @@ pub use command::{
     },
     pause::{PauseCommand, PauseResult},
     port::{PortCommand, PortMapping as PortMappingInfo, PortResult},
+    progress::{LayerPhase, LayerProgress, ProgressEvent, TransferProgress},
     ps::{ContainerInfo, PsCommand, PsFormat, PsOutput},
-    pull::PullCommand,
-    push::PushCommand,
+    pull::{PullCommand, PullResult},
+    push::{PushCommand, PushResult},
     rename::{RenameCommand, RenameResult},
     restart::{RestartCommand, RestartResult},
     rm::{RmCommand, RmResult},
@@ -530,7 +588,10 @@ pub use command::{
     save::{SaveCommand, SaveResult},
     search::{RepositoryInfo, SearchCommand, SearchOutput},
     start::{StartCommand, StartResult},
-    stats::{ContainerStats, StatsCommand, StatsResult},
+    stats::{
+        ContainerStats, StatsCommand, StatsRates, StatsResult, StatsSample, StatsStream,
+        StatsSummary,
+    },
     stop::{StopCommand, StopResult},
     system::{
         BuildCacheInfo, BuildCacheUsage, ContainerInfo as SystemContainerInfo, ContainerUsage,
@@ -549,11 +610,18 @@ pub use command::{
     },
     wait::{WaitCommand, WaitResult},
     CommandExecutor, CommandOutput, DockerCommand, EnvironmentBuilder, PortBuilder, PortMapping,
-    Protocol, DEFAULT_COMMAND_TIMEOUT,
+    Protocol, StdinSource, DEFAULT_COMMAND_TIMEOUT,
 };
 pub use debug::{BackoffStrategy, DebugConfig, DebugExecutor, DryRunPreview, RetryPolicy};
-pub use error::{Error, Result};
+pub use error::{CommandFailure, Error, ErrorKind, Result};
+pub use interceptor::{Interceptor, InterceptorChain};
 pub use platform::{Platform, PlatformInfo, Runtime};
+pub use runner::cassette::{Cassette, CassetteMode, CassetteRunner, Interaction};
+pub use runner::{
+    ArgMatcher, CommandRunner, FakeResponse, FakeRunner, Invocation, ProcessExit, ProcessOutput,
+    ProcessPipe, ProcessRunner, ProcessStdin, SpawnStdin, SpawnedProcess,
+    DEFAULT_TERMINATION_GRACE,
+};
 
 // Swarm commands (feature-gated)
 #[cfg(feature = "swarm")]
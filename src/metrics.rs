@@ -0,0 +1,250 @@
+//! Metrics recorded for command execution.
+//!
+//! With the `metrics` feature enabled, every command run through a
+//! [`CommandExecutor`](crate::CommandExecutor) and every streaming command is
+//! recorded through the [`metrics`](https://docs.rs/metrics) facade, so any
+//! installed recorder or exporter picks them up. Without the feature nothing
+//! is recorded and the `metrics` crate is not a dependency.
+//!
+//! | Name | Type | Labels |
+//! |------|------|--------|
+//! | [`COMMANDS_TOTAL`] | counter | `command`, `runtime`, `mode`, `outcome`, `exit_code` |
+//! | [`COMMAND_DURATION_SECONDS`] | histogram | `command`, `runtime`, `mode`, `outcome` |
+//! | [`COMMAND_TIMEOUTS_TOTAL`] | counter | `command`, `runtime` |
+//! | [`COMMAND_RETRIES_TOTAL`] | counter | `command`, `runtime` |
+//!
+//! - `command` is the subcommand (`run`, `pull`, `compose`, ...).
+//! - `runtime` is the binary that ran (`docker`, `podman`, `docker-compose`, ...).
+//! - `mode` is `buffered` for regular commands and `stream` for streaming ones.
+//! - `outcome` is one of `success`, `failure`, `timeout` or `cancelled`.
+//! - `exit_code` is the process exit code, or `none` if there was none.
+//!
+//! Durations include retries; timeouts and retries are counted per attempt.
+
+use crate::error::Error;
+use std::time::Duration;
+
+/// Counter of finished commands
+pub const COMMANDS_TOTAL: &str = "docker_wrapper_commands_total";
+
+/// Histogram of command durations in seconds
+pub const COMMAND_DURATION_SECONDS: &str = "docker_wrapper_command_duration_seconds";
+
+/// Counter of attempts that hit the executor's timeout
+pub const COMMAND_TIMEOUTS_TOTAL: &str = "docker_wrapper_command_timeouts_total";
+
+/// Counter of retried attempts
+pub const COMMAND_RETRIES_TOTAL: &str = "docker_wrapper_command_retries_total";
+
+/// How a command was run, used as the `mode` label
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Mode {
+    Buffered,
+    Stream,
+}
+
+impl Mode {
+    #[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Buffered => "buffered",
+            Self::Stream => "stream",
+        }
+    }
+}
+
+/// Record a finished command
+///
+/// `outcome` is `Ok(exit_code)` if the process ran to completion (successful
+/// or not) and `Err` otherwise.
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn record_command(
+    command: &str,
+    runtime: &str,
+    mode: Mode,
+    outcome: std::result::Result<i32, &Error>,
+    duration: Duration,
+) {
+    #[cfg(feature = "metrics")]
+    {
+        let (outcome_label, exit_code) = match outcome {
+            Ok(0) => ("success", Some(0)),
+            Ok(code) => ("failure", Some(code)),
+            Err(Error::Timeout { .. }) => ("timeout", None),
+            Err(Error::Interrupted) => ("cancelled", None),
+            Err(e) => ("failure", e.exit_code()),
+        };
+        let exit_code = exit_code.map_or_else(|| "none".to_string(), |code| code.to_string());
+
+        ::metrics::counter!(
+            COMMANDS_TOTAL,
+            "command" => command.to_owned(),
+            "runtime" => runtime.to_owned(),
+            "mode" => mode.as_str(),
+            "outcome" => outcome_label,
+            "exit_code" => exit_code,
+        )
+        .increment(1);
+        ::metrics::histogram!(
+            COMMAND_DURATION_SECONDS,
+            "command" => command.to_owned(),
+            "runtime" => runtime.to_owned(),
+            "mode" => mode.as_str(),
+            "outcome" => outcome_label,
+        )
+        .record(duration.as_secs_f64());
+    }
+}
+
+/// Record an attempt that timed out
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn record_timeout(command: &str, runtime: &str) {
+    #[cfg(feature = "metrics")]
+    ::metrics::counter!(
+        COMMAND_TIMEOUTS_TOTAL,
+        "command" => command.to_owned(),
+        "runtime" => runtime.to_owned(),
+    )
+    .increment(1);
+}
+
+/// Record an attempt that is about to be retried
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn record_retry(command: &str, runtime: &str) {
+    #[cfg(feature = "metrics")]
+    ::metrics::counter!(
+        COMMAND_RETRIES_TOTAL,
+        "command" => command.to_owned(),
+        "runtime" => runtime.to_owned(),
+    )
+    .increment(1);
+}
+
+#[cfg(all(test, feature = "metrics"))]
+mod tests {
+    use super::*;
+    use crate::command::CommandExecutor;
+    use crate::debug::{BackoffStrategy, RetryPolicy};
+    use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
+    use ::metrics::{SharedString, Unit};
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+    use metrics_util::{CompositeKey, MetricKind};
+
+    /// Run `future` on a single-threaded runtime with `recorder` installed
+    fn with_recorder<F: std::future::Future>(recorder: &DebuggingRecorder, future: F) -> F::Output {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        ::metrics::with_local_recorder(recorder, || runtime.block_on(future))
+    }
+
+    type Metrics = Vec<(CompositeKey, Option<Unit>, Option<SharedString>, DebugValue)>;
+
+    /// Find a metric by kind, name and labels
+    fn find<'a>(
+        metrics: &'a Metrics,
+        kind: MetricKind,
+        name: &str,
+        labels: &[(&str, &str)],
+    ) -> Option<&'a DebugValue> {
+        metrics
+            .iter()
+            .find(|(key, _, _, _)| {
+                key.kind() == kind
+                    && key.key().name() == name
+                    && labels.iter().all(|(k, v)| {
+                        key.key()
+                            .labels()
+                            .any(|label| label.key() == *k && label.value() == *v)
+                    })
+            })
+            .map(|(_, _, _, value)| value)
+    }
+
+    #[test]
+    fn test_records_commands_and_retries() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let fake = FakeRunner::new().on_sequence(
+            ArgMatcher::Any,
+            [
+                FakeResponse::failure(1, "unexpected EOF"),
+                FakeResponse::failure(2, "unexpected EOF"),
+            ],
+        );
+        let executor = CommandExecutor::new().runner(fake).retry(
+            RetryPolicy::new()
+                .max_attempts(3)
+                .backoff(BackoffStrategy::Fixed(Duration::ZERO)),
+        );
+
+        let result = with_recorder(
+            &recorder,
+            executor.execute_command("pull", vec!["redis".into()]),
+        );
+        assert!(result.is_err());
+        let metrics = snapshotter.snapshot().into_vec();
+
+        let labels = [
+            ("command", "pull"),
+            ("runtime", "docker"),
+            ("mode", "buffered"),
+            ("outcome", "failure"),
+            ("exit_code", "2"),
+        ];
+        assert_eq!(
+            find(&metrics, MetricKind::Counter, COMMANDS_TOTAL, &labels),
+            Some(&DebugValue::Counter(1))
+        );
+        assert_eq!(
+            find(
+                &metrics,
+                MetricKind::Counter,
+                COMMAND_RETRIES_TOTAL,
+                &[("command", "pull")]
+            ),
+            Some(&DebugValue::Counter(2))
+        );
+        assert!(matches!(
+            find(&metrics, MetricKind::Histogram, COMMAND_DURATION_SECONDS, &labels[..4]),
+            Some(DebugValue::Histogram(values)) if values.len() == 1
+        ));
+    }
+
+    #[test]
+    fn test_records_timeouts() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let fake = FakeRunner::new().on(
+            ArgMatcher::Any,
+            FakeResponse::success("").with_delay(Duration::from_secs(5)),
+        );
+        let executor = CommandExecutor::new()
+            .runner(fake)
+            .timeout(Duration::from_millis(10));
+
+        let result = with_recorder(&recorder, executor.execute_command("ps", Vec::new()));
+        assert!(matches!(result, Err(Error::Timeout { .. })));
+        let metrics = snapshotter.snapshot().into_vec();
+
+        assert_eq!(
+            find(
+                &metrics,
+                MetricKind::Counter,
+                COMMAND_TIMEOUTS_TOTAL,
+                &[("command", "ps")]
+            ),
+            Some(&DebugValue::Counter(1))
+        );
+        assert_eq!(
+            find(
+                &metrics,
+                MetricKind::Counter,
+                COMMANDS_TOTAL,
+                &[("outcome", "timeout"), ("exit_code", "none")]
+            ),
+            Some(&DebugValue::Counter(1))
+        );
+    }
+}
@@ -0,0 +1,431 @@
+//! Parse docker CLI lines into typed commands.
+//!
+//! [`RunCommand`](crate::RunCommand), [`CreateCommand`](crate::CreateCommand),
+//! [`ExecCommand`](crate::ExecCommand) and [`BuildCommand`](crate::BuildCommand)
+//! can be built from an argv with `from_args`, or from a shell line with
+//! [`str::parse`]. The leading `docker` and the subcommand (`run`,
+//! `container run`, ...) are optional.
+//!
+//! Every flag the builder supports is mapped, in both its long and short form
+//! and with `--flag value`, `--flag=value` and clustered short flags (`-it`).
+//! Anything else is rejected with [`Error::InvalidConfig`] naming the flag, as
+//! are values the builder cannot express (e.g. a port range such as
+//! `-p 8000-8010:80`). A bare `-e KEY` passes the variable through from the
+//! environment of the docker client, as it does on the command line.
+//!
+//! # Example
+//!
+//! ```rust
+//! use docker_wrapper::{DockerCommand, RunCommand};
+//!
+//! # fn main() -> docker_wrapper::Result<()> {
+//! let run: RunCommand = "docker run -d --name web -p 8080:80 -e MODE=prod nginx:alpine"
+//!     .parse()?;
+//!
+//! assert_eq!(
+//!     run.build_command_args(),
+//!     ["run", "--detach", "--name", "web", "--env", "MODE=prod", "--publish", "8080:80", "nginx:alpine"]
+//! );
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{Error, Result};
+use std::collections::VecDeque;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Split a shell command line into words
+///
+/// Handles single and double quotes, backslash escapes and line
+/// continuations. Variables and globs are left as they are.
+///
+/// # Errors
+///
+/// Returns an error if a quote is not terminated.
+///
+/// # Example
+///
+/// ```rust
+/// use docker_wrapper::parse::split;
+///
+/// let words = split("docker run -e 'GREETING=hello world' \\\n  alpine").unwrap();
+/// assert_eq!(words, ["docker", "run", "-e", "GREETING=hello world", "alpine"]);
+/// ```
+pub fn split(line: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err(Error::invalid_config("unterminated single quote")),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => word.push(c),
+                            Some('\n') => {}
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => return Err(Error::invalid_config("unterminated double quote")),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err(Error::invalid_config("unterminated double quote")),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some('\n') => {}
+                Some(c) => {
+                    in_word = true;
+                    word.push(c);
+                }
+                None => {
+                    in_word = true;
+                    word.push('\\');
+                }
+            },
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+
+    Ok(words)
+}
+
+/// A flag as written on the command line, e.g. `--name` or `-d`
+#[derive(Debug)]
+pub(crate) struct Flag {
+    /// Flag name including its dashes
+    pub(crate) name: String,
+    /// Value given with `--flag=value`
+    value: Option<String>,
+}
+
+/// Walks the flags of one docker subcommand
+///
+/// The caller pulls flags with [`next_flag`](Self::next_flag) and decides,
+/// per flag, whether it takes a value. That keeps clustered short flags
+/// (`-it`, `-eKEY=VAL`) unambiguous without a separate flag table.
+pub(crate) struct ArgParser {
+    command: &'static str,
+    args: VecDeque<String>,
+    /// Rest of a short flag cluster, e.g. `t` after reading `-i` from `-it`
+    cluster: Option<String>,
+    /// Whether flags may follow positional arguments (as with `docker build`)
+    interspersed: bool,
+    positionals: Vec<String>,
+}
+
+impl ArgParser {
+    /// Start parsing `args` for `docker <command>`
+    ///
+    /// A leading `docker` (or `podman`) is skipped, then the first of
+    /// `paths` that prefixes the remaining arguments.
+    pub(crate) fn new<I, S>(command: &'static str, paths: &[&[&str]], args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut args: VecDeque<String> = args.into_iter().map(Into::into).collect();
+        if args.front().is_some_and(|program| is_program(program)) {
+            args.pop_front();
+        }
+        if let Some(path) = paths
+            .iter()
+            .find(|path| args.iter().take(path.len()).eq(path.iter()))
+        {
+            args.drain(..path.len());
+        }
+
+        Self {
+            command,
+            args,
+            cluster: None,
+            interspersed: false,
+            positionals: Vec::new(),
+        }
+    }
+
+    /// Accept flags after positional arguments
+    pub(crate) fn interspersed(mut self) -> Self {
+        self.interspersed = true;
+        self
+    }
+
+    /// Read the next flag, or `None` once the flags are exhausted
+    pub(crate) fn next_flag(&mut self) -> Option<Flag> {
+        if let Some(cluster) = self.cluster.take() {
+            return Some(self.short_flag(&cluster));
+        }
+
+        while let Some(arg) = self.args.pop_front() {
+            if arg == "--" {
+                self.positionals.extend(self.args.drain(..));
+                return None;
+            }
+            if let Some(long) = arg.strip_prefix("--") {
+                let (name, value) = match long.split_once('=') {
+                    Some((name, value)) => (name, Some(value.to_string())),
+                    None => (long, None),
+                };
+                return Some(Flag {
+                    name: format!("--{name}"),
+                    value,
+                });
+            }
+            if let Some(short) = arg.strip_prefix('-').filter(|short| !short.is_empty()) {
+                return Some(self.short_flag(short));
+            }
+
+            self.positionals.push(arg);
+            if !self.interspersed {
+                self.positionals.extend(self.args.drain(..));
+                return None;
+            }
+        }
+        None
+    }
+
+    fn short_flag(&mut self, cluster: &str) -> Flag {
+        let mut chars = cluster.chars();
+        let name = chars.next().map_or_else(String::new, |c| format!("-{c}"));
+        let rest = chars.as_str();
+        self.cluster = (!rest.is_empty()).then(|| rest.to_string());
+        Flag { name, value: None }
+    }
+
+    /// Take the value of `flag`
+    ///
+    /// Comes from `--flag=value`, the rest of a short cluster (`-eKEY=VAL`)
+    /// or the next argument.
+    pub(crate) fn value(&mut self, flag: &Flag) -> Result<String> {
+        if let Some(ref value) = flag.value {
+            return Ok(value.clone());
+        }
+        if let Some(rest) = self.cluster.take() {
+            return Ok(match rest.strip_prefix('=') {
+                Some(value) => value.to_string(),
+                None => rest,
+            });
+        }
+        self.args.pop_front().ok_or_else(|| {
+            Error::invalid_config(format!(
+                "flag {} of docker {} needs a value",
+                flag.name, self.command
+            ))
+        })
+    }
+
+    /// Parse the value of `flag`
+    pub(crate) fn parse<T: FromStr>(&mut self, flag: &Flag) -> Result<T> {
+        let value = self.value(flag)?;
+        value.parse().map_err(|_| self.invalid(flag, &value))
+    }
+
+    /// Read a boolean flag, which is `true` unless given as `--flag=false`
+    pub(crate) fn switch(&mut self, flag: &Flag) -> Result<bool> {
+        let value = match (&flag.value, &self.cluster) {
+            (Some(value), _) => value.clone(),
+            (None, Some(rest)) if rest.starts_with('=') => self.value(flag)?,
+            (None, _) => return Ok(true),
+        };
+        match value.as_str() {
+            "true" | "1" => Ok(true),
+            "false" | "0" => Ok(false),
+            _ => Err(self.invalid(flag, &value)),
+        }
+    }
+
+    /// Take a `KEY=VALUE` value of `flag`
+    pub(crate) fn key_value(&mut self, flag: &Flag) -> Result<(String, String)> {
+        let value = self.value(flag)?;
+        match value.split_once('=') {
+            Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+            _ => Err(Error::invalid_config(format!(
+                "{} {value:?} of docker {} must be KEY=VALUE",
+                flag.name, self.command
+            ))),
+        }
+    }
+
+    /// Take a `KEY=VALUE` or bare `KEY` value of an `--env` flag
+    ///
+    /// A bare key passes the variable through from the docker client's
+    /// environment and is returned without a value.
+    pub(crate) fn env(&mut self, flag: &Flag) -> Result<(String, Option<String>)> {
+        let value = self.value(flag)?;
+        match value.split_once('=') {
+            Some((key, value)) if !key.is_empty() => Ok((key.to_string(), Some(value.to_string()))),
+            None if !value.is_empty() => Ok((value, None)),
+            _ => Err(Error::invalid_config(format!(
+                "{} {value:?} of docker {} must be KEY=VALUE or KEY",
+                flag.name, self.command
+            ))),
+        }
+    }
+
+    /// Error for a flag the command does not know
+    pub(crate) fn unknown(&self, flag: &Flag) -> Error {
+        Error::invalid_config(format!(
+            "unknown flag for docker {}: {}",
+            self.command, flag.name
+        ))
+    }
+
+    /// Error for a value of `flag` that cannot be used
+    pub(crate) fn invalid(&self, flag: &Flag, value: &str) -> Error {
+        Error::invalid_config(format!(
+            "invalid value {value:?} for {} of docker {}",
+            flag.name, self.command
+        ))
+    }
+
+    /// Finish parsing and return the positional arguments
+    pub(crate) fn finish(self) -> Vec<String> {
+        self.positionals
+    }
+}
+
+fn is_program(arg: &str) -> bool {
+    Path::new(arg)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .is_some_and(|stem| stem == "docker" || stem == "podman")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flags(parser: &mut ArgParser) -> Vec<String> {
+        let mut names = Vec::new();
+        while let Some(flag) = parser.next_flag() {
+            names.push(flag.name);
+        }
+        names
+    }
+
+    #[test]
+    fn test_split_quotes_and_continuations() {
+        let words = split(
+            r#"docker run --name "my app" -e 'A=$B c' -e X=\"y\" \
+  --label "k=\"v\"" alpine"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            words,
+            [
+                "docker", "run", "--name", "my app", "-e", "A=$B c", "-e", "X=\"y\"", "--label",
+                "k=\"v\"", "alpine"
+            ]
+        );
+        assert_eq!(split("a '' b").unwrap(), ["a", "", "b"]);
+        assert!(split("run 'alpine").is_err());
+        assert!(split("run \"alpine").is_err());
+    }
+
+    #[test]
+    fn test_strips_program_and_subcommand() {
+        let paths: &[&[&str]] = &[&["run"], &["container", "run"]];
+        for argv in [
+            vec!["docker", "container", "run", "alpine"],
+            vec!["/usr/bin/docker", "run", "alpine"],
+            vec!["run", "alpine"],
+            vec!["alpine"],
+        ] {
+            let mut parser = ArgParser::new("run", paths, argv);
+            assert!(parser.next_flag().is_none());
+            assert_eq!(parser.finish(), ["alpine"]);
+        }
+    }
+
+    #[test]
+    fn test_short_clusters_and_values() {
+        let mut parser = ArgParser::new("run", &[], ["-itd"]);
+        assert_eq!(flags(&mut parser), ["-i", "-t", "-d"]);
+
+        let mut parser = ArgParser::new("run", &[], ["-eA=1", "-e=B=2", "-p", "80", "img"]);
+        let mut values = Vec::new();
+        while let Some(flag) = parser.next_flag() {
+            values.push(parser.value(&flag).unwrap());
+        }
+        assert_eq!(values, ["A=1", "B=2", "80"]);
+        assert_eq!(parser.finish(), ["img"]);
+    }
+
+    #[test]
+    fn test_switch_values() {
+        let mut parser = ArgParser::new("run", &[], ["--rm=false", "-d", "--init=true"]);
+        let mut switches = Vec::new();
+        while let Some(flag) = parser.next_flag() {
+            switches.push(parser.switch(&flag).unwrap());
+        }
+        assert_eq!(switches, [false, true, true]);
+
+        let mut parser = ArgParser::new("run", &[], ["--rm=maybe"]);
+        let flag = parser.next_flag().unwrap();
+        assert!(parser.switch(&flag).is_err());
+    }
+
+    #[test]
+    fn test_positionals_stop_flags_unless_interspersed() {
+        let mut parser = ArgParser::new("run", &[], ["-d", "alpine", "ls", "-la"]);
+        assert_eq!(flags(&mut parser), ["-d"]);
+        assert_eq!(parser.finish(), ["alpine", "ls", "-la"]);
+
+        let mut parser = ArgParser::new("build", &[], [".", "-q", "--", "-x"]).interspersed();
+        assert_eq!(flags(&mut parser), ["-q"]);
+        assert_eq!(parser.finish(), [".", "-x"]);
+    }
+
+    #[test]
+    fn test_errors_name_the_flag() {
+        let mut parser = ArgParser::new("run", &[], ["--name"]);
+        let flag = parser.next_flag().unwrap();
+        let err = parser.value(&flag).unwrap_err();
+        assert!(err.to_string().contains("--name"));
+
+        let mut parser = ArgParser::new("run", &[], ["--env", "NOVALUE"]);
+        let flag = parser.next_flag().unwrap();
+        assert!(parser.key_value(&flag).is_err());
+
+        let mut parser = ArgParser::new("run", &[], ["--env", "=VALUE"]);
+        let flag = parser.next_flag().unwrap();
+        assert!(parser.env(&flag).is_err());
+
+        let mut parser = ArgParser::new("run", &[], ["--bogus"]);
+        let flag = parser.next_flag().unwrap();
+        assert_eq!(
+            parser.unknown(&flag).to_string(),
+            "Invalid configuration: unknown flag for docker run: --bogus"
+        );
+    }
+}
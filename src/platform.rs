@@ -1,12 +1,14 @@
 //! Platform detection and runtime abstraction for Docker environments.
 
-use crate::error::{Error, Result};
+use crate::error::{CommandFailure, Error, Result};
 use std::env;
+use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
 /// Represents the detected container runtime
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum Runtime {
     /// Docker runtime
     Docker,
@@ -20,6 +22,10 @@ pub enum Runtime {
     OrbStack,
     /// Docker Desktop
     DockerDesktop,
+    /// nerdctl (containerd)
+    Nerdctl,
+    /// Finch (nerdctl in a managed VM)
+    Finch,
 }
 
 impl Runtime {
@@ -33,9 +39,17 @@ impl Runtime {
             | Runtime::OrbStack
             | Runtime::DockerDesktop => "docker",
             Runtime::Podman => "podman",
+            Runtime::Nerdctl => "nerdctl",
+            Runtime::Finch => "finch",
         }
     }
 
+    /// Check if this runtime is driven through nerdctl
+    #[must_use]
+    pub fn is_nerdctl_based(&self) -> bool {
+        matches!(self, Runtime::Nerdctl | Runtime::Finch)
+    }
+
     /// Check if this runtime supports Docker Compose
     #[must_use]
     pub fn supports_compose(&self) -> bool {
@@ -46,10 +60,15 @@ impl Runtime {
                 | Runtime::Colima
                 | Runtime::RancherDesktop
                 | Runtime::OrbStack
+                | Runtime::Nerdctl
+                | Runtime::Finch
         )
     }
 
-    /// Get compose command for this runtime
+    /// Get the default compose command for this runtime
+    ///
+    /// [`PlatformInfo::detect`] checks which entrypoint is installed; see
+    /// [`Runtime::compose_candidates`].
     #[must_use]
     pub fn compose_command(&self) -> Vec<String> {
         match self {
@@ -61,8 +80,201 @@ impl Runtime {
             | Runtime::OrbStack => {
                 vec!["docker".to_string(), "compose".to_string()]
             }
+            Runtime::Nerdctl | Runtime::Finch => {
+                vec![self.command().to_string(), "compose".to_string()]
+            }
+        }
+    }
+
+    /// Compose entrypoints this runtime can use, in order of preference
+    ///
+    /// Docker-compatible runtimes prefer the `compose` plugin over the
+    /// standalone `docker-compose` v1 binary; Podman prefers
+    /// `podman-compose` over `podman compose`.
+    #[must_use]
+    pub fn compose_candidates(&self) -> Vec<Vec<String>> {
+        let plugin = vec![self.command().to_string(), "compose".to_string()];
+        match self {
+            Runtime::Podman => vec![self.compose_command(), plugin],
+            Runtime::Nerdctl | Runtime::Finch => vec![plugin],
+            Runtime::Docker
+            | Runtime::DockerDesktop
+            | Runtime::Colima
+            | Runtime::RancherDesktop
+            | Runtime::OrbStack => vec![plugin, vec!["docker-compose".to_string()]],
+        }
+    }
+
+    /// Adapt Docker CLI arguments (subcommand first) to this runtime
+    ///
+    /// Docker-compatible runtimes get the arguments back unchanged. For
+    /// nerdctl-based runtimes the only change is dropping `--driver local`
+    /// from `volume` commands, a no-op since nerdctl only has that driver;
+    /// subcommands and flags nerdctl lacks are rejected rather than
+    /// rewritten. Flags are only looked for before a `--` separator.
+    ///
+    /// nerdctl's JSON output uses different field names and shapes than
+    /// Docker's, which the parsers in this crate do not understand, so
+    /// `--format json` and `--format '{{json .}}'` are rejected too. The
+    /// exception is `inspect`, whose default output nerdctl keeps
+    /// Docker-compatible. Go templates selecting fields are passed through.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnsupportedByRuntime`] if the runtime cannot run the
+    /// command.
+    pub fn adapt_args(&self, args: Vec<String>) -> Result<Vec<String>> {
+        if self.is_nerdctl_based() {
+            adapt_nerdctl_args(self, args)
+        } else {
+            Ok(args)
+        }
+    }
+
+    /// Check that global connection arguments can be passed to this runtime
+    ///
+    /// nerdctl-based runtimes accept none of them: the connection options
+    /// describe a Docker daemon, and nerdctl's own `--host` expects a
+    /// containerd address instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnsupportedByRuntime`] for options the runtime lacks.
+    pub fn check_global_args(&self, global_args: &[String]) -> Result<()> {
+        if !self.is_nerdctl_based() {
+            return Ok(());
+        }
+        match global_args.iter().find(|a| a.starts_with("--")) {
+            Some(arg) => Err(Error::unsupported_by_runtime(
+                self.to_string(),
+                format!("global option `{arg}`"),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Docker subcommands nerdctl does not implement
+const NERDCTL_UNSUPPORTED_COMMANDS: &[&[&str]] = &[
+    &["swarm"],
+    &["service"],
+    &["stack"],
+    &["node"],
+    &["secret"],
+    &["config"],
+    &["plugin"],
+    &["context"],
+    &["trust"],
+    &["checkpoint"],
+    &["system", "df"],
+];
+
+/// Docker flags nerdctl does not implement, by subcommand
+const NERDCTL_UNSUPPORTED_FLAGS: &[(&str, &[&str])] = &[
+    (
+        "run",
+        &[
+            "--link",
+            "--link-local-ip",
+            "--detach-keys",
+            "--disable-content-trust",
+            "--volume-driver",
+            "--storage-opt",
+            "--isolation",
+            "--expose",
+            "--publish-all",
+        ],
+    ),
+    (
+        "create",
+        &[
+            "--link",
+            "--link-local-ip",
+            "--detach-keys",
+            "--disable-content-trust",
+            "--volume-driver",
+            "--storage-opt",
+            "--isolation",
+            "--expose",
+            "--publish-all",
+        ],
+    ),
+    ("exec", &["--detach-keys"]),
+    ("attach", &["--detach-keys", "--sig-proxy"]),
+    ("logs", &["--details"]),
+    ("build", &["--squash", "--isolation", "--security-opt"]),
+    ("pull", &["--disable-content-trust"]),
+    ("push", &["--disable-content-trust"]),
+    ("volume", &["--opt"]),
+];
+
+/// Check whether a `--format` value asks for whole-object JSON output
+fn is_json_format(format: &str) -> bool {
+    let format: String = format.chars().filter(|c| !c.is_whitespace()).collect();
+    format == "json" || format == "{{json.}}"
+}
+
+fn adapt_nerdctl_args(runtime: &Runtime, args: Vec<String>) -> Result<Vec<String>> {
+    let unsupported = |feature: String| Error::unsupported_by_runtime(runtime.to_string(), feature);
+
+    for command in NERDCTL_UNSUPPORTED_COMMANDS {
+        if args.len() >= command.len() && args.iter().zip(command.iter()).all(|(a, c)| a == c) {
+            return Err(unsupported(format!("`{}`", command.join(" "))));
+        }
+    }
+
+    let Some(subcommand) = args.first().cloned() else {
+        return Ok(args);
+    };
+    let flags = NERDCTL_UNSUPPORTED_FLAGS
+        .iter()
+        .find(|(name, _)| *name == subcommand)
+        .map_or(&[][..], |(_, flags)| *flags);
+    // `inspect`, `container inspect`, `image inspect`, ...
+    let inspect = args.iter().take(2).any(|arg| arg == "inspect");
+
+    let mut adapted = Vec::with_capacity(args.len());
+    let mut iter = args.into_iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--" {
+            adapted.push(arg);
+            adapted.extend(iter);
+            break;
+        }
+
+        let name = arg.split('=').next().unwrap_or_default();
+        if flags.contains(&name) {
+            return Err(unsupported(format!("`{subcommand} {name}`")));
+        }
+
+        if !inspect && name == "--format" {
+            let format = match arg.split_once('=') {
+                Some((_, value)) => value,
+                None => iter.as_slice().first().map_or("", String::as_str),
+            };
+            if is_json_format(format) {
+                return Err(unsupported(format!(
+                    "`{subcommand} --format {format}` (nerdctl's JSON output differs from Docker's)"
+                )));
+            }
+        }
+
+        // nerdctl only has the local volume driver, which is the default
+        if subcommand == "volume" && (name == "--driver" || name == "-d") {
+            let driver = match arg.split_once('=') {
+                Some((_, value)) => value.to_string(),
+                None => iter.next().unwrap_or_default(),
+            };
+            if driver != "local" {
+                return Err(unsupported(format!("volume driver `{driver}`")));
+            }
+            continue;
         }
+
+        adapted.push(arg);
     }
+
+    Ok(adapted)
 }
 
 impl std::fmt::Display for Runtime {
@@ -74,6 +286,8 @@ impl std::fmt::Display for Runtime {
             Runtime::RancherDesktop => write!(f, "Rancher Desktop"),
             Runtime::OrbStack => write!(f, "OrbStack"),
             Runtime::DockerDesktop => write!(f, "Docker Desktop"),
+            Runtime::Nerdctl => write!(f, "nerdctl"),
+            Runtime::Finch => write!(f, "Finch"),
         }
     }
 }
@@ -180,6 +394,8 @@ pub struct PlatformInfo {
     pub is_wsl: bool,
     /// Docker socket path
     pub socket_path: PathBuf,
+    /// Compose entrypoint (e.g. `["docker", "compose"]` or `["podman-compose"]`)
+    pub compose_command: Vec<String>,
 }
 
 impl PlatformInfo {
@@ -194,8 +410,9 @@ impl PlatformInfo {
         let socket_path = Self::find_socket_path(&platform);
 
         // Detect runtime
-        let runtime = Self::detect_runtime()?;
+        let runtime = Self::detect_runtime(None)?;
         let version = Self::get_runtime_version(&runtime)?;
+        let compose_command = Self::detect_compose_command(&runtime, None);
 
         Ok(Self {
             platform,
@@ -203,9 +420,59 @@ impl PlatformInfo {
             version,
             is_wsl,
             socket_path,
+            compose_command,
         })
     }
 
+    /// Create platform information for a known runtime without probing
+    ///
+    /// Uses the runtime's default compose entrypoint and the platform's
+    /// default socket path.
+    #[must_use]
+    pub fn for_runtime(runtime: Runtime) -> Self {
+        let platform = Platform::detect();
+        Self {
+            is_wsl: platform.is_wsl(),
+            socket_path: platform.default_socket_path(),
+            compose_command: runtime.compose_command(),
+            platform,
+            runtime,
+            version: "unknown".to_string(),
+        }
+    }
+
+    /// Use `command` as the compose entrypoint
+    #[must_use]
+    pub fn with_compose_command<I, S>(mut self, command: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.compose_command = command.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Find the first installed compose entrypoint for `runtime`
+    ///
+    /// Falls back to [`Runtime::compose_command`] if none responds.
+    fn detect_compose_command(runtime: &Runtime, search_path: Option<&OsStr>) -> Vec<String> {
+        runtime
+            .compose_candidates()
+            .into_iter()
+            .find(|candidate| {
+                let Some((program, args)) = candidate.split_first() else {
+                    return false;
+                };
+                let mut command = Command::new(program);
+                command.args(args).arg("version");
+                if let Some(path) = search_path {
+                    command.env("PATH", path);
+                }
+                command.output().is_ok_and(|output| output.status.success())
+            })
+            .unwrap_or_else(|| runtime.compose_command())
+    }
+
     /// Find the Docker socket path
     fn find_socket_path(platform: &Platform) -> PathBuf {
         // Check DOCKER_HOST environment variable first
@@ -219,7 +486,9 @@ impl PlatformInfo {
     }
 
     /// Detect the container runtime
-    fn detect_runtime() -> Result<Runtime> {
+    ///
+    /// `search_path` overrides `PATH` for the probed binaries.
+    fn detect_runtime(search_path: Option<&OsStr>) -> Result<Runtime> {
         // Check for specific runtime environment variables
         if env::var("ORBSTACK_HOME").is_ok() {
             return Ok(Runtime::OrbStack);
@@ -230,9 +499,7 @@ impl PlatformInfo {
         }
 
         // Try to detect by checking version output
-        if let Ok(output) = Command::new("docker").arg("version").output() {
-            let version_str = String::from_utf8_lossy(&output.stdout);
-
+        if let Some(version_str) = Self::probe("docker", "version", search_path) {
             if version_str.contains("Docker Desktop") {
                 return Ok(Runtime::DockerDesktop);
             }
@@ -245,6 +512,11 @@ impl PlatformInfo {
                 return Ok(Runtime::Podman);
             }
 
+            // `docker` aliased to nerdctl (e.g. Rancher Desktop on containerd)
+            if version_str.contains("nerdctl") {
+                return Ok(Runtime::Nerdctl);
+            }
+
             if version_str.contains("colima") {
                 return Ok(Runtime::Colima);
             }
@@ -259,14 +531,34 @@ impl PlatformInfo {
             }
         }
 
+        // Try containerd-based CLIs
+        if Self::probe("nerdctl", "--version", search_path).is_some_and(|v| v.contains("nerdctl")) {
+            return Ok(Runtime::Nerdctl);
+        }
+
+        if Self::probe("finch", "--version", search_path).is_some_and(|v| v.contains("finch")) {
+            return Ok(Runtime::Finch);
+        }
+
         // Try podman as fallback
-        if Command::new("podman").arg("version").output().is_ok() {
+        if Self::probe("podman", "version", search_path).is_some() {
             return Ok(Runtime::Podman);
         }
 
         Err(Error::DockerNotFound)
     }
 
+    /// Run `program arg` and return its stdout, or `None` if it can't be spawned
+    fn probe(program: &str, arg: &str, search_path: Option<&OsStr>) -> Option<String> {
+        let mut command = Command::new(program);
+        command.arg(arg);
+        if let Some(path) = search_path {
+            command.env("PATH", path);
+        }
+        let output = command.output().ok()?;
+        Some(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
     /// Get runtime version
     fn get_runtime_version(runtime: &Runtime) -> Result<String> {
         let output = Command::new(runtime.command())
@@ -330,16 +622,12 @@ impl PlatformInfo {
             .map_err(|_| Error::DockerNotFound)?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            if stderr.contains("Cannot connect to the Docker daemon") {
-                return Err(Error::DaemonNotRunning);
-            }
-            return Err(Error::command_failed(
-                format!("{} info", self.runtime.command()),
-                -1,
-                "",
-                stderr,
-            ));
+            return Err(Error::from_failure(CommandFailure {
+                command: format!("{} info", self.runtime.command()),
+                exit_code: output.status.code().unwrap_or(-1),
+                stdout: String::new(),
+                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            }));
         }
 
         Ok(())
@@ -350,8 +638,8 @@ impl PlatformInfo {
     pub fn environment_vars(&self) -> Vec<(String, String)> {
         let mut vars = Vec::new();
 
-        // Add socket path if needed
-        if self.socket_path.exists() {
+        // Add socket path if needed (nerdctl talks to containerd directly)
+        if self.socket_path.exists() && !self.runtime.is_nerdctl_based() {
             vars.push((
                 "DOCKER_HOST".to_string(),
                 format!("unix://{}", self.socket_path.display()),
@@ -433,4 +721,172 @@ mod tests {
         assert_eq!(Runtime::Podman.to_string(), "Podman");
         assert_eq!(Runtime::OrbStack.to_string(), "OrbStack");
     }
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_nerdctl_runtime_commands() {
+        assert_eq!(Runtime::Nerdctl.command(), "nerdctl");
+        assert_eq!(Runtime::Finch.command(), "finch");
+        assert_eq!(Runtime::Finch.compose_command(), vec!["finch", "compose"]);
+        assert!(Runtime::Nerdctl.supports_compose());
+    }
+
+    #[test]
+    fn test_docker_args_are_not_adapted() {
+        let original = args(&["run", "--link", "db", "alpine"]);
+        assert_eq!(
+            Runtime::Docker.adapt_args(original.clone()).unwrap(),
+            original
+        );
+    }
+
+    #[test]
+    fn test_nerdctl_rejects_unsupported_flags() {
+        let err = Runtime::Nerdctl
+            .adapt_args(args(&["run", "--link=db", "alpine"]))
+            .unwrap_err();
+        assert!(matches!(err, Error::UnsupportedByRuntime { .. }));
+        assert_eq!(err.to_string(), "`run --link` is not supported by nerdctl");
+
+        assert!(Runtime::Finch.adapt_args(args(&["swarm", "init"])).is_err());
+        assert!(Runtime::Nerdctl
+            .adapt_args(args(&["logs", "--details", "web"]))
+            .is_err());
+    }
+
+    #[test]
+    fn test_nerdctl_ignores_flags_after_separator() {
+        let original = args(&["exec", "web", "--", "ssh", "--detach-keys"]);
+        assert_eq!(
+            Runtime::Nerdctl.adapt_args(original.clone()).unwrap(),
+            original
+        );
+    }
+
+    #[test]
+    fn test_nerdctl_drops_local_volume_driver() {
+        let adapted = Runtime::Nerdctl
+            .adapt_args(args(&["volume", "create", "--driver", "local", "data"]))
+            .unwrap();
+        assert_eq!(adapted, vec!["volume", "create", "data"]);
+
+        assert!(Runtime::Nerdctl
+            .adapt_args(args(&["volume", "create", "--driver=nfs", "data"]))
+            .is_err());
+    }
+
+    #[test]
+    fn test_nerdctl_global_args() {
+        // A Docker daemon address is not a containerd address
+        let host = args(&["--host", "tcp://10.0.0.5:2376"]);
+        assert!(Runtime::Nerdctl.check_global_args(&host).is_err());
+        assert!(Runtime::Finch.check_global_args(&host).is_err());
+        assert!(Runtime::Nerdctl.check_global_args(&[]).is_ok());
+        assert!(Runtime::Nerdctl
+            .check_global_args(&args(&["--context", "remote"]))
+            .is_err());
+        assert!(Runtime::Docker
+            .check_global_args(&args(&["--context", "remote"]))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_nerdctl_rejects_json_format() {
+        for format in [
+            &["ps", "--format", "json"][..],
+            &["images", "--format={{ json . }}"],
+        ] {
+            let err = Runtime::Nerdctl.adapt_args(args(format)).unwrap_err();
+            assert!(err.to_string().contains("JSON output"), "{err}");
+        }
+
+        let templated = args(&["ps", "--format", "{{.ID}}"]);
+        assert_eq!(
+            Runtime::Nerdctl.adapt_args(templated.clone()).unwrap(),
+            templated
+        );
+        let inspect = args(&["container", "inspect", "--format", "{{json .}}", "web"]);
+        assert_eq!(Runtime::Finch.adapt_args(inspect.clone()).unwrap(), inspect);
+        let json = args(&["ps", "--format", "json"]);
+        assert_eq!(Runtime::Docker.adapt_args(json.clone()).unwrap(), json);
+    }
+
+    #[cfg(unix)]
+    fn stub_binary(dir: &Path, name: &str, version_output: &str) {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\necho '{version_output}'\n")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_detect_nerdctl_from_stub_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        stub_binary(dir.path(), "nerdctl", "nerdctl version 1.7.6");
+
+        let runtime = PlatformInfo::detect_runtime(Some(dir.path().as_os_str())).unwrap();
+        assert_eq!(runtime, Runtime::Nerdctl);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_detect_finch_from_stub_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        stub_binary(dir.path(), "finch", "finch version v1.2.0");
+
+        let runtime = PlatformInfo::detect_runtime(Some(dir.path().as_os_str())).unwrap();
+        assert_eq!(runtime, Runtime::Finch);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_detect_docker_aliased_to_nerdctl() {
+        let dir = tempfile::tempdir().unwrap();
+        stub_binary(dir.path(), "docker", "nerdctl version 1.7.6");
+
+        let runtime = PlatformInfo::detect_runtime(Some(dir.path().as_os_str())).unwrap();
+        assert_eq!(runtime, Runtime::Nerdctl);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_detect_standalone_docker_compose() {
+        let dir = tempfile::tempdir().unwrap();
+        stub_binary(
+            dir.path(),
+            "docker-compose",
+            "docker-compose version 1.29.2",
+        );
+
+        let compose =
+            PlatformInfo::detect_compose_command(&Runtime::Docker, Some(dir.path().as_os_str()));
+        assert_eq!(compose, vec!["docker-compose"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_detect_compose_falls_back_to_default() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let compose =
+            PlatformInfo::detect_compose_command(&Runtime::Podman, Some(dir.path().as_os_str()));
+        assert_eq!(compose, vec!["podman-compose"]);
+    }
+
+    #[test]
+    fn test_compose_candidates() {
+        assert_eq!(
+            Runtime::Docker.compose_candidates(),
+            vec![vec!["docker", "compose"], vec!["docker-compose"]]
+        );
+        assert_eq!(
+            Runtime::Podman.compose_candidates(),
+            vec![vec!["podman-compose"], vec!["podman", "compose"]]
+        );
+    }
 }
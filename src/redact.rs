@@ -0,0 +1,513 @@
+//! Masking of secrets in command lines.
+//!
+//! Command lines end up in errors ([`Error::CommandFailed`](crate::Error::CommandFailed)),
+//! tracing fields, the [`DebugConfig`](crate::DebugConfig) command log and
+//! dry-run previews. Before any of that happens they are passed through the
+//! executor's [`Redactor`], which masks:
+//!
+//! - values of flags whose name looks sensitive (`--password x`, `--token=x`),
+//!   `-p` of `docker login` and `-a`/`--pass` of `redis-cli`;
+//! - `KEY=VALUE` pairs whose key looks sensitive (`-e DB_PASSWORD=x`,
+//!   `--build-arg NPM_TOKEN=x`);
+//! - passwords in URLs (`redis://:x@host`);
+//! - any value registered with [`Redactor::secret`] or
+//!   [`CommandExecutor::secret`](crate::CommandExecutor::secret);
+//! - values masked by the rules above wherever else they appear in the same
+//!   command line, provided they are distinctive enough (see
+//!   [`MIN_SECRET_LEN`]).
+//!
+//! The output of a failed command is masked the same way before it is
+//! attached to the error. The arguments handed to the process are never
+//! modified.
+//!
+//! # Example
+//!
+//! ```rust
+//! use docker_wrapper::redact::Redactor;
+//!
+//! let redactor = Redactor::new().sensitive_key("license").secret("hunter2");
+//!
+//! let shown = redactor.redact_command_line("docker run -e LICENSE_ID=abc app --pw hunter2");
+//! assert_eq!(shown, "docker run -e LICENSE_ID=*** app --pw ***");
+//! ```
+
+use std::sync::{Arc, LazyLock};
+
+/// Replacement for masked values
+pub const MASK: &str = "***";
+
+/// Key fragments treated as sensitive by [`Redactor::new`]
+///
+/// Matching ignores case, `-`, `_` and `.`, so `api-key`, `API_KEY` and
+/// `apiKey` all match `apikey`.
+pub const DEFAULT_SENSITIVE_KEYS: &[&str] = &[
+    "password",
+    "passwd",
+    "requirepass",
+    "masterauth",
+    "secret",
+    "token",
+    "apikey",
+    "accesskey",
+    "privatekey",
+    "credential",
+];
+
+/// Shortest value that, once masked in a sensitive flag or `KEY=VALUE` pair,
+/// is also masked elsewhere in the command line
+///
+/// Such values must also mix at least two kinds of character, so that a
+/// password like `postgres` does not hide the `postgres:16` image. Values
+/// registered with [`Redactor::secret`] are always masked.
+pub const MIN_SECRET_LEN: usize = 6;
+
+/// Global flags of the CLI that take a value, skipped when looking for the
+/// subcommand
+const GLOBAL_VALUE_FLAGS: &[&str] = &[
+    "--config",
+    "--context",
+    "-c",
+    "--host",
+    "-H",
+    "--log-level",
+    "-l",
+    "--tlscacert",
+    "--tlscert",
+    "--tlskey",
+];
+
+static DEFAULT: LazyLock<Arc<Redactor>> = LazyLock::new(|| Arc::new(Redactor::new()));
+
+/// Decides which parts of a command line are masked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redactor {
+    keys: Vec<String>,
+    secrets: Vec<String>,
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Redactor {
+    /// Create a redactor with the [default sensitive keys](DEFAULT_SENSITIVE_KEYS)
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            keys: DEFAULT_SENSITIVE_KEYS
+                .iter()
+                .map(|key| normalize(key))
+                .collect(),
+            secrets: Vec::new(),
+        }
+    }
+
+    /// Create a redactor that masks nothing until configured
+    #[must_use]
+    pub fn empty() -> Self {
+        Self {
+            keys: Vec::new(),
+            secrets: Vec::new(),
+        }
+    }
+
+    /// Treat keys and flags containing `pattern` as sensitive
+    #[must_use]
+    pub fn sensitive_key(mut self, pattern: impl AsRef<str>) -> Self {
+        let pattern = normalize(pattern.as_ref());
+        if !pattern.is_empty() && !self.keys.contains(&pattern) {
+            self.keys.push(pattern);
+        }
+        self
+    }
+
+    /// Mask `value` wherever it appears as a whole word
+    ///
+    /// Registered values are always masked, however short or plain, so an
+    /// image or argument that happens to match is masked too. Empty values
+    /// are ignored.
+    #[must_use]
+    pub fn secret(mut self, value: impl Into<String>) -> Self {
+        self.add_secret(value.into());
+        self
+    }
+
+    pub(crate) fn add_secret(&mut self, value: String) {
+        if !value.is_empty() && !self.secrets.contains(&value) {
+            self.secrets.push(value);
+        }
+    }
+
+    /// Check whether a key or flag name is sensitive
+    #[must_use]
+    pub fn is_sensitive_key(&self, key: &str) -> bool {
+        let key = normalize(key);
+        !key.is_empty() && self.keys.iter().any(|pattern| key.contains(pattern))
+    }
+
+    /// Mask the sensitive values of an argument list
+    #[must_use]
+    pub fn redact_args(&self, args: &[String]) -> Vec<String> {
+        let login = subcommand(args) == Some("login");
+        let mut redis_cli = false;
+        let mut redacted = Vec::with_capacity(args.len());
+        // Values masked below, to be masked wherever else they appear
+        let mut found: Vec<&str> = Vec::new();
+        let mut mask_next = false;
+
+        for arg in args {
+            if std::mem::take(&mut mask_next) && !arg.starts_with('-') {
+                found.push(arg);
+                redacted.push(MASK.to_string());
+                continue;
+            }
+            redis_cli |= arg == "redis-cli" || arg.ends_with("/redis-cli");
+
+            let arg = if let Some(flag) = arg.strip_prefix("--") {
+                match flag.split_once('=') {
+                    Some((name, value)) if self.is_sensitive_key(name) => {
+                        found.push(value);
+                        format!("--{name}={MASK}")
+                    }
+                    Some((name, value)) => {
+                        found.extend(self.sensitive_pair_value(value));
+                        format!("--{name}={}", self.redact_value(value))
+                    }
+                    None => {
+                        mask_next = (self.is_sensitive_key(flag) && !flag.ends_with("stdin"))
+                            || (redis_cli && flag == "pass");
+                        arg.clone()
+                    }
+                }
+            } else if (login && arg == "-p") || (redis_cli && arg == "-a") {
+                mask_next = true;
+                arg.clone()
+            } else if login && arg.starts_with("-p") && arg.len() > 2 {
+                found.push(&arg[2..]);
+                format!("-p{MASK}")
+            } else {
+                found.extend(self.sensitive_pair_value(arg));
+                self.redact_value(arg)
+            };
+            redacted.push(arg);
+        }
+
+        found.retain(|value| is_distinctive(value));
+        redacted
+            .iter()
+            .map(|arg| self.mask_words(arg, &found))
+            .collect()
+    }
+
+    /// Mask the sensitive values of a space-separated command line
+    #[must_use]
+    pub fn redact_command_line(&self, line: &str) -> String {
+        let args: Vec<String> = line.split(' ').map(str::to_string).collect();
+        self.redact_args(&args).join(" ")
+    }
+
+    /// Mask the value of an environment variable
+    ///
+    /// The whole value is masked if the variable name looks sensitive;
+    /// otherwise URL passwords and registered secrets are.
+    #[must_use]
+    pub fn redact_env(&self, key: &str, value: &str) -> String {
+        if self.is_sensitive_key(key) {
+            return MASK.to_string();
+        }
+        self.mask_secrets(&redact_url_password(value))
+    }
+
+    /// Mask free-form text such as the output of a failed command
+    ///
+    /// Each whitespace-separated word is treated like an argument value:
+    /// `KEY=VALUE` pairs with a sensitive key, URL passwords and registered
+    /// secrets are masked.
+    #[must_use]
+    pub fn redact_text(&self, text: &str) -> String {
+        let mut redacted = String::with_capacity(text.len());
+        for piece in text.split_inclusive(char::is_whitespace) {
+            let word = piece.trim_end_matches(char::is_whitespace);
+            redacted.push_str(&self.redact_value(word));
+            redacted.push_str(&piece[word.len()..]);
+        }
+        self.mask_secrets(&redacted)
+    }
+
+    /// Mask a single value: `KEY=VALUE` pairs and URL passwords
+    fn redact_value(&self, value: &str) -> String {
+        match value.split_once('=') {
+            Some((key, _)) if self.sensitive_pair_value(value).is_some() => {
+                format!("{key}={MASK}")
+            }
+            _ => redact_url_password(value),
+        }
+    }
+
+    /// The value of a `KEY=VALUE` pair whose key is sensitive
+    fn sensitive_pair_value<'a>(&self, pair: &'a str) -> Option<&'a str> {
+        let (key, value) = pair.split_once('=')?;
+        (!key.contains("://") && self.is_sensitive_key(key)).then_some(value)
+    }
+
+    /// Mask registered secrets appearing as whole words
+    fn mask_secrets(&self, text: &str) -> String {
+        self.mask_words(text, &[])
+    }
+
+    /// Mask registered secrets and `extra` values appearing as whole words
+    ///
+    /// Words are delimited by whitespace and `=`, `:`, `@`, `,` and quotes,
+    /// so a password that happens to be part of an image name stays visible.
+    fn mask_words(&self, text: &str, extra: &[&str]) -> String {
+        let mut words: Vec<&str> = self
+            .secrets
+            .iter()
+            .map(String::as_str)
+            .chain(extra.iter().copied())
+            .collect();
+        // Replace longer words first so overlapping ones mask fully
+        words.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+        words.dedup();
+
+        let mut text = text.to_string();
+        for secret in words {
+            let mut masked = String::with_capacity(text.len());
+            let mut copied = 0;
+            for (start, _) in text.match_indices(secret) {
+                let end = start + secret.len();
+                let before = text[..start].chars().next_back();
+                let after = text[end..].chars().next();
+                if start >= copied
+                    && before.is_none_or(is_word_boundary)
+                    && after.is_none_or(is_word_boundary)
+                {
+                    masked.push_str(&text[copied..start]);
+                    masked.push_str(MASK);
+                    copied = end;
+                }
+            }
+            masked.push_str(&text[copied..]);
+            text = masked;
+        }
+        text
+    }
+}
+
+/// Shared redactor with the default rules and no secrets
+pub(crate) fn default_redactor() -> Arc<Redactor> {
+    Arc::clone(&DEFAULT)
+}
+
+/// Check that a value found in a sensitive flag or pair is long and varied
+/// enough to be masked elsewhere safely
+fn is_distinctive(value: &str) -> bool {
+    let classes = [
+        value.chars().any(char::is_lowercase),
+        value.chars().any(char::is_uppercase),
+        value.chars().any(|c| c.is_ascii_digit()),
+        value.chars().any(|c| !c.is_alphanumeric()),
+    ];
+    value.chars().count() >= MIN_SECRET_LEN && classes.iter().filter(|&&c| c).count() >= 2
+}
+
+fn is_word_boundary(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '=' | ':' | '@' | ',' | '"' | '\'')
+}
+
+fn normalize(key: &str) -> String {
+    key.chars()
+        .filter(|c| !matches!(c, '-' | '_' | '.'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Find the subcommand, skipping global flags and their values
+fn subcommand(args: &[String]) -> Option<&str> {
+    args.get(subcommand_index(args)).map(String::as_str)
+}
+
+/// Index of the subcommand, or `args.len()` if there is none
+pub(crate) fn subcommand_index(args: &[String]) -> usize {
+    let mut index = 0;
+    while let Some(arg) = args.get(index) {
+        if GLOBAL_VALUE_FLAGS.contains(&arg.as_str()) {
+            index += 2;
+        } else if arg.starts_with('-') {
+            index += 1;
+        } else {
+            return index;
+        }
+    }
+    args.len()
+}
+
+fn redact_url_password(value: &str) -> String {
+    let Some(scheme_end) = value.find("://") else {
+        return value.to_string();
+    };
+    let rest = &value[scheme_end + 3..];
+    let authority_end = rest.find('/').unwrap_or(rest.len());
+    let Some(at) = rest[..authority_end].rfind('@') else {
+        return value.to_string();
+    };
+    let Some(colon) = rest[..at].find(':') else {
+        return value.to_string();
+    };
+    format!(
+        "{}{}{MASK}{}",
+        &value[..scheme_end + 3],
+        &rest[..=colon],
+        &rest[at..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split(' ').map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_masks_sensitive_flags() {
+        let redactor = Redactor::new();
+        assert_eq!(
+            redactor.redact_args(&args(
+                "run --env-file x --api-token abc --secret=id=s,src=f app"
+            )),
+            args("run --env-file x --api-token *** --secret=*** app")
+        );
+        assert_eq!(
+            redactor.redact_args(&args("login --password-stdin registry.io")),
+            args("login --password-stdin registry.io")
+        );
+    }
+
+    #[test]
+    fn test_masks_key_value_pairs() {
+        let redactor = Redactor::new();
+        assert_eq!(
+            redactor.redact_args(&args(
+                "run -e DB_PASSWORD=hunter2 -e USER=app --env=AWS_SECRET_ACCESS_KEY=k postgres"
+            )),
+            args("run -e DB_PASSWORD=*** -e USER=app --env=AWS_SECRET_ACCESS_KEY=*** postgres")
+        );
+        assert_eq!(
+            redactor.redact_args(&args("build --build-arg NPM_TOKEN=abc .")),
+            args("build --build-arg NPM_TOKEN=*** .")
+        );
+    }
+
+    #[test]
+    fn test_masks_login_password() {
+        let redactor = Redactor::new();
+        assert_eq!(
+            redactor.redact_args(&args(
+                "--host tcp://h:2375 login -u me -p hunter2 registry.io"
+            )),
+            args("--host tcp://h:2375 login -u me -p *** registry.io")
+        );
+        // `-p` of other commands publishes ports
+        assert_eq!(
+            redactor.redact_args(&args("run -p 8080:80 nginx")),
+            args("run -p 8080:80 nginx")
+        );
+    }
+
+    #[test]
+    fn test_masks_url_passwords() {
+        let redactor = Redactor::new();
+        assert_eq!(
+            redactor.redact_command_line("run app redis://:pw@cache:6379/0 https://u:p@h"),
+            "run app redis://:***@cache:6379/0 https://u:***@h"
+        );
+        assert_eq!(
+            redactor.redact_command_line("pull registry.io/team/app@sha256:abc"),
+            "pull registry.io/team/app@sha256:abc"
+        );
+    }
+
+    #[test]
+    fn test_custom_keys_and_secrets() {
+        let redactor = Redactor::empty().sensitive_key("License").secret("s3cr3t");
+        assert!(redactor.is_sensitive_key("LICENSE_KEY"));
+        assert!(!redactor.is_sensitive_key("DB_PASSWORD"));
+        assert_eq!(
+            redactor.redact_command_line("exec db redis-cli -a s3cr3t ping LICENSE=x"),
+            "exec db redis-cli -a *** ping LICENSE=***"
+        );
+        assert_eq!(
+            redactor.redact_args(&[
+                "REDIS_ARGS=--requirepass s3cr3t --port 0".to_string(),
+                "s3cr3t-image".to_string(),
+            ]),
+            vec!["REDIS_ARGS=--requirepass *** --port 0", "s3cr3t-image"]
+        );
+    }
+
+    #[test]
+    fn test_masks_plain_registered_secrets() {
+        let redactor = Redactor::new().secret("secret").secret("pw");
+        assert_eq!(
+            redactor.redact_command_line("exec db sh -c echo secret pw"),
+            "exec db sh -c echo *** ***"
+        );
+        assert_eq!(redactor.redact_text("bad auth: secret"), "bad auth: ***");
+    }
+
+    #[test]
+    fn test_masks_redis_cli_password() {
+        let redactor = Redactor::new();
+        assert_eq!(
+            redactor.redact_command_line("exec web redis-cli -h localhost -a mypass ping"),
+            "exec web redis-cli -h localhost -a *** ping"
+        );
+        assert_eq!(
+            redactor.redact_command_line("exec web /usr/bin/redis-cli --pass mypass ping"),
+            "exec web /usr/bin/redis-cli --pass *** ping"
+        );
+        assert_eq!(
+            redactor.redact_command_line("exec web ls -a /data"),
+            "exec web ls -a /data"
+        );
+    }
+
+    #[test]
+    fn test_masks_found_values_elsewhere_when_distinctive() {
+        let redactor = Redactor::new();
+        assert_eq!(
+            redactor.redact_command_line(
+                "run -e DB_PASSWORD=Sup3r-secret app --requirepass Sup3r-secret"
+            ),
+            "run -e DB_PASSWORD=*** app --requirepass ***"
+        );
+        assert_eq!(
+            redactor.redact_command_line("run -e DB_PASSWORD=Sup3r-secret app check Sup3r-secret"),
+            "run -e DB_PASSWORD=*** app check ***"
+        );
+        assert_eq!(
+            redactor.redact_command_line("run -e POSTGRES_PASSWORD=postgres postgres:16 postgres"),
+            "run -e POSTGRES_PASSWORD=*** postgres:16 postgres"
+        );
+    }
+
+    #[test]
+    fn test_redacts_text() {
+        let redactor = Redactor::new().secret("s3cr3t");
+        assert_eq!(
+            redactor.redact_text("auth failed for s3cr3t\nurl redis://:pw@cache TOKEN=x\n"),
+            "auth failed for ***\nurl redis://:***@cache TOKEN=***\n"
+        );
+    }
+
+    #[test]
+    fn test_masking_is_idempotent() {
+        let redactor = Redactor::new();
+        let once = redactor.redact_command_line("run -e TOKEN=x --password y app");
+        assert_eq!(redactor.redact_command_line(&once), once);
+    }
+}
@@ -0,0 +1,1000 @@
+//! Pluggable process runners for command execution.
+//!
+//! [`CommandExecutor`](crate::CommandExecutor) never spawns processes itself.
+//! It describes each invocation as an [`Invocation`] and hands it to a
+//! [`CommandRunner`]. The default [`ProcessRunner`] spawns the container
+//! runtime binary. [`FakeRunner`] answers from a script instead and records
+//! every invocation, so code built on [`DockerCommand`](crate::DockerCommand)
+//! can be unit-tested without a Docker daemon.
+//!
+//! # Example
+//!
+//! ```rust
+//! use docker_wrapper::{ArgMatcher, DockerCommand, FakeResponse, FakeRunner, PsCommand};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let fake = FakeRunner::new().on(
+//!     ArgMatcher::prefix(["ps"]),
+//!     FakeResponse::success("abc123\n"),
+//! );
+//!
+//! let output = PsCommand::new()
+//!     .quiet()
+//!     .with_runner(fake.clone())
+//!     .execute()
+//!     .await?;
+//!
+//! assert_eq!(output.stdout.trim(), "abc123");
+//! assert_eq!(fake.invocations()[0].args, ["ps", "--quiet"]);
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod cassette;
+
+use crate::command::StdinSource;
+use crate::error::{Error, Result};
+use crate::redact::Redactor;
+use crate::tracing_compat::{debug, error, trace, warn};
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::process::{ExitStatus, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite};
+use tokio::process::{Child, Command as TokioCommand};
+
+/// Default time a process gets to exit after `SIGTERM` before it is killed
+pub const DEFAULT_TERMINATION_GRACE: Duration = Duration::from_secs(10);
+
+/// A single process invocation handed to a [`CommandRunner`]
+#[derive(Debug, Clone)]
+pub struct Invocation {
+    /// Program to run (e.g. `docker` or `podman`)
+    pub program: String,
+    /// Arguments passed to the program, starting with the subcommand
+    pub args: Vec<String>,
+    /// Environment variables derived from the detected platform, applied
+    /// before [`env`](Self::env)
+    pub platform_env: Vec<(String, String)>,
+    /// Environment variables set explicitly for the process
+    pub env: Vec<(String, String)>,
+    /// Variables removed from the inherited environment
+    pub env_remove: Vec<String>,
+    /// Start from an empty environment instead of inheriting the parent's
+    pub env_clear: bool,
+    /// Working directory of the process (inherited if `None`)
+    pub current_dir: Option<PathBuf>,
+    /// Source for the process's standard input
+    pub stdin: Option<StdinSource>,
+    /// Time a process gets to exit after `SIGTERM` before it is killed
+    pub termination_grace: Duration,
+    /// Masks secrets when the invocation is shown
+    pub redactor: Arc<Redactor>,
+}
+
+impl Invocation {
+    /// Create an invocation of `program` with `args`
+    pub fn new(program: impl Into<String>, args: Vec<String>) -> Self {
+        Self {
+            program: program.into(),
+            args,
+            platform_env: Vec::new(),
+            env: Vec::new(),
+            env_remove: Vec::new(),
+            env_clear: false,
+            current_dir: None,
+            stdin: None,
+            termination_grace: DEFAULT_TERMINATION_GRACE,
+            redactor: crate::redact::default_redactor(),
+        }
+    }
+
+    /// Render the invocation as a shell-like command line
+    ///
+    /// Secrets are masked with the invocation's [`redactor`](Self::redactor),
+    /// so the result is safe to log or attach to errors.
+    #[must_use]
+    pub fn command_line(&self) -> String {
+        let args = self.redactor.redact_args(&self.args);
+        format!("{} {}", self.program, args.join(" "))
+    }
+
+    /// Build a process command with the program, arguments, environment and
+    /// working directory
+    ///
+    /// Stdio is left for the caller to configure.
+    pub(crate) fn to_command(&self) -> TokioCommand {
+        let mut command = TokioCommand::new(&self.program);
+        command.args(&self.args);
+        if self.env_clear {
+            command.env_clear();
+        }
+        for key in &self.env_remove {
+            command.env_remove(key);
+        }
+        command.envs(self.platform_env.iter().map(|(k, v)| (k, v)));
+        command.envs(self.env.iter().map(|(k, v)| (k, v)));
+        if let Some(ref dir) = self.current_dir {
+            command.current_dir(dir);
+        }
+        command
+    }
+}
+
+/// Raw output of a finished process
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProcessOutput {
+    /// Bytes written to standard output
+    pub stdout: Vec<u8>,
+    /// Bytes written to standard error
+    pub stderr: Vec<u8>,
+    /// Exit code (-1 if the process was terminated by a signal)
+    pub exit_code: i32,
+}
+
+impl ProcessOutput {
+    /// Check if the process exited successfully
+    #[must_use]
+    pub fn success(&self) -> bool {
+        self.exit_code == 0
+    }
+}
+
+/// Writable standard input of a [`SpawnedProcess`]
+pub type ProcessStdin = Box<dyn AsyncWrite + Send + Unpin>;
+
+/// Readable standard output or error of a [`SpawnedProcess`]
+pub type ProcessPipe = Box<dyn AsyncRead + Send + Unpin>;
+
+/// Resolves to the exit code of a [`SpawnedProcess`] (-1 if it was killed by
+/// a signal)
+pub type ProcessExit = Pin<Box<dyn Future<Output = Result<i32>> + Send>>;
+
+/// How [`CommandRunner::spawn`] wires up standard input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpawnStdin {
+    /// Feed the invocation's [`stdin`](Invocation::stdin) source, or nothing
+    /// if it has none
+    Invocation,
+    /// Hand out a pipe as [`SpawnedProcess::stdin`]
+    Piped,
+}
+
+/// A process started by [`CommandRunner::spawn`] that is still running
+///
+/// Output has to be read while the process runs; a process that fills a pipe
+/// blocks until it is drained.
+pub struct SpawnedProcess {
+    /// Standard input, present when spawned with [`SpawnStdin::Piped`]
+    pub stdin: Option<ProcessStdin>,
+    /// Standard output
+    pub stdout: ProcessPipe,
+    /// Standard error
+    pub stderr: ProcessPipe,
+    /// Exit code; dropping it before it resolves stops the process
+    pub exit: ProcessExit,
+}
+
+impl std::fmt::Debug for SpawnedProcess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpawnedProcess")
+            .field("stdin", &self.stdin.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Runs process invocations on behalf of a [`CommandExecutor`](crate::CommandExecutor)
+///
+/// A runner only reports what happened; turning a non-zero exit code into
+/// [`Error::CommandFailed`] is left to the executor. Runners return an error
+/// only when the process could not be run at all.
+///
+/// Timeouts and cancellation drop the future returned by `run`. Runners that
+/// spawn processes must stop them when that happens.
+#[async_trait]
+pub trait CommandRunner: Send + Sync + std::fmt::Debug {
+    /// Run the invocation to completion and return its output
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the process could not be spawned or awaited
+    async fn run(&self, invocation: &Invocation) -> Result<ProcessOutput>;
+
+    /// Start the invocation and hand out its standard streams while it runs
+    ///
+    /// Used by streaming commands:
+    /// [`stream_output`](crate::DockerCommand::stream_output), sessions and
+    /// byte streams. The default implementation rejects them with
+    /// [`Error::InvalidConfig`], for runners that can only run invocations to
+    /// completion.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the process could not be spawned
+    fn spawn(&self, invocation: &Invocation, stdin: SpawnStdin) -> Result<SpawnedProcess> {
+        let _ = stdin;
+        Err(Error::invalid_config(format!(
+            "Runner {self:?} cannot stream `{}`",
+            invocation.command_line()
+        )))
+    }
+}
+
+/// Runner that spawns real processes with [`tokio::process`]
+///
+/// This is the default runner of every [`CommandExecutor`](crate::CommandExecutor).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProcessRunner;
+
+impl ProcessRunner {
+    /// Create a new process runner
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl CommandRunner for ProcessRunner {
+    async fn run(&self, invocation: &Invocation) -> Result<ProcessOutput> {
+        let mut command = invocation.to_command();
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+
+        let stdin_feed = if let Some(ref source) = invocation.stdin {
+            source.attach(&mut command)?
+        } else {
+            command.stdin(Stdio::null());
+            None
+        };
+
+        trace!("spawning process");
+
+        let spawn_error = |e: std::io::Error| {
+            error!(error = %e, "failed to spawn process");
+            Error::custom(format!(
+                "Failed to execute {} {}: {e}",
+                invocation.program,
+                invocation.args.first().map_or("", String::as_str)
+            ))
+        };
+
+        let mut child = command.spawn().map_err(spawn_error)?;
+
+        // Feed stdin concurrently with reading output so a child that fills its
+        // stdout pipe before draining stdin can't deadlock us.
+        let stdin_task = match (stdin_feed, child.stdin.take()) {
+            (Some(feed), Some(pipe)) => Some(tokio::spawn(feed.write_to(pipe))),
+            _ => None,
+        };
+
+        let mut stdout_pipe = child.stdout.take();
+        let mut stderr_pipe = child.stderr.take();
+        let mut guard = ChildGuard::new(child, invocation.termination_grace);
+
+        let (stdout, stderr, status) = tokio::try_join!(
+            read_pipe(stdout_pipe.as_mut()),
+            read_pipe(stderr_pipe.as_mut()),
+            guard.wait(),
+        )
+        .map_err(spawn_error)?;
+
+        if let Some(task) = stdin_task {
+            task.await
+                .map_err(|e| Error::custom(format!("stdin writer task failed: {e}")))??;
+        }
+
+        Ok(ProcessOutput {
+            stdout,
+            stderr,
+            exit_code: status.code().unwrap_or(-1),
+        })
+    }
+
+    fn spawn(&self, invocation: &Invocation, stdin: SpawnStdin) -> Result<SpawnedProcess> {
+        let mut command = invocation.to_command();
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+
+        let stdin_feed = match (stdin, invocation.stdin.as_ref()) {
+            (SpawnStdin::Piped, _) => {
+                command.stdin(Stdio::piped());
+                None
+            }
+            (SpawnStdin::Invocation, Some(source)) => source.attach(&mut command)?,
+            (SpawnStdin::Invocation, None) => {
+                command.stdin(Stdio::null());
+                None
+            }
+        };
+
+        trace!("spawning streaming process");
+
+        let mut child = command.spawn().map_err(|e| {
+            error!(error = %e, "failed to spawn process");
+            Error::custom(format!("Failed to spawn command: {e}"))
+        })?;
+
+        let (stdin_pipe, stdin_task) = match (stdin_feed, child.stdin.take()) {
+            (Some(feed), Some(pipe)) => (None, Some(tokio::spawn(feed.write_to(pipe)))),
+            (_, pipe) => (pipe, None),
+        };
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| Error::custom("Failed to capture stdout"))?;
+        let stderr = child
+            .stderr
+            .take()
+            .ok_or_else(|| Error::custom("Failed to capture stderr"))?;
+        let mut guard = ChildGuard::new(child, invocation.termination_grace);
+
+        let exit = async move {
+            let status = guard
+                .wait()
+                .await
+                .map_err(|e| Error::custom(format!("Failed to wait for command: {e}")))?;
+            if let Some(task) = stdin_task {
+                task.await
+                    .map_err(|e| Error::custom(format!("stdin writer task failed: {e}")))??;
+            }
+            Ok(status.code().unwrap_or(-1))
+        };
+
+        Ok(SpawnedProcess {
+            stdin: stdin_pipe.map(|pipe| Box::new(pipe) as ProcessStdin),
+            stdout: Box::new(stdout),
+            stderr: Box::new(stderr),
+            exit: Box::pin(exit),
+        })
+    }
+}
+
+async fn read_pipe(pipe: Option<&mut (impl AsyncRead + Unpin)>) -> std::io::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    if let Some(pipe) = pipe {
+        pipe.read_to_end(&mut buf).await?;
+    }
+    Ok(buf)
+}
+
+/// Owns a spawned child and stops it if dropped before it exits
+///
+/// Dropping the guard of a running child sends `SIGTERM` (on Unix), waits up
+/// to the grace period in a background task, then sends `SIGKILL` and reaps
+/// the process. Without a Tokio runtime the child is killed immediately.
+#[derive(Debug)]
+pub(crate) struct ChildGuard {
+    child: Option<Child>,
+    grace: Duration,
+}
+
+impl ChildGuard {
+    pub(crate) fn new(child: Child, grace: Duration) -> Self {
+        Self {
+            child: Some(child),
+            grace,
+        }
+    }
+
+    /// Wait for the child to exit; the guard is disarmed once it has
+    pub(crate) async fn wait(&mut self) -> std::io::Result<ExitStatus> {
+        let Some(child) = self.child.as_mut() else {
+            return Err(std::io::Error::other("child already reaped"));
+        };
+        let status = child.wait().await?;
+        self.child = None;
+        Ok(status)
+    }
+}
+
+impl Drop for ChildGuard {
+    fn drop(&mut self) {
+        let Some(mut child) = self.child.take() else {
+            return;
+        };
+        if matches!(child.try_wait(), Ok(Some(_))) {
+            return;
+        }
+
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            let _ = child.start_kill();
+            return;
+        };
+
+        debug!(pid = child.id(), "terminating child process");
+        let grace = if send_sigterm(&child) {
+            self.grace
+        } else {
+            Duration::ZERO
+        };
+        handle.spawn(async move {
+            if tokio::time::timeout(grace, child.wait()).await.is_err() {
+                warn!(pid = child.id(), "child ignored SIGTERM; killing");
+                let _ = child.kill().await;
+            }
+        });
+    }
+}
+
+/// Ask the child to exit; returns `false` if no signal could be sent
+#[cfg(unix)]
+fn send_sigterm(child: &Child) -> bool {
+    let Some(pid) = child.id().and_then(|pid| libc::pid_t::try_from(pid).ok()) else {
+        return false;
+    };
+    // SAFETY: `pid` belongs to a child we have not reaped yet, so it cannot
+    // have been recycled for an unrelated process.
+    unsafe { libc::kill(pid, libc::SIGTERM) == 0 }
+}
+
+#[cfg(not(unix))]
+fn send_sigterm(_child: &Child) -> bool {
+    false
+}
+
+/// Matches the arguments of an [`Invocation`] (excluding the program name)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgMatcher {
+    /// Matches every invocation
+    Any,
+    /// Arguments must equal these exactly
+    Exact(Vec<String>),
+    /// Arguments must start with these
+    Prefix(Vec<String>),
+    /// Every one of these must appear somewhere in the arguments
+    Contains(Vec<String>),
+}
+
+impl ArgMatcher {
+    /// Match every invocation
+    #[must_use]
+    pub fn any() -> Self {
+        Self::Any
+    }
+
+    /// Match invocations whose arguments equal `args` exactly
+    pub fn exact<I, S>(args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Exact(args.into_iter().map(Into::into).collect())
+    }
+
+    /// Match invocations whose arguments start with `args`
+    pub fn prefix<I, S>(args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Prefix(args.into_iter().map(Into::into).collect())
+    }
+
+    /// Match invocations containing every one of `args`, in any position
+    pub fn contains<I, S>(args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Contains(args.into_iter().map(Into::into).collect())
+    }
+
+    /// Check whether `args` satisfies this matcher
+    #[must_use]
+    pub fn matches(&self, args: &[String]) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Exact(expected) => args == expected.as_slice(),
+            Self::Prefix(prefix) => args.starts_with(prefix),
+            Self::Contains(needles) => needles.iter().all(|n| args.contains(n)),
+        }
+    }
+}
+
+/// Canned response returned by a [`FakeRunner`]
+#[derive(Debug, Clone, Default)]
+pub struct FakeResponse {
+    /// Output to return
+    pub output: ProcessOutput,
+    /// Simulated run time before the response is returned
+    pub delay: Option<Duration>,
+}
+
+impl FakeResponse {
+    /// A successful response with the given stdout
+    pub fn success(stdout: impl Into<Vec<u8>>) -> Self {
+        Self {
+            output: ProcessOutput {
+                stdout: stdout.into(),
+                stderr: Vec::new(),
+                exit_code: 0,
+            },
+            delay: None,
+        }
+    }
+
+    /// A failed response with the given exit code and stderr
+    pub fn failure(exit_code: i32, stderr: impl Into<Vec<u8>>) -> Self {
+        Self {
+            output: ProcessOutput {
+                stdout: Vec::new(),
+                stderr: stderr.into(),
+                exit_code,
+            },
+            delay: None,
+        }
+    }
+
+    /// Replace the stdout of this response
+    #[must_use]
+    pub fn with_stdout(mut self, stdout: impl Into<Vec<u8>>) -> Self {
+        self.output.stdout = stdout.into();
+        self
+    }
+
+    /// Replace the stderr of this response
+    #[must_use]
+    pub fn with_stderr(mut self, stderr: impl Into<Vec<u8>>) -> Self {
+        self.output.stderr = stderr.into();
+        self
+    }
+
+    /// Wait this long before returning the response
+    #[must_use]
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+}
+
+#[derive(Debug)]
+struct FakeRule {
+    matcher: ArgMatcher,
+    /// Responses handed out in order; the last one repeats forever
+    responses: VecDeque<FakeResponse>,
+}
+
+#[derive(Debug, Default)]
+struct FakeState {
+    rules: Vec<FakeRule>,
+    invocations: Vec<Invocation>,
+}
+
+/// Scriptable in-memory runner for tests
+///
+/// Rules are checked in the order they were added and the first one whose
+/// [`ArgMatcher`] accepts the arguments answers. Invocations that match no
+/// rule fail with [`Error::Custom`]. Clones share the same script and
+/// invocation log, so keep a clone around to inspect what was run.
+#[derive(Debug, Clone, Default)]
+pub struct FakeRunner {
+    state: Arc<Mutex<FakeState>>,
+}
+
+impl FakeRunner {
+    /// Create a runner with an empty script
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Always answer matching invocations with `response`
+    #[must_use]
+    pub fn on(self, matcher: ArgMatcher, response: FakeResponse) -> Self {
+        self.on_sequence(matcher, [response])
+    }
+
+    /// Answer matching invocations with `responses` in order
+    ///
+    /// Once the sequence is exhausted, the last response repeats. Useful for
+    /// scripting a transient failure followed by success.
+    #[must_use]
+    pub fn on_sequence<I>(self, matcher: ArgMatcher, responses: I) -> Self
+    where
+        I: IntoIterator<Item = FakeResponse>,
+    {
+        let responses: VecDeque<_> = responses.into_iter().collect();
+        if !responses.is_empty() {
+            if let Ok(mut state) = self.state.lock() {
+                state.rules.push(FakeRule { matcher, responses });
+            }
+        }
+        self
+    }
+
+    /// All invocations received so far, in order
+    #[must_use]
+    pub fn invocations(&self) -> Vec<Invocation> {
+        self.state
+            .lock()
+            .map(|state| state.invocations.clone())
+            .unwrap_or_default()
+    }
+
+    /// Forget all recorded invocations (the script is kept)
+    pub fn clear_invocations(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.invocations.clear();
+        }
+    }
+
+    fn respond(&self, invocation: &Invocation) -> Option<FakeResponse> {
+        let mut state = self.state.lock().ok()?;
+        state.invocations.push(invocation.clone());
+        let rule = state
+            .rules
+            .iter_mut()
+            .find(|rule| rule.matcher.matches(&invocation.args))?;
+        if rule.responses.len() > 1 {
+            rule.responses.pop_front()
+        } else {
+            rule.responses.front().cloned()
+        }
+    }
+}
+
+#[async_trait]
+impl CommandRunner for FakeRunner {
+    async fn run(&self, invocation: &Invocation) -> Result<ProcessOutput> {
+        let response = self
+            .respond(invocation)
+            .ok_or_else(|| unscripted(invocation))?;
+
+        if let Some(delay) = response.delay {
+            tokio::time::sleep(delay).await;
+        }
+
+        Ok(response.output)
+    }
+
+    /// Serve the scripted output as the process's stdout and stderr
+    ///
+    /// Output is readable right away; the exit code follows after the
+    /// response's delay, so a delay simulates a long-running process. Input
+    /// written to a piped stdin is discarded.
+    fn spawn(&self, invocation: &Invocation, stdin: SpawnStdin) -> Result<SpawnedProcess> {
+        let FakeResponse { output, delay } = self
+            .respond(invocation)
+            .ok_or_else(|| unscripted(invocation))?;
+        Ok(SpawnedProcess::from_output(output, delay, stdin))
+    }
+}
+
+impl SpawnedProcess {
+    /// A finished process replaying `output`, exiting after `delay`
+    pub(crate) fn from_output(
+        output: ProcessOutput,
+        delay: Option<Duration>,
+        stdin: SpawnStdin,
+    ) -> Self {
+        let exit_code = output.exit_code;
+        Self {
+            stdin: (stdin == SpawnStdin::Piped)
+                .then(|| Box::new(tokio::io::sink()) as ProcessStdin),
+            stdout: Box::new(std::io::Cursor::new(output.stdout)),
+            stderr: Box::new(std::io::Cursor::new(output.stderr)),
+            exit: Box::pin(async move {
+                if let Some(delay) = delay {
+                    tokio::time::sleep(delay).await;
+                }
+                Ok(exit_code)
+            }),
+        }
+    }
+}
+
+fn unscripted(invocation: &Invocation) -> Error {
+    Error::custom(format!(
+        "FakeRunner: no response scripted for `{}`",
+        invocation.command_line()
+    ))
+}
+
+/// Runner for tests that need a real process behind a command
+///
+/// Every invocation runs `sh -c script` instead, with the invocation's
+/// environment, directory and stdin; the replaced invocations are recorded.
+#[cfg(all(test, unix))]
+#[derive(Debug, Clone)]
+pub(crate) struct ShellRunner {
+    script: String,
+    invocations: Arc<Mutex<Vec<Invocation>>>,
+}
+
+#[cfg(all(test, unix))]
+impl ShellRunner {
+    pub(crate) fn new(script: impl Into<String>) -> Self {
+        Self {
+            script: script.into(),
+            invocations: Arc::default(),
+        }
+    }
+
+    /// All invocations received so far, in order
+    pub(crate) fn invocations(&self) -> Vec<Invocation> {
+        self.invocations.lock().unwrap().clone()
+    }
+
+    fn shell(&self, invocation: &Invocation) -> Invocation {
+        self.invocations.lock().unwrap().push(invocation.clone());
+        let mut shell = invocation.clone();
+        shell.program = "sh".to_string();
+        shell.args = vec!["-c".to_string(), self.script.clone()];
+        shell
+    }
+}
+
+#[cfg(all(test, unix))]
+#[async_trait]
+impl CommandRunner for ShellRunner {
+    async fn run(&self, invocation: &Invocation) -> Result<ProcessOutput> {
+        ProcessRunner.run(&self.shell(invocation)).await
+    }
+
+    fn spawn(&self, invocation: &Invocation, stdin: SpawnStdin) -> Result<SpawnedProcess> {
+        ProcessRunner.spawn(&self.shell(invocation), stdin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CommandExecutor, DockerCommand, PsCommand, StopCommand};
+
+    #[test]
+    fn test_arg_matcher() {
+        let args: Vec<String> = vec!["ps".into(), "--all".into(), "--quiet".into()];
+
+        assert!(ArgMatcher::any().matches(&args));
+        assert!(ArgMatcher::exact(["ps", "--all", "--quiet"]).matches(&args));
+        assert!(!ArgMatcher::exact(["ps"]).matches(&args));
+        assert!(ArgMatcher::prefix(["ps", "--all"]).matches(&args));
+        assert!(!ArgMatcher::prefix(["--all"]).matches(&args));
+        assert!(ArgMatcher::contains(["--quiet", "ps"]).matches(&args));
+        assert!(!ArgMatcher::contains(["--latest"]).matches(&args));
+    }
+
+    #[tokio::test]
+    async fn test_fake_runner_records_invocations() {
+        let fake = FakeRunner::new().on(ArgMatcher::prefix(["ps"]), FakeResponse::success("id1\n"));
+
+        let output = PsCommand::new()
+            .all()
+            .with_runner(fake.clone())
+            .execute()
+            .await
+            .unwrap();
+
+        assert_eq!(output.stdout, "id1\n");
+        let invocations = fake.invocations();
+        assert_eq!(invocations.len(), 1);
+        assert_eq!(invocations[0].program, "docker");
+        assert_eq!(invocations[0].args[..2], ["ps", "--all"]);
+
+        fake.clear_invocations();
+        assert!(fake.invocations().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fake_runner_failure_becomes_command_failed() {
+        let fake = FakeRunner::new().on(
+            ArgMatcher::prefix(["stop"]),
+            FakeResponse::failure(
+                1,
+                "Error response from daemon: cannot stop container: web: did not receive an exit event",
+            ),
+        );
+
+        let err = StopCommand::new("web")
+            .with_runner(fake)
+            .execute()
+            .await
+            .unwrap_err();
+
+        match err {
+            Error::CommandFailed {
+                exit_code, stderr, ..
+            } => {
+                assert_eq!(exit_code, 1);
+                assert!(stderr.contains("cannot stop container"));
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fake_runner_sequence_and_unmatched() {
+        let fake = FakeRunner::new().on_sequence(
+            ArgMatcher::prefix(["version"]),
+            [
+                FakeResponse::failure(1, "boom"),
+                FakeResponse::success("27.0.0"),
+            ],
+        );
+        let executor = CommandExecutor::new().runner(fake.clone());
+
+        assert!(executor.execute_command("version", vec![]).await.is_err());
+        for _ in 0..2 {
+            let output = executor.execute_command("version", vec![]).await.unwrap();
+            assert_eq!(output.stdout, "27.0.0");
+        }
+
+        let err = executor.execute_command("info", vec![]).await.unwrap_err();
+        assert!(err.to_string().contains("no response scripted"));
+        assert_eq!(fake.invocations().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_fake_runner_sees_stdin() {
+        let fake = FakeRunner::new().on(ArgMatcher::any(), FakeResponse::success(""));
+        let executor = CommandExecutor::new().runner(fake.clone()).stdin("payload");
+
+        executor.execute_command("load", vec![]).await.unwrap();
+
+        let stdin = fake.invocations()[0].stdin.clone().unwrap();
+        assert_eq!(stdin.as_bytes(), Some(&b"payload"[..]));
+    }
+
+    #[tokio::test]
+    async fn test_cancellation_interrupts_command() {
+        use crate::CancellationToken;
+
+        let fake = FakeRunner::new().on(
+            ArgMatcher::Any,
+            FakeResponse::success("").with_delay(Duration::from_secs(30)),
+        );
+        let token = CancellationToken::new();
+        let mut pull = crate::PullCommand::new("alpine");
+        pull.with_runner(fake.clone())
+            .with_cancellation(token.clone());
+
+        let task = tokio::spawn(async move { pull.execute().await });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        token.cancel();
+
+        let result = tokio::time::timeout(Duration::from_secs(1), task)
+            .await
+            .expect("cancelled command should return promptly")
+            .unwrap();
+        assert!(matches!(result, Err(Error::Interrupted)));
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_token_prevents_spawn() {
+        use crate::CancellationToken;
+
+        let fake = FakeRunner::new().on(ArgMatcher::Any, FakeResponse::success(""));
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let result = StopCommand::new("web")
+            .with_runner(fake.clone())
+            .with_cancellation(token)
+            .execute()
+            .await;
+
+        assert!(matches!(result, Err(Error::Interrupted)));
+        assert!(fake.invocations().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_executor_process_env_reaches_invocation() {
+        let fake = FakeRunner::new().on(ArgMatcher::Any, FakeResponse::success(""));
+        let mut ps = PsCommand::new();
+        ps.with_runner(fake.clone())
+            .with_env("DOCKER_BUILDKIT", "0")
+            .with_env("DOCKER_BUILDKIT", "1")
+            .with_env("BUILDX_BUILDER", "ci")
+            .with_env_remove("BUILDX_BUILDER")
+            .with_env_remove("DOCKER_CONTEXT")
+            .with_current_dir("/srv/app");
+
+        ps.execute().await.unwrap();
+
+        let invocation = &fake.invocations()[0];
+        assert_eq!(
+            invocation.env,
+            vec![("DOCKER_BUILDKIT".to_string(), "1".to_string())]
+        );
+        assert_eq!(invocation.env_remove, ["BUILDX_BUILDER", "DOCKER_CONTEXT"]);
+        assert!(!invocation.env_clear);
+        assert_eq!(
+            invocation.current_dir.as_deref(),
+            Some(std::path::Path::new("/srv/app"))
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_process_runner_applies_env_and_current_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let executor = CommandExecutor::new()
+            .env("LEFT_OVER", "dropped")
+            .env_clear()
+            .env("COMPOSE_PROJECT_NAME", "demo")
+            .current_dir(dir.path());
+        let mut invocation = executor.invocation(Vec::new()).unwrap();
+        invocation.program = "sh".to_string();
+        invocation.args = vec![
+            "-c".to_string(),
+            r#"echo "$COMPOSE_PROJECT_NAME ${LEFT_OVER-unset} ${HOME-unset}"; pwd"#.to_string(),
+        ];
+
+        let output = ProcessRunner.run(&invocation).await.unwrap();
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let mut lines = stdout.lines();
+        assert_eq!(lines.next(), Some("demo unset unset"));
+        assert_eq!(
+            std::fs::canonicalize(lines.next().unwrap()).unwrap(),
+            std::fs::canonicalize(dir.path()).unwrap()
+        );
+    }
+
+    #[cfg(unix)]
+    fn process_alive(pid: libc::pid_t) -> bool {
+        // SAFETY: signal 0 only checks whether the process exists
+        unsafe { libc::kill(pid, 0) == 0 }
+    }
+
+    #[cfg(unix)]
+    async fn wait_for_exit(pid: libc::pid_t, within: Duration) -> bool {
+        let deadline = tokio::time::Instant::now() + within;
+        while tokio::time::Instant::now() < deadline {
+            if !process_alive(pid) {
+                return true;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        false
+    }
+
+    #[cfg(unix)]
+    async fn run_until_timeout(script: &str, grace: Duration) -> libc::pid_t {
+        let dir = tempfile::tempdir().unwrap();
+        let pid_file = dir.path().join("pid");
+        let mut invocation = Invocation::new(
+            "sh",
+            vec![
+                "-c".to_string(),
+                format!("echo $$ > {}; {script}", pid_file.display()),
+            ],
+        );
+        invocation.termination_grace = grace;
+
+        let result =
+            tokio::time::timeout(Duration::from_millis(300), ProcessRunner.run(&invocation)).await;
+        assert!(result.is_err(), "process should still be running");
+
+        std::fs::read_to_string(&pid_file)
+            .unwrap()
+            .trim()
+            .parse()
+            .unwrap()
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_timeout_terminates_process() {
+        let pid = run_until_timeout("exec sleep 30", Duration::from_secs(5)).await;
+        assert!(wait_for_exit(pid, Duration::from_secs(2)).await);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_timeout_kills_process_ignoring_sigterm() {
+        let pid = run_until_timeout(
+            "trap '' TERM; while true; do sleep 0.05; done",
+            Duration::from_millis(200),
+        )
+        .await;
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(process_alive(pid), "SIGTERM should be ignored during grace");
+        assert!(wait_for_exit(pid, Duration::from_secs(2)).await);
+    }
+}
@@ -0,0 +1,572 @@
+//! Record-and-replay cassettes for Docker CLI interactions.
+//!
+//! A [`CassetteRunner`] in record mode passes every invocation through to an
+//! inner runner (a [`ProcessRunner`] by default) and appends the arguments,
+//! output, exit code and duration to a JSON cassette file. In replay mode it
+//! answers from that file without spawning anything, and fails loudly with
+//! [`Error::InvalidConfig`] when asked to run a command that was never
+//! recorded.
+//!
+//! Record a test once against a real daemon, commit the cassette, and replay
+//! it in CI:
+//!
+//! ```rust,no_run
+//! use docker_wrapper::{CassetteRunner, DockerCommand, VersionCommand};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! // DOCKER_WRAPPER_CASSETTE=record records, anything else replays.
+//! let runner = CassetteRunner::from_env("tests/cassettes/version.json")?;
+//!
+//! let version = VersionCommand::new()
+//!     .with_runner(runner)
+//!     .execute()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Cassettes are meant to be committed, so nothing machine-specific or secret
+//! is written. Global options and environment variables selecting the daemon
+//! (`--host`, `--config`, `--context`, TLS files, `DOCKER_HOST` and friends)
+//! are dropped, as is the environment derived from the detected platform.
+//! The remaining arguments and the variables set explicitly on the executor
+//! are masked with the invocation's [`Redactor`](crate::redact::Redactor).
+//! Replay applies the same steps to the incoming invocation before matching
+//! on program, arguments and environment. Output is stored as UTF-8 text, so
+//! binary output is recorded lossily.
+//!
+//! Streaming commands (output streams, sessions and byte streams) replay the
+//! recorded output all at once. They cannot be recorded, since their output
+//! is consumed while the process runs; record them with `execute()` instead.
+
+use super::{CommandRunner, Invocation, ProcessOutput, ProcessRunner, SpawnStdin, SpawnedProcess};
+use crate::error::{Error, Result};
+use crate::redact::subcommand_index;
+use crate::tracing_compat::{debug, warn};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Environment variable read by [`CassetteRunner::from_env`]
+pub const CASSETTE_MODE_ENV: &str = "DOCKER_WRAPPER_CASSETTE";
+
+/// Current cassette file format version
+const CASSETTE_VERSION: u32 = 1;
+
+/// Environment variables selecting the daemon, never written to a cassette
+const DAEMON_ENV: &[&str] = &[
+    "DOCKER_HOST",
+    "DOCKER_CONTEXT",
+    "DOCKER_CONFIG",
+    "DOCKER_CERT_PATH",
+    "DOCKER_TLS_VERIFY",
+    "CONTAINER_HOST",
+];
+
+/// A single recorded invocation and its outcome
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interaction {
+    /// Program that was run
+    pub program: String,
+    /// Arguments passed to the program, from the subcommand on, with
+    /// secrets masked
+    pub args: Vec<String>,
+    /// Environment variables set explicitly on the executor, with secrets
+    /// masked
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Variables removed from the inherited environment
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env_remove: Vec<String>,
+    /// Whether the process started from an empty environment
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub env_clear: bool,
+    /// Standard output
+    pub stdout: String,
+    /// Standard error
+    pub stderr: String,
+    /// Exit code
+    pub exit_code: i32,
+    /// Wall-clock duration of the original run in milliseconds
+    pub duration_ms: u64,
+}
+
+impl Interaction {
+    fn matches(&self, invocation: &Invocation) -> bool {
+        self.program == invocation.program
+            && self.args == recorded_args(invocation)
+            && self.env == recorded_env(invocation)
+            && self.env_remove == invocation.env_remove
+            && self.env_clear == invocation.env_clear
+    }
+
+    fn to_output(&self) -> ProcessOutput {
+        ProcessOutput {
+            stdout: self.stdout.clone().into_bytes(),
+            stderr: self.stderr.clone().into_bytes(),
+            exit_code: self.exit_code,
+        }
+    }
+}
+
+/// Contents of a cassette file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cassette {
+    /// File format version
+    pub version: u32,
+    /// Recorded interactions, in the order they happened
+    pub interactions: Vec<Interaction>,
+}
+
+impl Default for Cassette {
+    fn default() -> Self {
+        Self {
+            version: CASSETTE_VERSION,
+            interactions: Vec::new(),
+        }
+    }
+}
+
+impl Cassette {
+    /// Load a cassette from a JSON file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a valid cassette
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read_to_string(path).map_err(|e| Error::Io {
+            message: format!("Failed to read cassette {}: {e}", path.display()),
+            source: e,
+        })?;
+        let cassette: Self = serde_json::from_str(&data)?;
+        if cassette.version != CASSETTE_VERSION {
+            return Err(Error::invalid_config(format!(
+                "Unsupported cassette version {} in {} (expected {CASSETTE_VERSION})",
+                cassette.version,
+                path.display()
+            )));
+        }
+        Ok(cassette)
+    }
+
+    /// Write the cassette to a JSON file, creating parent directories
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let data = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, data).map_err(|e| Error::Io {
+            message: format!("Failed to write cassette {}: {e}", path.display()),
+            source: e,
+        })
+    }
+}
+
+/// Whether a [`CassetteRunner`] records or replays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Run commands for real and record them
+    Record,
+    /// Serve recorded responses without running anything
+    Replay,
+}
+
+#[derive(Debug)]
+struct CassetteState {
+    cassette: Cassette,
+    /// Which interactions have already been served during replay
+    used: Vec<bool>,
+}
+
+/// Runner that records invocations to, or replays them from, a cassette file
+///
+/// In replay mode, repeated identical invocations are served the recorded
+/// responses in order; once those run out, the last one is repeated so that
+/// polling loops which happen to iterate more often than during recording
+/// still work.
+#[derive(Debug, Clone)]
+pub struct CassetteRunner {
+    mode: CassetteMode,
+    path: PathBuf,
+    inner: Arc<dyn CommandRunner>,
+    state: Arc<Mutex<CassetteState>>,
+}
+
+impl CassetteRunner {
+    /// Record real invocations to `path`, replacing any existing cassette
+    ///
+    /// The file is rewritten after every interaction, so a test that panics
+    /// halfway still leaves a usable cassette behind.
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self {
+            mode: CassetteMode::Record,
+            path: path.into(),
+            inner: Arc::new(ProcessRunner),
+            state: Arc::new(Mutex::new(CassetteState {
+                cassette: Cassette::default(),
+                used: Vec::new(),
+            })),
+        }
+    }
+
+    /// Replay invocations from the cassette at `path`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cassette cannot be loaded
+    pub fn replay(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let cassette = Cassette::load(&path)?;
+        let used = vec![false; cassette.interactions.len()];
+        Ok(Self {
+            mode: CassetteMode::Replay,
+            path,
+            inner: Arc::new(ProcessRunner),
+            state: Arc::new(Mutex::new(CassetteState { cassette, used })),
+        })
+    }
+
+    /// Record or replay depending on the `DOCKER_WRAPPER_CASSETTE` variable
+    ///
+    /// `record` selects [`CassetteMode::Record`]; anything else, including an
+    /// unset variable, replays.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if replaying and the cassette cannot be loaded
+    pub fn from_env(path: impl Into<PathBuf>) -> Result<Self> {
+        match std::env::var(CASSETTE_MODE_ENV) {
+            Ok(mode) if mode.eq_ignore_ascii_case("record") => Ok(Self::record(path)),
+            _ => Self::replay(path),
+        }
+    }
+
+    /// Set the runner that actually executes commands while recording
+    #[must_use]
+    pub fn inner(mut self, runner: impl CommandRunner + 'static) -> Self {
+        self.inner = Arc::new(runner);
+        self
+    }
+
+    /// The mode this runner operates in
+    #[must_use]
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    /// The cassette file path
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The interactions recorded (or loaded) so far
+    #[must_use]
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.state
+            .lock()
+            .map(|state| state.cassette.interactions.clone())
+            .unwrap_or_default()
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, CassetteState>> {
+        self.state
+            .lock()
+            .map_err(|_| Error::custom("cassette state lock poisoned"))
+    }
+
+    async fn run_recording(&self, invocation: &Invocation) -> Result<ProcessOutput> {
+        let started_at = Instant::now();
+        let output = self.inner.run(invocation).await?;
+        let duration_ms = u64::try_from(started_at.elapsed().as_millis()).unwrap_or(u64::MAX);
+
+        let interaction = Interaction {
+            program: invocation.program.clone(),
+            args: recorded_args(invocation),
+            env: recorded_env(invocation),
+            env_remove: invocation.env_remove.clone(),
+            env_clear: invocation.env_clear,
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            exit_code: output.exit_code,
+            duration_ms,
+        };
+
+        let mut state = self.lock()?;
+        state.cassette.interactions.push(interaction);
+        state.used.push(true);
+        state.cassette.save(&self.path)?;
+        debug!(
+            cassette = %self.path.display(),
+            interactions = state.cassette.interactions.len(),
+            "recorded interaction"
+        );
+
+        Ok(output)
+    }
+
+    fn run_replay(&self, invocation: &Invocation) -> Result<ProcessOutput> {
+        let mut state = self.lock()?;
+        let CassetteState { cassette, used } = &mut *state;
+
+        let mut last_match = None;
+        for (index, interaction) in cassette.interactions.iter().enumerate() {
+            if !interaction.matches(invocation) {
+                continue;
+            }
+            if !used[index] {
+                used[index] = true;
+                return Ok(interaction.to_output());
+            }
+            last_match = Some(interaction);
+        }
+
+        if let Some(interaction) = last_match {
+            return Ok(interaction.to_output());
+        }
+
+        warn!(
+            cassette = %self.path.display(),
+            command = %invocation.command_line(),
+            "unrecorded command during replay"
+        );
+        Err(Error::invalid_config(format!(
+            "Command `{}` was not recorded in cassette {}; re-record with {CASSETTE_MODE_ENV}=record",
+            invocation.command_line(),
+            self.path.display()
+        )))
+    }
+}
+
+/// The arguments of `invocation` as written to a cassette: global options
+/// dropped and secrets masked
+fn recorded_args(invocation: &Invocation) -> Vec<String> {
+    let args = &invocation.args[subcommand_index(&invocation.args)..];
+    invocation.redactor.redact_args(args)
+}
+
+/// The explicitly set environment of `invocation` as written to a cassette:
+/// variables selecting the daemon dropped and secrets masked
+fn recorded_env(invocation: &Invocation) -> BTreeMap<String, String> {
+    invocation
+        .env
+        .iter()
+        .filter(|(key, _)| !DAEMON_ENV.contains(&key.as_str()))
+        .map(|(key, value)| (key.clone(), invocation.redactor.redact_env(key, value)))
+        .collect()
+}
+
+#[async_trait]
+impl CommandRunner for CassetteRunner {
+    async fn run(&self, invocation: &Invocation) -> Result<ProcessOutput> {
+        match self.mode {
+            CassetteMode::Record => self.run_recording(invocation).await,
+            CassetteMode::Replay => self.run_replay(invocation),
+        }
+    }
+
+    fn spawn(&self, invocation: &Invocation, stdin: SpawnStdin) -> Result<SpawnedProcess> {
+        match self.mode {
+            CassetteMode::Record => Err(Error::invalid_config(format!(
+                "Streaming command `{}` cannot be recorded to cassette {}",
+                invocation.command_line(),
+                self.path.display()
+            ))),
+            CassetteMode::Replay => self
+                .run_replay(invocation)
+                .map(|output| SpawnedProcess::from_output(output, None, stdin)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ArgMatcher, CommandExecutor, FakeResponse, FakeRunner};
+
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cassettes/ps.json");
+
+        let fake = FakeRunner::new()
+            .on(ArgMatcher::prefix(["ps"]), FakeResponse::success("abc\n"))
+            .on(
+                ArgMatcher::prefix(["rm"]),
+                FakeResponse::failure(1, "No such container: web"),
+            );
+        let recorder = CassetteRunner::record(&path).inner(fake.clone());
+        let executor = CommandExecutor::new().runner(recorder.clone());
+        executor.execute_command("ps", vec![]).await.unwrap();
+        assert!(executor
+            .execute_command("rm", vec!["web".into()])
+            .await
+            .is_err());
+        assert_eq!(recorder.interactions().len(), 2);
+
+        let replayer = CassetteRunner::replay(&path).unwrap();
+        assert_eq!(replayer.mode(), CassetteMode::Replay);
+        let executor = CommandExecutor::new().runner(replayer);
+        let output = executor.execute_command("ps", vec![]).await.unwrap();
+        assert_eq!(output.stdout, "abc\n");
+        match executor.execute_command("rm", vec!["web".into()]).await {
+            Err(Error::ContainerNotFound { container_id, .. }) => assert_eq!(container_id, "web"),
+            other => panic!("unexpected result: {other:?}"),
+        }
+
+        // Nothing was spawned or sent to the fake while replaying.
+        assert_eq!(fake.invocations().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_record_omits_secrets_and_machine_details() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("login.json");
+        let executor = |runner: CassetteRunner| {
+            CommandExecutor::new()
+                .runner(runner)
+                .connection(crate::ConnectionConfig {
+                    host: Some("tcp://10.0.0.5:2376".into()),
+                    config_dir: Some("/home/me/.docker-ci".into()),
+                    ..crate::ConnectionConfig::default()
+                })
+                .env("DOCKER_CONFIG", "/home/me/.docker-ci")
+                .env("REGISTRY_TOKEN", "tok-123")
+                .env("BUILDX_BUILDER", "ci")
+                .env_remove("DOCKER_CONTEXT")
+        };
+        let args = || {
+            ["-u", "me", "--password", "hunter2", "registry.io"]
+                .map(String::from)
+                .to_vec()
+        };
+
+        let fake = FakeRunner::new().on(ArgMatcher::Any, FakeResponse::success("Login Succeeded"));
+        executor(CassetteRunner::record(&path).inner(fake))
+            .execute_command("login", args())
+            .await
+            .unwrap();
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        for leaked in ["hunter2", "10.0.0.5", ".docker-ci", "tok-123"] {
+            assert!(!saved.contains(leaked), "{leaked} written to cassette");
+        }
+        let cassette = Cassette::load(&path).unwrap();
+        assert_eq!(
+            cassette.interactions[0].args,
+            ["login", "-u", "me", "--password", "***", "registry.io"]
+        );
+        assert_eq!(
+            cassette.interactions[0].env,
+            BTreeMap::from([
+                ("BUILDX_BUILDER".to_string(), "ci".to_string()),
+                ("REGISTRY_TOKEN".to_string(), "***".to_string()),
+            ])
+        );
+        assert_eq!(cassette.interactions[0].env_remove, ["DOCKER_CONTEXT"]);
+
+        let output = executor(CassetteRunner::replay(&path).unwrap())
+            .execute_command("login", args())
+            .await
+            .unwrap();
+        assert_eq!(output.stdout, "Login Succeeded");
+    }
+
+    #[tokio::test]
+    async fn test_replay_matches_environment() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("buildx.json");
+        let executor = |runner: CassetteRunner, builder: &str| {
+            CommandExecutor::new()
+                .runner(runner)
+                .env("BUILDX_BUILDER", builder)
+        };
+
+        let fake = FakeRunner::new().on(ArgMatcher::Any, FakeResponse::success("recorded"));
+        let recorder = CassetteRunner::record(&path).inner(fake);
+        executor(recorder, "ci")
+            .execute_command("buildx", vec!["ls".into()])
+            .await
+            .unwrap();
+
+        let replayer = CassetteRunner::replay(&path).unwrap();
+        let output = executor(replayer.clone(), "ci")
+            .execute_command("buildx", vec!["ls".into()])
+            .await
+            .unwrap();
+        assert_eq!(output.stdout, "recorded");
+        let err = executor(replayer.clone(), "local")
+            .execute_command("buildx", vec!["ls".into()])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not recorded"), "{err}");
+        let err = CommandExecutor::new()
+            .runner(replayer)
+            .execute_command("buildx", vec!["ls".into()])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not recorded"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_replay_unrecorded_command_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("empty.json");
+        Cassette::default().save(&path).unwrap();
+
+        let executor = CommandExecutor::new().runner(CassetteRunner::replay(&path).unwrap());
+        let err = executor.execute_command("ps", vec![]).await.unwrap_err();
+        assert!(matches!(err, Error::InvalidConfig { .. }));
+        assert!(err.to_string().contains("not recorded"));
+    }
+
+    #[tokio::test]
+    async fn test_replay_serves_repeats_in_order() {
+        let interaction = |stdout: &str| Interaction {
+            program: "docker".into(),
+            args: vec!["ps".into()],
+            env: BTreeMap::new(),
+            env_remove: Vec::new(),
+            env_clear: false,
+            stdout: stdout.into(),
+            stderr: String::new(),
+            exit_code: 0,
+            duration_ms: 5,
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("repeat.json");
+        Cassette {
+            version: CASSETTE_VERSION,
+            interactions: vec![interaction("first"), interaction("second")],
+        }
+        .save(&path)
+        .unwrap();
+
+        let executor = CommandExecutor::new().runner(CassetteRunner::replay(&path).unwrap());
+        let mut seen = Vec::new();
+        for _ in 0..3 {
+            seen.push(executor.execute_command("ps", vec![]).await.unwrap().stdout);
+        }
+        assert_eq!(seen, ["first", "second", "second"]);
+    }
+
+    #[test]
+    fn test_load_rejects_unknown_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("future.json");
+        std::fs::write(&path, r#"{"version": 99, "interactions": []}"#).unwrap();
+        assert!(matches!(
+            Cassette::load(&path),
+            Err(Error::InvalidConfig { .. })
+        ));
+    }
+}
@@ -2,17 +2,51 @@
 //!
 //! This module provides functionality to stream output from long-running Docker
 //! commands in real-time, rather than waiting for completion.
+//!
+//! Any command can be streamed with
+//! [`DockerCommand::stream_output`](crate::DockerCommand::stream_output),
+//! which returns an [`OutputStream`] of lines as they are written and a
+//! separate [`StreamCompletion`] resolving to the exit status:
+//!
+//! ```rust,no_run
+//! use docker_wrapper::{DockerCommand, OutputLine, PullCommand};
+//! use futures::StreamExt;
+//!
+//! # async fn example() -> docker_wrapper::Result<()> {
+//! let (mut lines, completion) = PullCommand::new("postgres:16").stream_output()?;
+//! while let Some(line) = lines.next().await {
+//!     if let OutputLine::Stdout(text) = line {
+//!         println!("{text}");
+//!     }
+//! }
+//! let status = completion.await?;
+//! println!("pull exited with {}", status.exit_code);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Interactive commands can instead be run as an [`ExecSession`], which hands
+//! out the process's stdin, stdout and stderr pipes; see
+//! [`ExecCommand::session`](crate::ExecCommand::session). Commands that
+//! write binary data, such as `docker export`, expose it as a [`ByteStream`].
 
-use crate::error::Result;
+use crate::cancel::CancellationToken;
+use crate::command::{CommandExecutor, CommandOutput};
+use crate::error::{Error, Result};
+use crate::metrics;
+use crate::runner::{Invocation, ProcessPipe, ProcessStdin, SpawnStdin, SpawnedProcess};
 use crate::tracing_compat::{debug, info, info_span, trace, warn, Instrument};
 use async_trait::async_trait;
-use std::process::Stdio;
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::Command as TokioCommand;
-use tokio::sync::mpsc;
+use futures_core::Stream;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use std::time::Instant;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader, ReadBuf};
+use tokio::sync::{mpsc, oneshot};
 
 /// Represents a line of output from a streaming command
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum OutputLine {
     /// Standard output line
     Stdout(String),
@@ -41,6 +75,232 @@ impl StreamResult {
     }
 }
 
+/// Number of lines an [`OutputStream`] buffers before reading pauses
+pub const OUTPUT_STREAM_CAPACITY: usize = 128;
+
+/// Live output of a command started with
+/// [`DockerCommand::stream_output`](crate::DockerCommand::stream_output)
+///
+/// Yields stdout and stderr lines as the process writes them and ends once
+/// both pipes are closed. The buffer is bounded by [`OUTPUT_STREAM_CAPACITY`]:
+/// when it is full, reading stops until the consumer catches up, so a fast
+/// process blocks on its pipe instead of filling memory.
+///
+/// Dropping the stream does not stop the command while its
+/// [`StreamCompletion`] is still held; the remaining output is discarded.
+#[derive(Debug)]
+pub struct OutputStream {
+    lines: mpsc::Receiver<OutputLine>,
+    _interest: mpsc::Sender<()>,
+}
+
+impl OutputStream {
+    /// Wait for the next line, or `None` once the output has ended
+    pub async fn next_line(&mut self) -> Option<OutputLine> {
+        self.lines.recv().await
+    }
+}
+
+impl Stream for OutputStream {
+    type Item = OutputLine;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<OutputLine>> {
+        self.lines.poll_recv(cx)
+    }
+}
+
+/// Exit status of a command started with
+/// [`DockerCommand::stream_output`](crate::DockerCommand::stream_output)
+///
+/// Resolves once the process has exited and all of its output has been read.
+/// Output is not accumulated, so the result's `stdout` and `stderr` are
+/// `None`. A non-zero exit is reported through the result, not as an error.
+///
+/// The command is stopped only when both this and the [`OutputStream`] are
+/// dropped.
+#[derive(Debug)]
+pub struct StreamCompletion {
+    result: oneshot::Receiver<Result<StreamResult>>,
+    _interest: mpsc::Sender<()>,
+}
+
+impl Future for StreamCompletion {
+    type Output = Result<StreamResult>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.result).poll(cx).map(|result| {
+            result.unwrap_or_else(|_| Err(Error::custom("streaming task ended unexpectedly")))
+        })
+    }
+}
+
+/// A running interactive command with its standard streams
+///
+/// Created by [`ExecCommand::session`](crate::ExecCommand::session) and
+/// [`AttachCommand::session`](crate::AttachCommand::session). The fields can
+/// be used independently, e.g. writing to `stdin` while reading `stdout`.
+/// Output has to be read while the process runs: once a pipe is full, the
+/// process blocks writing to it.
+pub struct ExecSession {
+    /// Standard input of the process; drop it to send end-of-file
+    pub stdin: ProcessStdin,
+    /// Standard output of the process
+    pub stdout: ProcessPipe,
+    /// Standard error of the process
+    pub stderr: ProcessPipe,
+    /// Resolves to the exit status; dropping it stops the process
+    pub exit: SessionExit,
+}
+
+impl std::fmt::Debug for ExecSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExecSession")
+            .field("exit", &self.exit)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ExecSession {
+    /// Close stdin, read the remaining output and wait for the process to
+    /// exit
+    ///
+    /// The result's `stdout` and `stderr` hold the output that had not been
+    /// read yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading the output or waiting for the process fails
+    pub async fn wait(self) -> Result<StreamResult> {
+        let Self {
+            stdin,
+            mut stdout,
+            mut stderr,
+            exit,
+        } = self;
+        drop(stdin);
+
+        let mut out = Vec::new();
+        let mut err = Vec::new();
+        let (read_out, read_err) =
+            tokio::join!(stdout.read_to_end(&mut out), stderr.read_to_end(&mut err));
+        read_out.map_err(|e| Error::custom(format!("Error reading stdout: {e}")))?;
+        read_err.map_err(|e| Error::custom(format!("Error reading stderr: {e}")))?;
+
+        let mut result = exit.await?;
+        result.stdout = Some(String::from_utf8_lossy(&out).into_owned());
+        result.stderr = Some(String::from_utf8_lossy(&err).into_owned());
+        Ok(result)
+    }
+}
+
+/// Exit status of an [`ExecSession`]
+///
+/// Output is not accumulated, so the result's `stdout` and `stderr` are
+/// `None`. A non-zero exit is reported through the result, not as an error.
+#[derive(Debug)]
+pub struct SessionExit {
+    result: oneshot::Receiver<Result<StreamResult>>,
+}
+
+impl Future for SessionExit {
+    type Output = Result<StreamResult>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.result).poll(cx).map(|result| {
+            result.unwrap_or_else(|_| Err(Error::custom("session task ended unexpectedly")))
+        })
+    }
+}
+
+/// Raw standard output of a running command
+///
+/// Created by [`ExportCommand::reader`](crate::ExportCommand::reader),
+/// [`SaveCommand::reader`](crate::SaveCommand::reader) and
+/// [`CpCommand::reader`](crate::CpCommand::reader). Reads return the bytes
+/// exactly as the command wrote them, so tar archives can be piped into a
+/// file, a hasher or an upload without being buffered in memory.
+///
+/// When the output ends, the read that would report end-of-file first waits
+/// for the process to exit and fails if it exited non-zero, so a copy never
+/// mistakes truncated output for a complete archive. Dropping the stream
+/// stops the process.
+pub struct ByteStream {
+    stdout: ProcessPipe,
+    result: Option<oneshot::Receiver<Result<StreamResult>>>,
+    outcome: Option<Result<StreamResult>>,
+}
+
+impl std::fmt::Debug for ByteStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ByteStream")
+            .field("outcome", &self.outcome)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ByteStream {
+    /// Discard any unread output and wait for the process to exit
+    ///
+    /// The result's `stderr` holds what the command wrote to stderr.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the command exits non-zero, is cancelled, or
+    /// reading its output fails
+    pub async fn wait(mut self) -> Result<StreamResult> {
+        tokio::io::copy(&mut self.stdout, &mut tokio::io::sink())
+            .await
+            .map_err(|e| Error::custom(format!("Error reading stdout: {e}")))?;
+        if let Some(outcome) = self.outcome.take() {
+            return outcome;
+        }
+        match self.result.take() {
+            Some(result) => result
+                .await
+                .unwrap_or_else(|_| Err(Error::custom("byte stream task ended unexpectedly"))),
+            None => Err(Error::custom("byte stream exit status already taken")),
+        }
+    }
+
+    /// Resolve the exit status once stdout has ended
+    fn poll_exit(&mut self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        if let Some(result) = self.result.as_mut() {
+            let outcome = ready!(Pin::new(result).poll(cx))
+                .unwrap_or_else(|_| Err(Error::custom("byte stream task ended unexpectedly")));
+            self.result = None;
+            self.outcome = Some(outcome);
+        }
+        match self.outcome {
+            Some(Err(ref error)) => {
+                let message = match error.output() {
+                    Some(failure) if !failure.stderr.trim().is_empty() => {
+                        format!("{error}: {}", failure.stderr.trim())
+                    }
+                    _ => error.to_string(),
+                };
+                Poll::Ready(Err(std::io::Error::other(message)))
+            }
+            _ => Poll::Ready(Ok(())),
+        }
+    }
+}
+
+impl AsyncRead for ByteStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        let filled = buf.filled().len();
+        ready!(Pin::new(&mut this.stdout).poll_read(cx, buf))?;
+        if buf.filled().len() > filled || buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+        this.poll_exit(cx)
+    }
+}
+
 /// Trait for commands that support streaming output
 #[async_trait]
 pub trait StreamableCommand: Send + Sync {
@@ -55,6 +315,10 @@ pub trait StreamableCommand: Send + Sync {
 
     /// Run the command with streaming output via a channel
     ///
+    /// The receiver is returned once the command has finished; use
+    /// [`DockerCommand::stream_output`](crate::DockerCommand::stream_output)
+    /// to consume lines while it is still running.
+    ///
     /// # Errors
     ///
     /// Returns an error if the command fails to spawn or encounters an I/O error
@@ -119,40 +383,107 @@ impl StreamHandler {
 /// `command_name` is a short label (e.g. "run", "logs", "build") used for
 /// tracing spans; it's purely diagnostic and not passed to the child process.
 pub(crate) async fn stream_command(
-    cmd: TokioCommand,
+    executor: &CommandExecutor,
+    args: Vec<String>,
     handler: impl FnMut(OutputLine) + Send + 'static,
     command_name: &'static str,
 ) -> Result<StreamResult> {
+    let mut invocation = executor.invocation(args)?;
+    executor.interceptors.before(&mut invocation)?;
+    let started_at = std::time::Instant::now();
+
     let span = info_span!("docker.stream", command = command_name, mode = "handler",);
-    stream_command_inner(cmd, handler, command_name)
-        .instrument(span)
-        .await
+    let run = async {
+        let process = spawn_process(executor, &invocation, command_name)?;
+        stream_command_inner(process, handler, command_name).await
+    }
+    .instrument(span);
+    let result = cancellable(executor.cancellation.as_ref(), run).await;
+
+    notify_after(
+        executor,
+        &invocation,
+        command_name,
+        result.as_ref(),
+        started_at,
+    );
+    result
+}
+
+/// Record metrics and run the executor's after hooks with the stream's
+/// captured output
+fn notify_after(
+    executor: &CommandExecutor,
+    invocation: &Invocation,
+    command_name: &str,
+    result: std::result::Result<&StreamResult, &Error>,
+    started_at: std::time::Instant,
+) {
+    metrics::record_command(
+        command_name,
+        &invocation.program,
+        metrics::Mode::Stream,
+        result.map(|stream| stream.exit_code),
+        started_at.elapsed(),
+    );
+    if executor.interceptors.is_empty() {
+        return;
+    }
+    let output = result.map(|stream| CommandOutput {
+        stdout: stream.stdout.clone().unwrap_or_default(),
+        stderr: stream.stderr.clone().unwrap_or_default(),
+        exit_code: stream.exit_code,
+        success: stream.success,
+    });
+    executor.interceptors.after(
+        invocation,
+        output.as_ref().map_err(|e| *e),
+        started_at.elapsed(),
+    );
+}
+
+/// Start `invocation` through the executor's runner, feeding its stdin source
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+fn spawn_process(
+    executor: &CommandExecutor,
+    invocation: &Invocation,
+    command_name: &str,
+) -> Result<SpawnedProcess> {
+    executor
+        .runner
+        .spawn(invocation, SpawnStdin::Invocation)
+        .inspect_err(
+            |e| warn!(command = %command_name, error = %e, "failed to spawn streaming command"),
+        )
+}
+
+/// Run `future`, returning [`Error::Interrupted`] if `token` is cancelled first.
+async fn cancellable<T>(
+    token: Option<&CancellationToken>,
+    future: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    match token {
+        Some(token) => token
+            .run_until_cancelled(future)
+            .await
+            .unwrap_or(Err(Error::Interrupted)),
+        None => future.await,
+    }
 }
 
 #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
 async fn stream_command_inner(
-    mut cmd: TokioCommand,
+    process: SpawnedProcess,
     mut handler: impl FnMut(OutputLine) + Send + 'static,
     command_name: &'static str,
 ) -> Result<StreamResult> {
-    cmd.stdout(Stdio::piped());
-    cmd.stderr(Stdio::piped());
-
     let started_at = std::time::Instant::now();
-
-    let mut child = cmd.spawn().map_err(|e| {
-        warn!(command = command_name, error = %e, "failed to spawn streaming command");
-        crate::error::Error::custom(format!("Failed to spawn command: {e}"))
-    })?;
-
-    let stdout = child
-        .stdout
-        .take()
-        .ok_or_else(|| crate::error::Error::custom("Failed to capture stdout"))?;
-    let stderr = child
-        .stderr
-        .take()
-        .ok_or_else(|| crate::error::Error::custom("Failed to capture stderr"))?;
+    let SpawnedProcess {
+        stdout,
+        stderr,
+        exit,
+        ..
+    } = process;
 
     let stdout_reader = BufReader::new(stdout);
     let stderr_reader = BufReader::new(stderr);
@@ -161,17 +492,20 @@ async fn stream_command_inner(
 
     let mut stdout_accumulator = Vec::new();
     let mut stderr_accumulator = Vec::new();
+    let mut stdout_open = true;
+    let mut stderr_open = true;
 
-    loop {
+    // Keep reading until both pipes close; either may finish first
+    while stdout_open || stderr_open {
         tokio::select! {
-            line = stdout_lines.next_line() => {
+            line = stdout_lines.next_line(), if stdout_open => {
                 match line {
                     Ok(Some(text)) => {
                         debug!(stream = "stdout", line = %text, "stream line");
                         stdout_accumulator.push(text.clone());
                         handler(OutputLine::Stdout(text));
                     }
-                    Ok(None) => break,
+                    Ok(None) => stdout_open = false,
                     Err(e) => {
                         return Err(crate::error::Error::custom(
                             format!("Error reading stdout: {e}")
@@ -179,14 +513,14 @@ async fn stream_command_inner(
                     }
                 }
             }
-            line = stderr_lines.next_line() => {
+            line = stderr_lines.next_line(), if stderr_open => {
                 match line {
                     Ok(Some(text)) => {
                         debug!(stream = "stderr", line = %text, "stream line");
                         stderr_accumulator.push(text.clone());
                         handler(OutputLine::Stderr(text));
                     }
-                    Ok(None) => break,
+                    Ok(None) => stderr_open = false,
                     Err(e) => {
                         return Err(crate::error::Error::custom(
                             format!("Error reading stderr: {e}")
@@ -197,13 +531,8 @@ async fn stream_command_inner(
         }
     }
 
-    let status = child
-        .wait()
-        .await
-        .map_err(|e| crate::error::Error::custom(format!("Failed to wait for command: {e}")))?;
-
-    let exit_code = status.code().unwrap_or(-1);
-    let success = status.success();
+    let exit_code = exit.await?;
+    let success = exit_code == 0;
     let duration_ms = u64::try_from(started_at.elapsed().as_millis()).unwrap_or(u64::MAX);
 
     #[cfg_attr(not(feature = "tracing"), allow(clippy::if_same_then_else))]
@@ -241,39 +570,45 @@ async fn stream_command_inner(
 ///
 /// `command_name` is a short diagnostic label used for tracing spans.
 pub(crate) async fn stream_command_channel(
-    cmd: TokioCommand,
+    executor: &CommandExecutor,
+    args: Vec<String>,
     command_name: &'static str,
 ) -> Result<(mpsc::Receiver<OutputLine>, StreamResult)> {
+    let mut invocation = executor.invocation(args)?;
+    executor.interceptors.before(&mut invocation)?;
+    let started_at = std::time::Instant::now();
+
     let span = info_span!("docker.stream", command = command_name, mode = "channel",);
-    stream_command_channel_inner(cmd, command_name)
-        .instrument(span)
-        .await
+    let run = async {
+        let process = spawn_process(executor, &invocation, command_name)?;
+        stream_command_channel_inner(process, command_name).await
+    }
+    .instrument(span);
+    let result = cancellable(executor.cancellation.as_ref(), run).await;
+
+    notify_after(
+        executor,
+        &invocation,
+        command_name,
+        result.as_ref().map(|(_, stream)| stream),
+        started_at,
+    );
+    result
 }
 
 #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
 async fn stream_command_channel_inner(
-    mut cmd: TokioCommand,
+    process: SpawnedProcess,
     command_name: &'static str,
 ) -> Result<(mpsc::Receiver<OutputLine>, StreamResult)> {
     let (tx, rx) = mpsc::channel(100);
     let started_at = std::time::Instant::now();
-
-    cmd.stdout(Stdio::piped());
-    cmd.stderr(Stdio::piped());
-
-    let mut child = cmd.spawn().map_err(|e| {
-        warn!(command = command_name, error = %e, "failed to spawn streaming command");
-        crate::error::Error::custom(format!("Failed to spawn command: {e}"))
-    })?;
-
-    let stdout = child
-        .stdout
-        .take()
-        .ok_or_else(|| crate::error::Error::custom("Failed to capture stdout"))?;
-    let stderr = child
-        .stderr
-        .take()
-        .ok_or_else(|| crate::error::Error::custom("Failed to capture stderr"))?;
+    let SpawnedProcess {
+        stdout,
+        stderr,
+        exit,
+        ..
+    } = process;
 
     let tx_clone = tx.clone();
 
@@ -304,17 +639,12 @@ async fn stream_command_channel_inner(
     });
 
     // Wait for both tasks and the process
-    let status_future = child.wait();
-    let (stdout_lines, stderr_lines, status) =
-        tokio::join!(stdout_task, stderr_task, status_future);
+    let (stdout_lines, stderr_lines, exit_code) = tokio::join!(stdout_task, stderr_task, exit);
 
     let stdout_lines = stdout_lines.unwrap_or_default();
     let stderr_lines = stderr_lines.unwrap_or_default();
-    let status = status
-        .map_err(|e| crate::error::Error::custom(format!("Failed to wait for command: {e}")))?;
-
-    let exit_code = status.code().unwrap_or(-1);
-    let success = status.success();
+    let exit_code = exit_code?;
+    let success = exit_code == 0;
     let duration_ms = u64::try_from(started_at.elapsed().as_millis()).unwrap_or(u64::MAX);
 
     #[cfg_attr(not(feature = "tracing"), allow(clippy::if_same_then_else))]
@@ -349,9 +679,295 @@ async fn stream_command_channel_inner(
     ))
 }
 
+/// Start `args` (subcommand first) and forward its output to an
+/// [`OutputStream`]
+///
+/// The process is spawned before returning, so spawn errors and interceptor
+/// vetoes surface here. Reading happens on a background task.
+pub(crate) fn spawn_output_stream(
+    executor: &CommandExecutor,
+    args: Vec<String>,
+) -> Result<(OutputStream, StreamCompletion)> {
+    executor.connection.validate()?;
+    let command_name = args.first().cloned().unwrap_or_default();
+    let mut invocation = executor.invocation(args)?;
+    executor.interceptors.before(&mut invocation)?;
+
+    let process = spawn_process(executor, &invocation, &command_name)?;
+
+    let (line_tx, line_rx) = mpsc::channel(OUTPUT_STREAM_CAPACITY);
+    let (result_tx, result_rx) = oneshot::channel();
+    // Never sent on; closes once both handles are dropped
+    let (interest_tx, mut interest_rx) = mpsc::channel::<()>(1);
+    let executor = executor.clone();
+    let started_at = Instant::now();
+
+    let span = info_span!("docker.stream", command = %command_name, mode = "stream");
+    tokio::spawn(
+        async move {
+            let run = forward_output(process, line_tx, &command_name);
+            let result = tokio::select! {
+                result = cancellable(executor.cancellation.as_ref(), run) => result,
+                _ = interest_rx.recv() => {
+                    debug!(command = %command_name, "stream dropped, stopping command");
+                    return;
+                }
+            };
+            notify_after(
+                &executor,
+                &invocation,
+                &command_name,
+                result.as_ref(),
+                started_at,
+            );
+            let _ = result_tx.send(result);
+        }
+        .instrument(span),
+    );
+
+    Ok((
+        OutputStream {
+            lines: line_rx,
+            _interest: interest_tx.clone(),
+        },
+        StreamCompletion {
+            result: result_rx,
+            _interest: interest_tx,
+        },
+    ))
+}
+
+/// Spawn `args` with piped standard streams and hand them out as a session
+///
+/// Runs the before hooks and fails if they veto. A background task waits for
+/// the process, stopping it when the executor's cancellation token fires or
+/// the [`SessionExit`] is dropped. Any stdin source set on the executor is
+/// ignored.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn spawn_session(executor: &CommandExecutor, args: Vec<String>) -> Result<ExecSession> {
+    executor.connection.validate()?;
+    let command_name = args.first().cloned().unwrap_or_default();
+    let mut invocation = executor.invocation(args)?;
+    executor.interceptors.before(&mut invocation)?;
+
+    let SpawnedProcess {
+        stdin,
+        stdout,
+        stderr,
+        exit,
+    } = executor
+        .runner
+        .spawn(&invocation, SpawnStdin::Piped)
+        .inspect_err(|e| warn!(command = %command_name, error = %e, "failed to spawn session"))?;
+    let stdin = stdin.ok_or_else(|| Error::custom("Failed to capture stdin"))?;
+
+    let (mut result_tx, result_rx) = oneshot::channel();
+    let executor = executor.clone();
+    let started_at = Instant::now();
+
+    let span = info_span!("docker.stream", command = %command_name, mode = "session");
+    tokio::spawn(
+        async move {
+            let wait = async {
+                let exit_code = exit.await?;
+                Ok(StreamResult {
+                    exit_code,
+                    success: exit_code == 0,
+                    stdout: None,
+                    stderr: None,
+                })
+            };
+            let result = tokio::select! {
+                result = cancellable(executor.cancellation.as_ref(), wait) => result,
+                () = result_tx.closed() => {
+                    debug!(command = %command_name, "session dropped, stopping command");
+                    return;
+                }
+            };
+            notify_after(
+                &executor,
+                &invocation,
+                &command_name,
+                result.as_ref(),
+                started_at,
+            );
+            let _ = result_tx.send(result);
+        }
+        .instrument(span),
+    );
+
+    Ok(ExecSession {
+        stdin,
+        stdout,
+        stderr,
+        exit: SessionExit { result: result_rx },
+    })
+}
+
+/// Spawn `args` and hand out its stdout as a [`ByteStream`]
+///
+/// Runs the before hooks and fails if they veto. A background task collects
+/// stderr and waits for the process, turning a non-zero exit into an error.
+/// The process is stopped when the executor's
+/// cancellation token fires or the stream is dropped.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn spawn_byte_stream(
+    executor: &CommandExecutor,
+    args: Vec<String>,
+) -> Result<ByteStream> {
+    executor.connection.validate()?;
+    let command_name = args.first().cloned().unwrap_or_default();
+    let mut invocation = executor.invocation(args)?;
+    executor.interceptors.before(&mut invocation)?;
+
+    let SpawnedProcess {
+        stdout,
+        mut stderr,
+        exit,
+        ..
+    } = spawn_process(executor, &invocation, &command_name)?;
+
+    let (mut result_tx, result_rx) = oneshot::channel();
+    let executor = executor.clone();
+    let started_at = Instant::now();
+
+    let span = info_span!("docker.stream", command = %command_name, mode = "bytes");
+    tokio::spawn(
+        async move {
+            let wait = async {
+                let mut err = Vec::new();
+                stderr
+                    .read_to_end(&mut err)
+                    .await
+                    .map_err(|e| Error::custom(format!("Error reading stderr: {e}")))?;
+                let exit_code = exit.await?;
+                Ok(StreamResult {
+                    exit_code,
+                    success: exit_code == 0,
+                    stdout: None,
+                    stderr: Some(String::from_utf8_lossy(&err).into_owned()),
+                })
+            };
+            let result = tokio::select! {
+                result = cancellable(executor.cancellation.as_ref(), wait) => result,
+                () = result_tx.closed() => {
+                    debug!(command = %command_name, "byte stream dropped, stopping command");
+                    return;
+                }
+            };
+            notify_after(
+                &executor,
+                &invocation,
+                &command_name,
+                result.as_ref(),
+                started_at,
+            );
+            let result = result.and_then(|status| {
+                if status.success {
+                    Ok(status)
+                } else {
+                    Err(executor.failure(
+                        format!("docker {command_name}"),
+                        status.exit_code,
+                        "",
+                        &status.stderr.unwrap_or_default(),
+                    ))
+                }
+            });
+            let _ = result_tx.send(result);
+        }
+        .instrument(span),
+    );
+
+    Ok(ByteStream {
+        stdout,
+        result: Some(result_rx),
+        outcome: None,
+    })
+}
+
+/// Send each line of the child's output to `lines` and wait for it to exit
+///
+/// Sending waits for room in the channel, which is what applies
+/// backpressure. Lines are discarded once the receiver is gone.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+async fn forward_output(
+    process: SpawnedProcess,
+    lines: mpsc::Sender<OutputLine>,
+    command_name: &str,
+) -> Result<StreamResult> {
+    let started_at = Instant::now();
+    let SpawnedProcess {
+        stdout,
+        stderr,
+        exit,
+        ..
+    } = process;
+    let mut stdout_lines = BufReader::new(stdout).lines();
+    let mut stderr_lines = BufReader::new(stderr).lines();
+    let mut stdout_open = true;
+    let mut stderr_open = true;
+
+    while stdout_open || stderr_open {
+        let line = tokio::select! {
+            line = stdout_lines.next_line(), if stdout_open => match line {
+                Ok(Some(text)) => OutputLine::Stdout(text),
+                Ok(None) => {
+                    stdout_open = false;
+                    continue;
+                }
+                Err(e) => return Err(Error::custom(format!("Error reading stdout: {e}"))),
+            },
+            line = stderr_lines.next_line(), if stderr_open => match line {
+                Ok(Some(text)) => OutputLine::Stderr(text),
+                Ok(None) => {
+                    stderr_open = false;
+                    continue;
+                }
+                Err(e) => return Err(Error::custom(format!("Error reading stderr: {e}"))),
+            },
+        };
+        debug!(line = ?line, "stream line");
+        let _ = lines.send(line).await;
+    }
+    // End the stream before waiting for the exit status
+    drop(lines);
+
+    let exit_code = exit.await?;
+    let success = exit_code == 0;
+    let duration_ms = u64::try_from(started_at.elapsed().as_millis()).unwrap_or(u64::MAX);
+
+    #[cfg_attr(not(feature = "tracing"), allow(clippy::if_same_then_else))]
+    if success {
+        info!(
+            command = %command_name,
+            exit_code = exit_code,
+            duration_ms = duration_ms,
+            "stream command completed"
+        );
+    } else {
+        warn!(
+            command = %command_name,
+            exit_code = exit_code,
+            duration_ms = duration_ms,
+            "stream command exited non-zero"
+        );
+    }
+
+    Ok(StreamResult {
+        exit_code,
+        success,
+        stdout: None,
+        stderr: None,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
+    use crate::{DockerCommand, LogsCommand};
+    use std::time::Duration;
 
     #[test]
     fn test_output_line() {
@@ -396,4 +1012,129 @@ mod tests {
         let result2 = filter(OutputLine::Stdout("normal message".to_string()));
         assert!(result2.is_none());
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_stream_uses_process_env_and_current_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut logs = LogsCommand::new("web");
+        logs.with_env("DOCKER_CONFIG", "/etc/docker-ci")
+            .with_current_dir(dir.path())
+            .with_runner(crate::runner::ShellRunner::new("echo $DOCKER_CONFIG; pwd"));
+
+        let result = logs.stream(|_| {}).await.unwrap();
+
+        let stdout = result.stdout.unwrap();
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(lines[0], "/etc/docker-ci");
+        assert_eq!(
+            std::fs::canonicalize(lines[1]).unwrap(),
+            std::fs::canonicalize(dir.path()).unwrap()
+        );
+    }
+
+    /// A logs command whose CLI responds with `response`
+    fn faked(response: FakeResponse) -> LogsCommand {
+        let mut logs = LogsCommand::new("web");
+        logs.with_runner(FakeRunner::new().on(ArgMatcher::Any, response));
+        logs
+    }
+
+    #[tokio::test]
+    async fn test_stream_output_yields_lines_before_exit() {
+        let token = CancellationToken::new();
+        let mut logs = faked(FakeResponse::success("ready\n").with_delay(Duration::from_secs(30)));
+        logs.with_cancellation(token.clone());
+
+        let (mut lines, mut completion) = logs.stream_output().unwrap();
+        let first = tokio::time::timeout(Duration::from_secs(5), lines.next_line())
+            .await
+            .unwrap();
+
+        assert!(matches!(first, Some(OutputLine::Stdout(ref text)) if text == "ready"));
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), &mut completion)
+                .await
+                .is_err(),
+            "command should still be running"
+        );
+        token.cancel();
+        assert!(matches!(completion.await, Err(Error::Interrupted)));
+    }
+
+    #[tokio::test]
+    async fn test_stream_output_applies_backpressure() {
+        use futures::StreamExt;
+
+        // Far more output than the channel can hold
+        let (lines, mut completion) = faked(FakeResponse::success("y\n".repeat(200_000)))
+            .stream_output()
+            .unwrap();
+
+        assert!(
+            tokio::time::timeout(Duration::from_millis(200), &mut completion)
+                .await
+                .is_err(),
+            "completion should wait until the stream is read"
+        );
+        assert_eq!(lines.count().await, 200_000);
+        assert!(completion.await.unwrap().success);
+    }
+
+    #[tokio::test]
+    async fn test_stream_output_reports_exit_status() {
+        use futures::StreamExt;
+
+        let (lines, completion) = faked(FakeResponse::failure(3, "err\n").with_stdout("out\n"))
+            .stream_output()
+            .unwrap();
+        let mut lines: Vec<String> = lines
+            .map(|line| match line {
+                OutputLine::Stdout(text) => format!("stdout {text}"),
+                OutputLine::Stderr(text) => format!("stderr {text}"),
+            })
+            .collect()
+            .await;
+        lines.sort();
+        let result = completion.await.unwrap();
+
+        assert_eq!(lines, ["stderr err", "stdout out"]);
+        assert_eq!(result.exit_code, 3);
+        assert!(!result.success);
+        assert!(result.stdout.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stream_output_veto_is_returned_immediately() {
+        use crate::{interceptor, PullCommand};
+
+        let mut pull = PullCommand::new("redis:7");
+        pull.with_interceptor(interceptor::before(|_| {
+            Err(Error::invalid_config("no pulls in tests"))
+        }));
+
+        assert!(matches!(
+            pull.stream_output(),
+            Err(Error::InvalidConfig { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_stream_rejected_by_runner_without_spawn() {
+        #[derive(Debug)]
+        struct RunOnly;
+
+        #[async_trait::async_trait]
+        impl crate::runner::CommandRunner for RunOnly {
+            async fn run(&self, _: &crate::runner::Invocation) -> Result<crate::ProcessOutput> {
+                unreachable!("streaming must not fall back to run")
+            }
+        }
+
+        let mut logs = LogsCommand::new("web");
+        logs.with_runner(RunOnly);
+
+        let error = logs.stream(|_| {}).await.unwrap_err();
+        assert!(matches!(error, Error::InvalidConfig { .. }), "{error:?}");
+    }
 }
@@ -11,7 +11,7 @@
 #![allow(clippy::inefficient_to_string)]
 
 use crate::tracing_compat::{debug, error, info, trace, warn};
-use crate::{DockerCommand, RunCommand};
+use crate::{CommandExecutor, DockerCommand, RunCommand};
 use async_trait::async_trait;
 use std::collections::HashMap;
 
@@ -104,6 +104,31 @@ pub struct TemplateConfig {
 
     /// Platform specification (e.g., "linux/amd64", "linux/arm64")
     pub platform: Option<String>,
+
+    /// Executor used for every Docker command the template issues
+    pub executor: CommandExecutor,
+}
+
+impl TemplateConfig {
+    /// The executor for the template's commands
+    ///
+    /// Values of sensitive environment variables (such as passwords) are
+    /// registered as secrets, so they are masked wherever they show up as
+    /// plain arguments, e.g. in a `redis-cli -a` health check.
+    pub(crate) fn command_executor(&self) -> CommandExecutor {
+        self.env
+            .iter()
+            .filter(|(key, _)| self.executor.redactor.is_sensitive_key(key))
+            .fold(self.executor.clone(), |executor, (_, value)| {
+                executor.secret(value.clone())
+            })
+    }
+}
+
+/// Attach a copy of `executor` to `cmd`, replacing its default one.
+pub(crate) fn use_executor<C: DockerCommand>(executor: &CommandExecutor, mut cmd: C) -> C {
+    *cmd.get_executor_mut() = executor.clone();
+    cmd
 }
 
 /// Volume mount configuration
@@ -150,12 +175,27 @@ pub trait Template: Send + Sync {
     /// Get a mutable reference to the configuration
     fn config_mut(&mut self) -> &mut TemplateConfig;
 
+    /// Run every Docker command issued by this template through `executor`
+    ///
+    /// This is how a custom [`CommandRunner`](crate::CommandRunner), such as
+    /// a [`FakeRunner`](crate::FakeRunner), reaches the template's commands.
+    fn with_executor(mut self, executor: CommandExecutor) -> Self
+    where
+        Self: Sized,
+    {
+        self.config_mut().executor = executor;
+        self
+    }
+
     /// Build the RunCommand for this template
     fn build_command(&self) -> RunCommand {
         let config = self.config();
-        let mut cmd = RunCommand::new(format!("{}:{}", config.image, config.tag))
-            .name(&config.name)
-            .detach();
+        let mut cmd = use_executor(
+            &config.command_executor(),
+            RunCommand::new(format!("{}:{}", config.image, config.tag)),
+        )
+        .name(&config.name)
+        .detach();
 
         // Add port mappings
         for (host, container) in &config.ports {
@@ -265,13 +305,17 @@ pub trait Template: Send + Sync {
     async fn stop(&self) -> Result<()> {
         use crate::StopCommand;
 
-        let name = self.config().name.as_str();
+        let config = self.config();
+        let name = config.name.as_str();
         info!(template = %name, "stopping container");
 
-        StopCommand::new(name).execute().await.map_err(|e| {
-            error!(template = %name, error = %e, "failed to stop container");
-            e
-        })?;
+        use_executor(&config.command_executor(), StopCommand::new(name))
+            .execute()
+            .await
+            .map_err(|e| {
+                error!(template = %name, error = %e, "failed to stop container");
+                e
+            })?;
 
         debug!(template = %name, "container stopped");
         Ok(())
@@ -281,10 +325,11 @@ pub trait Template: Send + Sync {
     async fn remove(&self) -> Result<()> {
         use crate::RmCommand;
 
-        let name = self.config().name.as_str();
+        let config = self.config();
+        let name = config.name.as_str();
         info!(template = %name, "removing container");
 
-        RmCommand::new(name)
+        use_executor(&config.command_executor(), RmCommand::new(name))
             .force()
             .volumes()
             .execute()
@@ -302,9 +347,10 @@ pub trait Template: Send + Sync {
     async fn is_running(&self) -> Result<bool> {
         use crate::PsCommand;
 
-        let name = &self.config().name;
+        let config = self.config();
+        let name = &config.name;
 
-        let output = PsCommand::new()
+        let output = use_executor(&config.command_executor(), PsCommand::new())
             .filter(format!("name={name}"))
             .quiet()
             .execute()
@@ -321,7 +367,8 @@ pub trait Template: Send + Sync {
     async fn logs(&self, follow: bool, tail: Option<&str>) -> Result<crate::CommandOutput> {
         use crate::LogsCommand;
 
-        let mut cmd = LogsCommand::new(&self.config().name);
+        let config = self.config();
+        let mut cmd = use_executor(&config.command_executor(), LogsCommand::new(&config.name));
 
         if follow {
             cmd = cmd.follow();
@@ -339,7 +386,11 @@ pub trait Template: Send + Sync {
         use crate::ExecCommand;
 
         let cmd_vec: Vec<String> = command.iter().map(|s| s.to_string()).collect();
-        let cmd = ExecCommand::new(&self.config().name, cmd_vec);
+        let config = self.config();
+        let cmd = use_executor(
+            &config.command_executor(),
+            ExecCommand::new(&config.name, cmd_vec),
+        );
 
         cmd.execute().await.map_err(Into::into)
     }
@@ -394,7 +445,9 @@ pub trait Template: Send + Sync {
                 if has_health_check {
                     use crate::InspectCommand;
 
-                    if let Ok(inspect) = InspectCommand::new(name).execute().await {
+                    let inspect_cmd =
+                        use_executor(&self.config().command_executor(), InspectCommand::new(name));
+                    if let Ok(inspect) = inspect_cmd.execute().await {
                         // Check health status in the inspect output
                         if let Ok(containers) =
                             serde_json::from_str::<serde_json::Value>(&inspect.stdout)
@@ -481,6 +534,86 @@ pub trait Template: Send + Sync {
             )))
         }
     }
+
+    /// Blocking version of [`start`](Self::start)
+    ///
+    /// # Errors
+    ///
+    /// Same as [`start`](Self::start).
+    #[cfg(feature = "blocking")]
+    fn start_blocking(&self) -> Result<String> {
+        crate::blocking::block_on(self.start())
+    }
+
+    /// Blocking version of [`start_and_wait`](Self::start_and_wait)
+    ///
+    /// # Errors
+    ///
+    /// Same as [`start_and_wait`](Self::start_and_wait).
+    #[cfg(feature = "blocking")]
+    fn start_and_wait_blocking(&self) -> Result<String> {
+        crate::blocking::block_on(self.start_and_wait())
+    }
+
+    /// Blocking version of [`stop`](Self::stop)
+    ///
+    /// # Errors
+    ///
+    /// Same as [`stop`](Self::stop).
+    #[cfg(feature = "blocking")]
+    fn stop_blocking(&self) -> Result<()> {
+        crate::blocking::block_on(self.stop())
+    }
+
+    /// Blocking version of [`remove`](Self::remove)
+    ///
+    /// # Errors
+    ///
+    /// Same as [`remove`](Self::remove).
+    #[cfg(feature = "blocking")]
+    fn remove_blocking(&self) -> Result<()> {
+        crate::blocking::block_on(self.remove())
+    }
+
+    /// Blocking version of [`is_running`](Self::is_running)
+    ///
+    /// # Errors
+    ///
+    /// Same as [`is_running`](Self::is_running).
+    #[cfg(feature = "blocking")]
+    fn is_running_blocking(&self) -> Result<bool> {
+        crate::blocking::block_on(self.is_running())
+    }
+
+    /// Blocking version of [`logs`](Self::logs)
+    ///
+    /// # Errors
+    ///
+    /// Same as [`logs`](Self::logs).
+    #[cfg(feature = "blocking")]
+    fn logs_blocking(&self, follow: bool, tail: Option<&str>) -> Result<crate::CommandOutput> {
+        crate::blocking::block_on(self.logs(follow, tail))
+    }
+
+    /// Blocking version of [`exec`](Self::exec)
+    ///
+    /// # Errors
+    ///
+    /// Same as [`exec`](Self::exec).
+    #[cfg(feature = "blocking")]
+    fn exec_blocking(&self, command: Vec<&str>) -> Result<crate::ExecOutput> {
+        crate::blocking::block_on(self.exec(command))
+    }
+
+    /// Blocking version of [`wait_for_ready`](Self::wait_for_ready)
+    ///
+    /// # Errors
+    ///
+    /// Same as [`wait_for_ready`](Self::wait_for_ready).
+    #[cfg(feature = "blocking")]
+    fn wait_for_ready_blocking(&self) -> Result<()> {
+        crate::blocking::block_on(self.wait_for_ready())
+    }
 }
 
 /// Builder for creating custom templates
@@ -505,6 +638,7 @@ impl TemplateBuilder {
                 memory_limit: None,
                 cpu_limit: None,
                 platform: None,
+                executor: CommandExecutor::default(),
             },
         }
     }
@@ -571,6 +705,12 @@ impl TemplateBuilder {
         self
     }
 
+    /// Set the executor used for the template's Docker commands
+    pub fn executor(mut self, executor: CommandExecutor) -> Self {
+        self.config.executor = executor;
+        self
+    }
+
     /// Build into a custom template
     pub fn build(self) -> CustomTemplate {
         CustomTemplate {
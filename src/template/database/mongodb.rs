@@ -45,6 +45,7 @@ impl MongodbTemplate {
             memory_limit: None,
             cpu_limit: None,
             platform: None,
+            executor: crate::CommandExecutor::default(),
         };
 
         Self { config }
@@ -206,9 +207,12 @@ impl Template for MongodbTemplate {
         let config = self.config();
         let image_tag = format!("{}:{}", config.image, config.tag);
 
-        let mut cmd = crate::RunCommand::new(image_tag)
-            .name(&config.name)
-            .detach();
+        let mut cmd = crate::template::use_executor(
+            &config.command_executor(),
+            crate::RunCommand::new(image_tag),
+        )
+        .name(&config.name)
+        .detach();
 
         // Add port mappings
         for (host, container) in &config.ports {
@@ -50,6 +50,7 @@ impl MysqlTemplate {
             memory_limit: None,
             cpu_limit: None,
             platform: None,
+            executor: crate::CommandExecutor::default(),
         };
 
         Self { config }
@@ -233,9 +234,12 @@ impl Template for MysqlTemplate {
         let config = self.config();
         let image_tag = format!("{}:{}", config.image, config.tag);
 
-        let mut cmd = crate::RunCommand::new(image_tag)
-            .name(&config.name)
-            .detach();
+        let mut cmd = crate::template::use_executor(
+            &config.command_executor(),
+            crate::RunCommand::new(image_tag),
+        )
+        .name(&config.name)
+        .detach();
 
         // Add port mappings
         for (host, container) in &config.ports {
@@ -50,6 +50,7 @@ impl PostgresTemplate {
             memory_limit: None,
             cpu_limit: None,
             platform: None,
+            executor: crate::CommandExecutor::default(),
         };
 
         Self { config }
@@ -50,6 +50,7 @@ impl RedisTemplate {
             memory_limit: None,
             cpu_limit: None,
             platform: None,
+            executor: crate::CommandExecutor::default(),
         };
 
         Self {
@@ -405,9 +406,12 @@ impl Template for RedisTemplate {
             format!("{}:{}", config.image, config.tag)
         };
 
-        let mut cmd = crate::RunCommand::new(image_tag)
-            .name(&config.name)
-            .detach();
+        let mut cmd = crate::template::use_executor(
+            &config.command_executor(),
+            crate::RunCommand::new(image_tag),
+        )
+        .name(&config.name)
+        .detach();
 
         // Add port mappings. In host networking mode the container shares the
         // host's network namespace, so published ports are ignored by Docker
@@ -834,4 +838,100 @@ mod tests {
         // Plaintext is closed, so connection_string() returns the TLS endpoint.
         assert_eq!(template.connection_string(), "rediss://localhost:6380");
     }
+
+    #[tokio::test]
+    async fn test_redis_template_uses_injected_runner() {
+        use crate::{ArgMatcher, CommandExecutor, FakeResponse, FakeRunner};
+
+        let fake = FakeRunner::new()
+            .on(
+                ArgMatcher::prefix(["run"]),
+                FakeResponse::success("abc123\n"),
+            )
+            .on(
+                ArgMatcher::prefix(["stop"]),
+                FakeResponse::success("test-redis\n"),
+            );
+
+        let template = RedisTemplate::new("test-redis")
+            .with_executor(CommandExecutor::new().runner(fake.clone()));
+
+        let id = template.start().await.unwrap();
+        assert_eq!(id, "abc123");
+        template.stop().await.unwrap();
+
+        let invocations = fake.invocations();
+        assert_eq!(invocations.len(), 2);
+        assert_eq!(invocations[0].args[0], "run");
+        assert!(invocations[0].args.contains(&"redis:7-alpine".to_string()));
+        assert_eq!(invocations[1].args, ["stop", "test-redis"]);
+    }
+
+    #[tokio::test]
+    async fn test_redis_template_masks_password_in_exec_errors() {
+        use crate::{ArgMatcher, CommandExecutor, FakeResponse, FakeRunner};
+
+        let fake = FakeRunner::new().on(
+            ArgMatcher::prefix(["exec"]),
+            FakeResponse::failure(1, "AUTH failed for secret"),
+        );
+        // A single kind of character, yet explicitly a password
+        let template = RedisTemplate::new("test-redis")
+            .password("secret")
+            .with_executor(CommandExecutor::new().runner(fake));
+
+        let err = template
+            .exec(vec!["redis-cli", "-h", "localhost", "-a", "secret", "ping"])
+            .await
+            .unwrap_err();
+        let crate::template::TemplateError::DockerError(crate::Error::CommandFailed {
+            command,
+            stderr,
+            ..
+        }) = err
+        else {
+            panic!("expected CommandFailed, got {err:?}");
+        };
+        assert_eq!(
+            command,
+            "docker exec test-redis redis-cli -h localhost -a *** ping"
+        );
+        assert_eq!(stderr, "AUTH failed for ***");
+
+        // Other executors are unaffected
+        let shown = CommandExecutor::new()
+            .invocation(vec!["exec".into(), "secret".into()])
+            .unwrap()
+            .command_line();
+        assert_eq!(shown, "docker exec secret");
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_redis_template_blocking_lifecycle() {
+        use crate::{ArgMatcher, CommandExecutor, FakeResponse, FakeRunner};
+
+        let fake = FakeRunner::new()
+            .on(
+                ArgMatcher::prefix(["run"]),
+                FakeResponse::success("abc123\n"),
+            )
+            .on(
+                ArgMatcher::prefix(["stop"]),
+                FakeResponse::success("test-redis\n"),
+            )
+            .on(ArgMatcher::prefix(["rm"]), FakeResponse::success(""));
+
+        let template = RedisTemplate::new("test-redis")
+            .with_executor(CommandExecutor::new().runner(fake.clone()));
+
+        assert_eq!(template.start_blocking().unwrap(), "abc123");
+        template.stop_blocking().unwrap();
+        template.remove_blocking().unwrap();
+
+        let invocations = fake.invocations();
+        assert_eq!(invocations.len(), 3);
+        assert_eq!(invocations[1].args, ["stop", "test-redis"]);
+        assert_eq!(invocations[2].args[0], "rm");
+    }
 }
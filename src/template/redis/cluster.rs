@@ -15,8 +15,8 @@ use super::common::{
     REDIS_STACK_SERVER_IMAGE, REDIS_STACK_TAG, REDIS_TLS_CA_FILE, REDIS_TLS_CERT_FILE,
     REDIS_TLS_DIR, REDIS_TLS_KEY_FILE,
 };
-use crate::template::{Template, TemplateConfig, TemplateError};
-use crate::{DockerCommand, ExecCommand, NetworkCreateCommand, RunCommand};
+use crate::template::{use_executor, Template, TemplateConfig, TemplateError};
+use crate::{CommandExecutor, DockerCommand, ExecCommand, NetworkCreateCommand, RunCommand};
 use async_trait::async_trait;
 
 /// Redis Cluster template for automatic multi-node cluster setup
@@ -64,6 +64,8 @@ pub struct RedisClusterTemplate {
     /// Host directory containing TLS certificate material, mounted read-only
     /// into every node when TLS is enabled.
     tls_certs_dir: Option<String>,
+    /// Executor used for every Docker command the template issues
+    executor: CommandExecutor,
 }
 
 impl RedisClusterTemplate {
@@ -94,6 +96,7 @@ impl RedisClusterTemplate {
             redis_tag: None,
             platform: None,
             tls_certs_dir: None,
+            executor: CommandExecutor::default(),
         }
     }
 
@@ -579,7 +582,12 @@ impl RedisClusterTemplate {
         }
         role_args.push("role".to_string());
 
-        let output = ExecCommand::new(&node_name, role_args).execute().await?;
+        let output = use_executor(
+            &self.command_executor(),
+            ExecCommand::new(&node_name, role_args),
+        )
+        .execute()
+        .await?;
 
         // `redis-cli role` prints the role keyword ("master" or "slave") on the
         // first line of its reply.
@@ -595,10 +603,13 @@ impl RedisClusterTemplate {
 
     /// Create the cluster network
     async fn create_network(&self) -> Result<String, TemplateError> {
-        let output = NetworkCreateCommand::new(&self.network_name)
-            .driver("bridge")
-            .execute()
-            .await?;
+        let output = use_executor(
+            &self.command_executor(),
+            NetworkCreateCommand::new(&self.network_name),
+        )
+        .driver("bridge")
+        .execute()
+        .await?;
 
         // Network ID is in stdout
         Ok(output.stdout.trim().to_string())
@@ -617,7 +628,9 @@ impl RedisClusterTemplate {
         // Choose image based on custom image or Redis Stack preference
         let image = self.node_image();
 
-        let mut cmd = RunCommand::new(image).name(&node_name).detach();
+        let mut cmd = use_executor(&self.command_executor(), RunCommand::new(image))
+            .name(&node_name)
+            .detach();
 
         if host_mode {
             // Host networking: share the host namespace, no published ports.
@@ -716,9 +729,12 @@ impl RedisClusterTemplate {
     async fn start_redis_insight(&self) -> Result<String, TemplateError> {
         let insight_name = format!("{}-insight", self.name);
 
-        let mut cmd = RunCommand::new(self.insight_image())
-            .name(&insight_name)
-            .detach();
+        let mut cmd = use_executor(
+            &self.command_executor(),
+            RunCommand::new(self.insight_image()),
+        )
+        .name(&insight_name)
+        .detach();
 
         if self.uses_host_network() {
             // No bridge network exists in host mode; the UI is reached on the
@@ -777,6 +793,15 @@ impl RedisClusterTemplate {
         format!("{}:{}", REDIS_INSIGHT_CLUSTER_IMAGE, self.redis_insight_tag)
     }
 
+    /// The executor for the template's commands, masking the password
+    /// wherever it is passed as a plain argument.
+    fn command_executor(&self) -> CommandExecutor {
+        match self.password {
+            Some(ref password) => self.executor.clone().secret(password.clone()),
+            None => self.executor.clone(),
+        }
+    }
+
     /// Build the redis-cli ping arguments used for node readiness checks.
     ///
     /// In host networking mode each node listens on its own `port_base + index`
@@ -821,10 +846,13 @@ impl RedisClusterTemplate {
             for &i in &pending {
                 let node_name = self.node_name(i);
                 let ping_args = self.build_ping_args(i);
-                let ready = ExecCommand::new(&node_name, ping_args)
-                    .execute()
-                    .await
-                    .is_ok_and(|output| output.stdout.trim() == "PONG");
+                let ready = use_executor(
+                    &self.command_executor(),
+                    ExecCommand::new(&node_name, ping_args),
+                )
+                .execute()
+                .await
+                .is_ok_and(|output| output.stdout.trim() == "PONG");
 
                 if !ready {
                     still_pending.push(i);
@@ -898,9 +926,12 @@ impl RedisClusterTemplate {
         // Execute cluster create in the first container
         let first_node_name = self.node_name(0);
 
-        ExecCommand::new(&first_node_name, create_args)
-            .execute()
-            .await?;
+        use_executor(
+            &self.command_executor(),
+            ExecCommand::new(&first_node_name, create_args),
+        )
+        .execute()
+        .await?;
 
         Ok(())
     }
@@ -923,7 +954,12 @@ impl RedisClusterTemplate {
             info_args.push(password.clone());
         }
 
-        let output = ExecCommand::new(&node_name, info_args).execute().await?;
+        let output = use_executor(
+            &self.command_executor(),
+            ExecCommand::new(&node_name, info_args),
+        )
+        .execute()
+        .await?;
 
         // Parse the cluster info output
         ClusterInfo::from_output(&output.stdout)
@@ -1097,6 +1133,11 @@ impl Template for RedisClusterTemplate {
         unimplemented!("RedisClusterTemplate manages multiple containers")
     }
 
+    fn with_executor(mut self, executor: CommandExecutor) -> Self {
+        self.executor = executor;
+        self
+    }
+
     async fn start(&self) -> Result<String, TemplateError> {
         // Create the private bridge network first. Host networking shares the
         // host namespace, so no bridge network is created in that mode.
@@ -1142,13 +1183,17 @@ impl Template for RedisClusterTemplate {
         // Stop all nodes
         for i in 0..self.total_nodes() {
             let node_name = self.node_name(i);
-            let _ = StopCommand::new(&node_name).execute().await;
+            let _ = use_executor(&self.command_executor(), StopCommand::new(&node_name))
+                .execute()
+                .await;
         }
 
         // Stop RedisInsight if it was started
         if self.with_redis_insight {
             let insight_name = format!("{}-insight", self.name);
-            let _ = StopCommand::new(&insight_name).execute().await;
+            let _ = use_executor(&self.command_executor(), StopCommand::new(&insight_name))
+                .execute()
+                .await;
         }
 
         Ok(())
@@ -1160,13 +1205,17 @@ impl Template for RedisClusterTemplate {
         // Remove all containers
         for i in 0..self.total_nodes() {
             let node_name = self.node_name(i);
-            let _ = RmCommand::new(&node_name).force().volumes().execute().await;
+            let _ = use_executor(&self.command_executor(), RmCommand::new(&node_name))
+                .force()
+                .volumes()
+                .execute()
+                .await;
         }
 
         // Remove RedisInsight if it was started
         if self.with_redis_insight {
             let insight_name = format!("{}-insight", self.name);
-            let _ = RmCommand::new(&insight_name)
+            let _ = use_executor(&self.command_executor(), RmCommand::new(&insight_name))
                 .force()
                 .volumes()
                 .execute()
@@ -1175,7 +1224,12 @@ impl Template for RedisClusterTemplate {
 
         // Remove the network. None is created in host networking mode.
         if !self.uses_host_network() {
-            let _ = NetworkRmCommand::new(&self.network_name).execute().await;
+            let _ = use_executor(
+                &self.command_executor(),
+                NetworkRmCommand::new(&self.network_name),
+            )
+            .execute()
+            .await;
         }
 
         Ok(())
@@ -9,7 +9,8 @@
 #![allow(clippy::return_self_not_must_use)]
 #![allow(clippy::needless_borrows_for_generic_args)]
 
-use crate::{DockerCommand, RmCommand, RunCommand, StopCommand};
+use crate::template::use_executor;
+use crate::{CommandExecutor, DockerCommand, RmCommand, RunCommand, StopCommand};
 use std::time::Duration;
 
 #[cfg(feature = "template-redis-enterprise")]
@@ -38,6 +39,7 @@ pub struct RedisEnterpriseTemplate {
     bootstrap_timeout: Duration,
     bootstrap_retries: u32,
     api_ready_timeout: Duration,
+    executor: CommandExecutor,
 }
 
 impl RedisEnterpriseTemplate {
@@ -63,9 +65,16 @@ impl RedisEnterpriseTemplate {
             bootstrap_timeout: Duration::from_secs(60),
             bootstrap_retries: 3,
             api_ready_timeout: Duration::from_secs(30),
+            executor: CommandExecutor::default(),
         }
     }
 
+    /// Set the executor used to start the Redis Enterprise container
+    pub fn with_executor(mut self, executor: CommandExecutor) -> Self {
+        self.executor = executor;
+        self
+    }
+
     /// Set the cluster name
     pub fn cluster_name(mut self, name: impl Into<String>) -> Self {
         self.cluster_name = name.into();
@@ -207,11 +216,14 @@ impl RedisEnterpriseTemplate {
 
         // Start the Redis Enterprise container
         let container_name = format!("{}-enterprise", self.name);
-        let mut cmd = RunCommand::new(format!("{}:{}", self.image, self.tag))
-            .name(&container_name)
-            .port(self.ui_port, 8443)
-            .port(self.api_port, 9443)
-            .detach();
+        let mut cmd = use_executor(
+            &self.executor,
+            RunCommand::new(format!("{}:{}", self.image, self.tag)),
+        )
+        .name(&container_name)
+        .port(self.ui_port, 8443)
+        .port(self.api_port, 9443)
+        .detach();
 
         // Add database ports range
         for i in 0..10 {
@@ -278,6 +290,7 @@ impl RedisEnterpriseTemplate {
             } else {
                 None
             },
+            executor: self.executor.clone(),
         })
     }
 
@@ -557,6 +570,8 @@ pub struct RedisEnterpriseConnectionInfo {
     pub password: String,
     /// Database port if initial database was created
     pub database_port: Option<u16>,
+    /// Executor used to stop the cluster
+    pub executor: CommandExecutor,
 }
 
 impl RedisEnterpriseConnectionInfo {
@@ -583,7 +598,7 @@ impl RedisEnterpriseConnectionInfo {
     /// Returns an error if container cleanup fails
     pub async fn stop(self) -> Result<(), crate::Error> {
         // Stop the container
-        StopCommand::new(&self.container_name)
+        use_executor(&self.executor, StopCommand::new(&self.container_name))
             .execute()
             .await
             .map_err(|e| crate::Error::Custom {
@@ -591,7 +606,7 @@ impl RedisEnterpriseConnectionInfo {
             })?;
 
         // Remove the container
-        RmCommand::new(&self.container_name)
+        use_executor(&self.executor, RmCommand::new(&self.container_name))
             .force()
             .volumes()
             .execute()
@@ -652,4 +667,33 @@ mod tests {
         assert!(json.contains(r#""password": "TestPass123!""#));
         assert!(json.contains(r#""action": "create_cluster""#));
     }
+
+    #[tokio::test]
+    async fn test_connection_info_stop_uses_executor() {
+        use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
+
+        let fake = FakeRunner::new().on(ArgMatcher::Any, FakeResponse::success(""));
+        let info = RedisEnterpriseConnectionInfo {
+            name: "test".to_string(),
+            container_name: "test-enterprise".to_string(),
+            cluster_name: "Test Cluster".to_string(),
+            ui_url: "https://localhost:8443".to_string(),
+            api_url: "https://localhost:9443".to_string(),
+            username: "admin@redis.local".to_string(),
+            password: "TestPass123!".to_string(),
+            database_port: None,
+            executor: CommandExecutor::new().runner(fake.clone()),
+        };
+
+        info.stop().await.unwrap();
+
+        let args: Vec<_> = fake.invocations().into_iter().map(|i| i.args).collect();
+        assert_eq!(
+            args,
+            [
+                vec!["stop", "test-enterprise"],
+                vec!["rm", "--force", "--volumes", "test-enterprise"],
+            ]
+        );
+    }
 }
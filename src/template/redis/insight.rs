@@ -35,6 +35,7 @@ impl RedisInsightTemplate {
             memory_limit: None,
             cpu_limit: None,
             platform: None,
+            executor: crate::CommandExecutor::default(),
         };
 
         Self { config }
@@ -12,8 +12,8 @@
 #![allow(clippy::unnecessary_get_then_check)]
 
 use super::common::{DEFAULT_REDIS_IMAGE, DEFAULT_REDIS_TAG};
-use crate::template::{Template, TemplateConfig, TemplateError};
-use crate::{DockerCommand, NetworkCreateCommand, RunCommand};
+use crate::template::{use_executor, Template, TemplateConfig, TemplateError};
+use crate::{CommandExecutor, DockerCommand, NetworkCreateCommand, RunCommand};
 use async_trait::async_trait;
 
 /// Redis Sentinel template for high availability setup
@@ -40,6 +40,8 @@ pub struct RedisSentinelTemplate {
     redis_tag: Option<String>,
     /// Platform for containers
     platform: Option<String>,
+    /// Executor used for every Docker command the template issues
+    executor: CommandExecutor,
 }
 
 impl RedisSentinelTemplate {
@@ -64,6 +66,7 @@ impl RedisSentinelTemplate {
             redis_image: None,
             redis_tag: None,
             platform: None,
+            executor: CommandExecutor::default(),
         }
     }
 
@@ -219,7 +222,7 @@ impl RedisSentinelTemplate {
 
         // Create network if not provided
         if self.network.is_none() {
-            NetworkCreateCommand::new(&network_name)
+            use_executor(&self.executor, NetworkCreateCommand::new(&network_name))
                 .execute()
                 .await
                 .map_err(|e| crate::Error::Custom {
@@ -280,13 +283,16 @@ impl RedisSentinelTemplate {
                 sentinel_config.clone()
             };
 
-            let mut sentinel_cmd = Self::build_sentinel_command(
-                &sentinel_name,
-                sentinel_port,
-                &sentinel_config,
-                self.redis_image.as_deref(),
-                self.redis_tag.as_deref(),
-                self.platform.as_deref(),
+            let mut sentinel_cmd = use_executor(
+                &self.command_executor(),
+                Self::build_sentinel_command(
+                    &sentinel_name,
+                    sentinel_port,
+                    &sentinel_config,
+                    self.redis_image.as_deref(),
+                    self.redis_tag.as_deref(),
+                    self.platform.as_deref(),
+                ),
             );
             sentinel_cmd = sentinel_cmd.network(&network_name);
 
@@ -318,6 +324,7 @@ impl RedisSentinelTemplate {
                 })
                 .collect(),
             password: self.password.clone(),
+            executor: self.command_executor(),
             network: network_name,
             containers: {
                 let mut containers = vec![master_name];
@@ -353,7 +360,10 @@ impl RedisSentinelTemplate {
             format!("{DEFAULT_REDIS_IMAGE}:{DEFAULT_REDIS_TAG}")
         };
 
-        let mut cmd = RunCommand::new(image).name(name).port(port, 6379).detach();
+        let mut cmd = use_executor(&self.command_executor(), RunCommand::new(image))
+            .name(name)
+            .port(port, 6379)
+            .detach();
 
         // Add platform if specified
         if let Some(ref platform) = self.platform {
@@ -497,6 +507,15 @@ impl RedisSentinelTemplate {
         names
     }
 
+    /// The executor for the template's commands, masking the password
+    /// wherever it is passed as a plain argument.
+    fn command_executor(&self) -> CommandExecutor {
+        match self.password {
+            Some(ref password) => self.executor.clone().secret(password.clone()),
+            None => self.executor.clone(),
+        }
+    }
+
     /// The `redis-cli` PING arguments used for readiness checks.
     fn build_ping_args(&self) -> Vec<String> {
         let mut args = vec!["redis-cli".to_string()];
@@ -533,10 +552,13 @@ impl RedisSentinelTemplate {
         loop {
             let mut still_pending = Vec::new();
             for name in &pending {
-                let ready = ExecCommand::new(name, ping_args.clone())
-                    .execute()
-                    .await
-                    .is_ok_and(|output| output.stdout.trim().eq_ignore_ascii_case("PONG"));
+                let ready = use_executor(
+                    &self.command_executor(),
+                    ExecCommand::new(name, ping_args.clone()),
+                )
+                .execute()
+                .await
+                .is_ok_and(|output| output.stdout.trim().eq_ignore_ascii_case("PONG"));
 
                 if !ready {
                     still_pending.push(name.clone());
@@ -577,6 +599,11 @@ impl Template for RedisSentinelTemplate {
         unimplemented!("RedisSentinelTemplate manages multiple containers")
     }
 
+    fn with_executor(mut self, executor: CommandExecutor) -> Self {
+        self.executor = executor;
+        self
+    }
+
     async fn start(&self) -> Result<String, TemplateError> {
         let info = self.start_topology().await?;
         Ok(format!(
@@ -602,7 +629,7 @@ impl Template for RedisSentinelTemplate {
 
         // Report on the master container, which represents the topology.
         let master = format!("{}-master", self.name);
-        let output = PsCommand::new()
+        let output = use_executor(&self.command_executor(), PsCommand::new())
             .filter(format!("name={master}"))
             .quiet()
             .execute()
@@ -620,7 +647,9 @@ impl Template for RedisSentinelTemplate {
         use crate::StopCommand;
 
         for name in self.container_names() {
-            let _ = StopCommand::new(&name).execute().await;
+            let _ = use_executor(&self.command_executor(), StopCommand::new(&name))
+                .execute()
+                .await;
         }
 
         Ok(())
@@ -630,13 +659,22 @@ impl Template for RedisSentinelTemplate {
         use crate::{NetworkRmCommand, RmCommand};
 
         for name in self.container_names() {
-            let _ = RmCommand::new(&name).force().volumes().execute().await;
+            let _ = use_executor(&self.command_executor(), RmCommand::new(&name))
+                .force()
+                .volumes()
+                .execute()
+                .await;
         }
 
         // Remove the network only if it was created by the template.
         if self.network.is_none() {
             let network_name = format!("{}-network", self.name);
-            let _ = NetworkRmCommand::new(&network_name).execute().await;
+            let _ = use_executor(
+                &self.command_executor(),
+                NetworkRmCommand::new(&network_name),
+            )
+            .execute()
+            .await;
         }
 
         Ok(())
@@ -663,6 +701,8 @@ pub struct SentinelConnectionInfo {
     pub network: String,
     /// Names of all containers in the cluster
     pub containers: Vec<String>,
+    /// Executor used to stop the cluster
+    pub executor: CommandExecutor,
 }
 
 /// Information about a Sentinel instance
@@ -706,14 +746,14 @@ impl SentinelConnectionInfo {
 
         // Stop and remove all containers
         for container in &self.containers {
-            StopCommand::new(container)
+            use_executor(&self.executor, StopCommand::new(container))
                 .execute()
                 .await
                 .map_err(|e| crate::Error::Custom {
                     message: format!("Failed to stop {container}: {e}"),
                 })?;
 
-            RmCommand::new(container)
+            use_executor(&self.executor, RmCommand::new(container))
                 .force()
                 .volumes()
                 .execute()
@@ -725,7 +765,7 @@ impl SentinelConnectionInfo {
 
         // Remove network if it was created by us
         if self.network.starts_with(&self.name) {
-            NetworkRmCommand::new(&self.network)
+            use_executor(&self.executor, NetworkRmCommand::new(&self.network))
                 .execute()
                 .await
                 .map_err(|e| crate::Error::Custom {
@@ -885,4 +925,35 @@ mod tests {
         let template = RedisSentinelTemplate::new("test-sentinel");
         assert_eq!(Template::name(&template), "test-sentinel");
     }
+
+    #[tokio::test]
+    async fn test_connection_info_stop_uses_executor() {
+        use crate::runner::{ArgMatcher, FakeResponse, FakeRunner};
+
+        let fake = FakeRunner::new().on(ArgMatcher::Any, FakeResponse::success(""));
+        let info = SentinelConnectionInfo {
+            name: "test-sentinel".to_string(),
+            master_name: "mymaster".to_string(),
+            master_host: "localhost".to_string(),
+            master_port: 6379,
+            replica_ports: vec![],
+            sentinels: vec![],
+            password: None,
+            network: "test-sentinel-network".to_string(),
+            containers: vec!["test-sentinel-master".to_string()],
+            executor: CommandExecutor::new().runner(fake.clone()),
+        };
+
+        info.stop().await.unwrap();
+
+        let args: Vec<_> = fake.invocations().into_iter().map(|i| i.args).collect();
+        assert_eq!(
+            args,
+            [
+                vec!["stop", "test-sentinel-master"],
+                vec!["rm", "--force", "--volumes", "test-sentinel-master"],
+                vec!["network", "rm", "test-sentinel-network"],
+            ]
+        );
+    }
 }
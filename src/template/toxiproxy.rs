@@ -300,6 +300,7 @@ impl ToxiproxyTemplate {
             memory_limit: None,
             cpu_limit: None,
             platform: None,
+            executor: crate::CommandExecutor::default(),
         };
 
         Self {
@@ -595,6 +595,18 @@ impl<T: Template> ContainerGuardBuilder<T> {
     }
 }
 
+#[cfg(feature = "blocking")]
+impl<T: Template> ContainerGuardBuilder<T> {
+    /// Blocking version of [`start`](Self::start).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the container fails to start or the readiness check times out.
+    pub fn start_blocking(self) -> Result<ContainerGuard<T>, TemplateError> {
+        crate::blocking::block_on(self.start())
+    }
+}
+
 /// RAII guard for automatic container lifecycle management.
 ///
 /// When this guard is dropped, the container is automatically stopped and
@@ -930,6 +942,81 @@ impl<T: Template> ContainerGuard<T> {
     }
 }
 
+/// Blocking versions of the lifecycle methods, for synchronous tests.
+///
+/// The guard still cleans up on drop, with or without an async runtime.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use docker_wrapper::testing::ContainerGuard;
+/// # use docker_wrapper::RedisTemplate;
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let guard = ContainerGuard::new(RedisTemplate::new("redis"))
+///     .wait_for_ready(true)
+///     .start_blocking()?;
+///
+/// assert!(guard.is_running_blocking()?);
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "blocking")]
+impl<T: Template> ContainerGuard<T> {
+    /// Blocking version of [`is_running`](Self::is_running).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Docker command fails.
+    pub fn is_running_blocking(&self) -> Result<bool, TemplateError> {
+        crate::blocking::block_on(self.is_running())
+    }
+
+    /// Blocking version of [`wait_for_ready`](Self::wait_for_ready).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the readiness check fails or times out.
+    pub fn wait_for_ready_blocking(&self) -> Result<(), TemplateError> {
+        crate::blocking::block_on(self.wait_for_ready())
+    }
+
+    /// Blocking version of [`host_port`](Self::host_port).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the container port is not published.
+    pub fn host_port_blocking(&self, container_port: u16) -> Result<u16, TemplateError> {
+        crate::blocking::block_on(self.host_port(container_port))
+    }
+
+    /// Blocking version of [`logs`](Self::logs).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Docker command fails.
+    pub fn logs_blocking(&self) -> Result<String, TemplateError> {
+        crate::blocking::block_on(self.logs())
+    }
+
+    /// Blocking version of [`stop`](Self::stop).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Docker command fails.
+    pub fn stop_blocking(&self) -> Result<(), TemplateError> {
+        crate::blocking::block_on(self.stop())
+    }
+
+    /// Blocking version of [`cleanup`](Self::cleanup).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Docker commands fail.
+    pub fn cleanup_blocking(&self) -> Result<(), TemplateError> {
+        crate::blocking::block_on(self.cleanup())
+    }
+}
+
 impl<T: Template + HasConnectionString> ContainerGuard<T> {
     /// Get the connection string for the underlying service.
     ///
@@ -1357,6 +1444,18 @@ impl ContainerGuardSetBuilder {
     }
 }
 
+#[cfg(feature = "blocking")]
+impl ContainerGuardSetBuilder {
+    /// Blocking version of [`start_all`](Self::start_all).
+    ///
+    /// # Errors
+    ///
+    /// Same as [`start_all`](Self::start_all).
+    pub fn start_all_blocking(self) -> Result<ContainerGuardSet, TemplateError> {
+        crate::blocking::block_on(self.start_all())
+    }
+}
+
 impl Default for ContainerGuardSetBuilder {
     fn default() -> Self {
         Self::new()